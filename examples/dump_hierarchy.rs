@@ -5,7 +5,7 @@
 //! Usage: cargo run --example dump_hierarchy -- <path/to/file.usdz>
 
 use std::env;
-use tinyusdz_rs::Stage;
+use tinyusdz_rs::{Stage, TraversalOptions};
 
 fn main() {
     let args: Vec<String> = env::args().collect();
@@ -33,11 +33,10 @@ fn main() {
     println!("================");
 
     // Traverse all prims and print with indentation
-    // Note: This simplified version doesn't track actual hierarchy depth
-    // A full implementation would need to track parent-child relationships
-    for prim in stage.traverse() {
+    let mut prims = stage.traverse_with(TraversalOptions::new());
+    while let Some(prim) = prims.next() {
+        let indent = "  ".repeat(prims.depth());
         let type_name = prim.type_name();
-        let name = prim.name();
         let num_children = prim.num_children();
         let num_props = prim.property_names().len();
 
@@ -49,8 +48,12 @@ fn main() {
         };
 
         println!(
-            "/{} <{}>  [{} children, {} properties]",
-            name, type_str, num_children, num_props
+            "{}{} <{}>  [{} children, {} properties]",
+            indent,
+            prims.path(),
+            type_str,
+            num_children,
+            num_props
         );
 
         // Print properties
@@ -58,10 +61,10 @@ fn main() {
         if !props.is_empty() {
             for prop in props.iter().take(10) {
                 // Limit to first 10 properties
-                println!("    .{}", prop);
+                println!("{}    .{}", indent, prop);
             }
             if props.len() > 10 {
-                println!("    ... and {} more properties", props.len() - 10);
+                println!("{}    ... and {} more properties", indent, props.len() - 10);
             }
         }
     }
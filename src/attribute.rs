@@ -1,31 +1,119 @@
 //! USD Attribute handling.
-//!
-//! Note: Attribute extraction is limited in the current C API version.
-//! Property/attribute access functions are not yet fully implemented in tinyusdz.
 
+use crate::mesh::Interpolation;
+use crate::usda::{find_meta, to_value, PropertySpec};
 use crate::value::Value;
 
 /// A USD Attribute holds a value on a prim.
 ///
-/// Note: Due to limitations in the current C API, attribute value extraction
-/// is not yet fully supported. This struct serves as a placeholder for
-/// future functionality.
-#[derive(Debug)]
+/// An attribute can have a default value, time samples, or both.
+#[derive(Debug, Clone)]
 pub struct Attribute {
-    // Placeholder - the C API doesn't yet support property extraction
-    _private: (),
+    name: String,
+    type_name: String,
+    uniform: bool,
+    custom: bool,
+    value: Value,
+    time_samples: Vec<(f64, Value)>,
+    connections: Vec<String>,
+    metadata: Vec<(String, Value)>,
 }
 
 impl Attribute {
-    /// Returns the value of this attribute.
+    /// Creates an attribute from a parsed property spec.
+    pub(crate) fn from_spec(spec: &PropertySpec) -> Self {
+        Attribute {
+            name: spec.name.clone(),
+            type_name: spec.type_name.clone(),
+            uniform: spec.uniform,
+            custom: spec.custom,
+            value: spec
+                .default
+                .as_ref()
+                .map(|node| to_value(&spec.type_name, node))
+                .unwrap_or_default(),
+            time_samples: spec
+                .time_samples
+                .iter()
+                .map(|(t, node)| (*t, to_value(&spec.type_name, node)))
+                .collect(),
+            connections: spec.targets.clone(),
+            metadata: spec
+                .metadata
+                .iter()
+                .map(|m| (m.key.clone(), m.value.to_untyped_value()))
+                .collect(),
+        }
+    }
+
+    /// Returns the name of this attribute (e.g., "points", "primvars:st").
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the USD type name of this attribute (e.g., "point3f[]").
+    pub fn type_name(&self) -> &str {
+        &self.type_name
+    }
+
+    /// Returns the default value of this attribute.
     ///
-    /// Note: Currently returns Value::None due to C API limitations.
+    /// Returns [`Value::None`] if the attribute has no default value.
     pub fn value(&self) -> Value {
-        Value::None
+        self.value.clone()
     }
 
     /// Returns true if this attribute is an array type.
     pub fn is_array(&self) -> bool {
-        false
+        self.type_name.ends_with("[]")
+    }
+
+    /// Returns true if this attribute is declared `uniform`.
+    pub fn is_uniform(&self) -> bool {
+        self.uniform
+    }
+
+    /// Returns true if this attribute is declared `custom`.
+    pub fn is_custom(&self) -> bool {
+        self.custom
+    }
+
+    /// Returns the time samples of this attribute, ordered by time.
+    pub fn time_samples(&self) -> &[(f64, Value)] {
+        &self.time_samples
     }
+
+    /// Returns true if this attribute has time samples.
+    pub fn is_time_sampled(&self) -> bool {
+        !self.time_samples.is_empty()
+    }
+
+    /// Returns the paths this attribute is connected to.
+    pub fn connections(&self) -> &[String] {
+        &self.connections
+    }
+
+    /// Returns an attribute metadata value by key (e.g. "interpolation").
+    pub fn metadata(&self, key: &str) -> Option<&Value> {
+        self.metadata.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Returns the primvar interpolation, if authored.
+    pub fn interpolation(&self) -> Option<Interpolation> {
+        self.metadata("interpolation")
+            .and_then(Value::as_str)
+            .and_then(Interpolation::from_token)
+    }
+
+    /// Returns the primvar element size, if authored.
+    pub fn element_size(&self) -> Option<i32> {
+        self.metadata("elementSize").and_then(Value::as_int)
+    }
+}
+
+/// Reads the `interpolation` metadata of a property spec.
+pub(crate) fn spec_interpolation(spec: &PropertySpec) -> Option<Interpolation> {
+    find_meta(&spec.metadata, "interpolation")
+        .and_then(|node| node.as_str())
+        .and_then(Interpolation::from_token)
 }
@@ -0,0 +1,130 @@
+//! Axis-aligned bounding boxes.
+
+use crate::xform::transform_point;
+
+/// An axis-aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingBox {
+    /// Minimum corner.
+    pub min: [f64; 3],
+    /// Maximum corner.
+    pub max: [f64; 3],
+}
+
+impl BoundingBox {
+    /// Creates an empty bounding box that contains no points.
+    pub fn empty() -> Self {
+        BoundingBox {
+            min: [f64::INFINITY; 3],
+            max: [f64::NEG_INFINITY; 3],
+        }
+    }
+
+    /// Creates a bounding box from its corners.
+    pub fn new(min: [f64; 3], max: [f64; 3]) -> Self {
+        BoundingBox { min, max }
+    }
+
+    /// Creates the smallest bounding box containing all `points`.
+    pub fn from_points(points: &[[f32; 3]]) -> Self {
+        let mut bounds = Self::empty();
+        for p in points {
+            bounds.extend_point([p[0] as f64, p[1] as f64, p[2] as f64]);
+        }
+        bounds
+    }
+
+    /// Returns true if the box contains no points.
+    pub fn is_empty(&self) -> bool {
+        (0..3).any(|i| self.min[i] > self.max[i])
+    }
+
+    /// Grows the box to contain `p`.
+    pub fn extend_point(&mut self, p: [f64; 3]) {
+        for (i, &c) in p.iter().enumerate() {
+            self.min[i] = self.min[i].min(c);
+            self.max[i] = self.max[i].max(c);
+        }
+    }
+
+    /// Grows the box to contain `other`.
+    pub fn union(&mut self, other: &BoundingBox) {
+        if !other.is_empty() {
+            self.extend_point(other.min);
+            self.extend_point(other.max);
+        }
+    }
+
+    /// Returns the box enclosing this box after transforming it by `m`.
+    pub fn transformed(&self, m: &[[f64; 4]; 4]) -> BoundingBox {
+        let mut result = BoundingBox::empty();
+        if self.is_empty() {
+            return result;
+        }
+        for corner in 0..8 {
+            let pick = |axis: usize| {
+                if corner & (1 << axis) == 0 {
+                    self.min[axis]
+                } else {
+                    self.max[axis]
+                }
+            };
+            result.extend_point(transform_point(m, [pick(0), pick(1), pick(2)]));
+        }
+        result
+    }
+
+    /// Returns the center of the box.
+    pub fn center(&self) -> [f64; 3] {
+        [0, 1, 2].map(|i| (self.min[i] + self.max[i]) * 0.5)
+    }
+
+    /// Returns the size of the box along each axis.
+    pub fn size(&self) -> [f64; 3] {
+        if self.is_empty() {
+            return [0.0; 3];
+        }
+        [0, 1, 2].map(|i| self.max[i] - self.min[i])
+    }
+}
+
+impl Default for BoundingBox {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_union_and_transform() {
+        let mut bounds = BoundingBox::empty();
+        assert!(bounds.is_empty());
+        assert_eq!(bounds.size(), [0.0; 3]);
+
+        // Empty boxes do not grow the union.
+        bounds.union(&BoundingBox::empty());
+        assert!(bounds.is_empty());
+        bounds.union(&BoundingBox::from_points(&[
+            [0.0, 0.0, 0.0],
+            [1.0, 2.0, 3.0],
+        ]));
+        assert_eq!(bounds.center(), [0.5, 1.0, 1.5]);
+
+        // Scale x by 2, then translate by (1, 0, 0), in row-vector form.
+        let m = [
+            [2.0, 0.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [1.0, 0.0, 0.0, 1.0],
+        ];
+        let transformed = bounds.transformed(&m);
+        assert_eq!(
+            transformed,
+            BoundingBox::new([1.0, 0.0, 0.0], [3.0, 2.0, 3.0])
+        );
+        assert!(BoundingBox::empty().transformed(&m).is_empty());
+    }
+}
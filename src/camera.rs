@@ -0,0 +1,286 @@
+//! USD Camera extraction.
+//!
+//! Lens and aperture values are in tenths of a scene unit, so with
+//! centimeter scene units they are millimeters.
+
+use crate::stage::Stage;
+use crate::usda::PrimSpec;
+use crate::value::Value;
+use crate::xform;
+
+/// Camera projection type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Projection {
+    /// Perspective projection (USD default).
+    #[default]
+    Perspective,
+    /// Orthographic projection.
+    Orthographic,
+}
+
+impl Projection {
+    /// Parses a USD projection token (e.g. "orthographic").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "perspective" => Some(Projection::Perspective),
+            "orthographic" => Some(Projection::Orthographic),
+            _ => None,
+        }
+    }
+}
+
+/// A camera extracted from a USD Camera prim.
+///
+/// The camera looks down its local -Z axis with +Y up.
+#[derive(Debug, Clone)]
+pub struct Camera {
+    /// The name of the camera.
+    pub name: String,
+    /// Projection type.
+    pub projection: Projection,
+    /// Focal length.
+    pub focal_length: f32,
+    /// Horizontal aperture (film width).
+    pub horizontal_aperture: f32,
+    /// Vertical aperture (film height).
+    pub vertical_aperture: f32,
+    /// Horizontal aperture offset.
+    pub horizontal_aperture_offset: f32,
+    /// Vertical aperture offset.
+    pub vertical_aperture_offset: f32,
+    /// Near and far clipping distances in scene units.
+    pub clipping_range: [f32; 2],
+    /// Distance to the focus plane in scene units.
+    pub focus_distance: f32,
+    /// Lens aperture as an f-number; 0 disables depth of field.
+    pub f_stop: f32,
+    /// World transformation matrix (4x4).
+    pub world_transform: [[f64; 4]; 4],
+}
+
+impl Camera {
+    /// Ratio of lens and aperture units to scene units for orthographic
+    /// cameras.
+    const APERTURE_UNIT: f64 = 0.1;
+
+    /// Creates a camera with the USD schema defaults.
+    pub fn new(name: impl Into<String>) -> Self {
+        Camera {
+            name: name.into(),
+            projection: Projection::Perspective,
+            focal_length: 50.0,
+            horizontal_aperture: 20.955,
+            vertical_aperture: 15.2908,
+            horizontal_aperture_offset: 0.0,
+            vertical_aperture_offset: 0.0,
+            clipping_range: [1.0, 1_000_000.0],
+            focus_distance: 0.0,
+            f_stop: 0.0,
+            world_transform: xform::identity(),
+        }
+    }
+
+    /// Returns the aperture aspect ratio (width / height).
+    pub fn aspect_ratio(&self) -> f32 {
+        if self.vertical_aperture == 0.0 {
+            return 1.0;
+        }
+        self.horizontal_aperture / self.vertical_aperture
+    }
+
+    /// Returns the vertical field of view in radians.
+    ///
+    /// Returns 0 for orthographic cameras.
+    pub fn vertical_fov(&self) -> f32 {
+        self.fov(self.vertical_aperture)
+    }
+
+    /// Returns the horizontal field of view in radians.
+    ///
+    /// Returns 0 for orthographic cameras.
+    pub fn horizontal_fov(&self) -> f32 {
+        self.fov(self.horizontal_aperture)
+    }
+
+    fn fov(&self, aperture: f32) -> f32 {
+        if self.projection == Projection::Orthographic || self.focal_length <= 0.0 {
+            return 0.0;
+        }
+        2.0 * (aperture * 0.5 / self.focal_length).atan()
+    }
+
+    /// Returns the view matrix (the inverse of the world transform).
+    pub fn view_matrix(&self) -> [[f64; 4]; 4] {
+        xform::invert(&self.world_transform).unwrap_or_else(xform::identity)
+    }
+
+    /// Returns the projection matrix, honouring aperture offsets.
+    ///
+    /// The matrix uses the USD row-vector convention and maps view space to
+    /// OpenGL clip space (depth in -1..1).
+    pub fn projection_matrix(&self) -> [[f64; 4]; 4] {
+        let near = self.clipping_range[0] as f64;
+        let far = self.clipping_range[1] as f64;
+
+        let window = |aperture: f32, offset: f32| {
+            let (aperture, offset) = (aperture as f64, offset as f64);
+            [offset - aperture * 0.5, offset + aperture * 0.5]
+        };
+        let [mut left, mut right] =
+            window(self.horizontal_aperture, self.horizontal_aperture_offset);
+        let [mut bottom, mut top] = window(self.vertical_aperture, self.vertical_aperture_offset);
+
+        let mut m = [[0.0; 4]; 4];
+        match self.projection {
+            Projection::Perspective => {
+                let scale = near / self.focal_length as f64;
+                left *= scale;
+                right *= scale;
+                bottom *= scale;
+                top *= scale;
+
+                m[0][0] = 2.0 * near / (right - left);
+                m[1][1] = 2.0 * near / (top - bottom);
+                m[2][0] = (right + left) / (right - left);
+                m[2][1] = (top + bottom) / (top - bottom);
+                m[2][2] = -(far + near) / (far - near);
+                m[2][3] = -1.0;
+                m[3][2] = -2.0 * far * near / (far - near);
+            }
+            Projection::Orthographic => {
+                left *= Self::APERTURE_UNIT;
+                right *= Self::APERTURE_UNIT;
+                bottom *= Self::APERTURE_UNIT;
+                top *= Self::APERTURE_UNIT;
+
+                m[0][0] = 2.0 / (right - left);
+                m[1][1] = 2.0 / (top - bottom);
+                m[2][2] = -2.0 / (far - near);
+                m[3][0] = -(right + left) / (right - left);
+                m[3][1] = -(top + bottom) / (top - bottom);
+                m[3][2] = -(far + near) / (far - near);
+                m[3][3] = 1.0;
+            }
+        }
+        m
+    }
+}
+
+/// Utility to extract cameras from a USD stage.
+pub struct CameraExtractor<'a> {
+    stage: &'a Stage,
+}
+
+impl<'a> CameraExtractor<'a> {
+    /// Creates a new camera extractor for the given stage.
+    pub fn new(stage: &'a Stage) -> Self {
+        CameraExtractor { stage }
+    }
+
+    /// Returns an iterator over all cameras in the stage.
+    pub fn cameras(&self) -> impl Iterator<Item = Camera> + '_ {
+        let mut cameras = Vec::new();
+        self.stage.walk_transforms(|visit, _, world| {
+            if visit.spec.type_name == "Camera" {
+                let mut camera = Self::extract_camera(visit.spec);
+                camera.world_transform = *world;
+                cameras.push(camera);
+            }
+        });
+        cameras.into_iter()
+    }
+
+    /// Extracts camera data from a Camera prim's spec.
+    fn extract_camera(spec: &PrimSpec) -> Camera {
+        let mut camera = Camera::new(&spec.name);
+        let float = |name: &str| spec.value(name).as_ref().and_then(Value::as_f64);
+
+        if let Some(projection) = spec
+            .value("projection")
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(Projection::from_token)
+        {
+            camera.projection = projection;
+        }
+
+        let fields = [
+            ("focalLength", &mut camera.focal_length),
+            ("horizontalAperture", &mut camera.horizontal_aperture),
+            ("verticalAperture", &mut camera.vertical_aperture),
+            (
+                "horizontalApertureOffset",
+                &mut camera.horizontal_aperture_offset,
+            ),
+            (
+                "verticalApertureOffset",
+                &mut camera.vertical_aperture_offset,
+            ),
+            ("focusDistance", &mut camera.focus_distance),
+            ("fStop", &mut camera.f_stop),
+        ];
+        for (name, field) in fields {
+            if let Some(value) = float(name) {
+                *field = value as f32;
+            }
+        }
+
+        if let Some(range) = spec
+            .value("clippingRange")
+            .as_ref()
+            .and_then(Value::as_double2)
+        {
+            camera.clipping_range = range.map(|c| c as f32);
+        }
+
+        camera
+    }
+
+    /// Extracts all cameras and returns them as a vector.
+    pub fn collect(&self) -> Vec<Camera> {
+        self.cameras().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::parse_prim;
+
+    #[test]
+    fn test_extract_camera() {
+        let spec = parse_prim(
+            r#"def Camera "Cam"
+{
+    float2 clippingRange = (0.1, 100)
+    float focalLength = 35
+    float horizontalAperture = 36
+    float verticalAperture = 24
+    token projection = "perspective"
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let camera = CameraExtractor::extract_camera(&spec);
+        assert_eq!(camera.name, "Cam");
+        assert_eq!(camera.clipping_range, [0.1, 100.0]);
+        assert_eq!(camera.aspect_ratio(), 1.5);
+
+        let fov = camera.vertical_fov();
+        assert!((fov - 2.0 * (12.0f32 / 35.0).atan()).abs() < 1e-6);
+
+        // The near plane maps to -1 and the far plane to +1 in clip space.
+        let m = camera.projection_matrix();
+        let depth = |z: f64| {
+            let p = xform::transform_point(&m, [0.0, 0.0, -z]);
+            p[2]
+        };
+        assert!((depth(0.1) + 1.0).abs() < 1e-6);
+        assert!((depth(100.0) - 1.0).abs() < 1e-6);
+
+        // The top of the aperture maps to the top of clip space.
+        let top = xform::transform_point(&m, [0.0, 12.0 / 35.0, -1.0]);
+        assert!((top[1] - 1.0).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,398 @@
+//! UsdCollectionAPI support.
+//!
+//! A collection names a set of prims (and optionally properties) with
+//! `collection:<name>:includes` and `collection:<name>:excludes`
+//! relationships. Collections are used for light linking and for collection
+//! material bindings.
+
+use std::collections::HashSet;
+
+use crate::prim;
+use crate::stage::{is_within, Stage};
+use crate::traversal::TraversalOptions;
+use crate::usda::PrimSpec;
+
+/// How the targets of a collection's `includes` are expanded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ExpansionRule {
+    /// Only the targeted paths are members.
+    ExplicitOnly,
+    /// The targeted prims and all their descendants are members.
+    #[default]
+    ExpandPrims,
+    /// Like `ExpandPrims`, plus the properties of every member prim.
+    ExpandPrimsAndProperties,
+}
+
+impl ExpansionRule {
+    /// Parses a USD expansion rule token (e.g. "explicitOnly").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "explicitOnly" => Some(ExpansionRule::ExplicitOnly),
+            "expandPrims" => Some(ExpansionRule::ExpandPrims),
+            "expandPrimsAndProperties" => Some(ExpansionRule::ExpandPrimsAndProperties),
+            _ => None,
+        }
+    }
+}
+
+/// A collection authored on a prim with the CollectionAPI schema.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::Stage;
+///
+/// let stage = Stage::open("scene.usda").unwrap();
+/// for prim in stage.find("/World/Lights/*").unwrap() {
+///     for collection in prim.collections() {
+///         if collection.name == "lightLink" {
+///             println!("{} lights {:?}", prim.name(), collection.compute_members(&stage));
+///         }
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collection {
+    /// The collection name (e.g. "lightLink").
+    pub name: String,
+    /// How included prims are expanded.
+    pub expansion_rule: ExpansionRule,
+    /// True if the pseudo-root, and so the whole stage, is included.
+    pub include_root: bool,
+    /// Included prim, property and collection paths.
+    pub includes: Vec<String>,
+    /// Excluded prim and property paths.
+    pub excludes: Vec<String>,
+}
+
+impl Collection {
+    /// Computes the paths of the collection's members, in depth-first stage
+    /// order.
+    ///
+    /// With `explicitOnly`, members are exactly the included paths that are
+    /// not excluded. Otherwise a path is a member if its nearest included or
+    /// excluded ancestor (or itself) is included. Included collections
+    /// (`</Prim.collection:name>`) contribute their own members.
+    pub fn compute_members(&self, stage: &Stage) -> Vec<String> {
+        let mut visited = HashSet::new();
+        self.members(stage, &mut visited)
+    }
+
+    fn members(&self, stage: &Stage, visited: &mut HashSet<String>) -> Vec<String> {
+        let mut includes = Vec::new();
+        let mut nested = Vec::new();
+        for path in &self.includes {
+            match path.split_once(".collection:") {
+                Some((owner, name)) => {
+                    if visited.insert(path.clone()) {
+                        nested.extend(included_collection(stage, owner, name, visited));
+                    }
+                }
+                None => includes.push(path.as_str()),
+            }
+        }
+        if self.include_root {
+            includes.push("/");
+        }
+
+        let mut members = Vec::new();
+        let mut prims = stage.traverse_with(TraversalOptions::new());
+        while let Some(prim) = prims.next() {
+            let path = prims.path().to_string();
+            // Prune subtrees that neither contain nor lie below an include.
+            if !includes
+                .iter()
+                .any(|include| contains(&path, include) || contains(include, &path))
+            {
+                prims.skip_children();
+                continue;
+            }
+            if !self.is_member(&includes, &path) {
+                continue;
+            }
+            if self.expansion_rule == ExpansionRule::ExpandPrimsAndProperties {
+                for name in prim.property_names() {
+                    let property = format!("{}.{}", path, name);
+                    if !self.excludes.contains(&property) {
+                        members.push(property);
+                    }
+                }
+            }
+            members.push(path);
+        }
+
+        // Explicitly included properties.
+        for include in &includes {
+            if include.contains('.') && !self.excludes.iter().any(|e| e == include) {
+                members.push(include.to_string());
+            }
+        }
+        for path in nested {
+            if !members.contains(&path) && !self.is_excluded(&path) {
+                members.push(path);
+            }
+        }
+        members
+    }
+
+    fn is_member(&self, includes: &[&str], path: &str) -> bool {
+        if self.expansion_rule == ExpansionRule::ExplicitOnly {
+            return includes.contains(&path) && !self.excludes.iter().any(|e| e == path);
+        }
+        // The most specific authored path decides; excludes win ties.
+        let included = nearest(path, includes.iter().copied());
+        let excluded = nearest(path, self.excludes.iter().map(String::as_str));
+        match (included, excluded) {
+            (Some(i), Some(e)) => i > e,
+            (included, _) => included.is_some(),
+        }
+    }
+
+    fn is_excluded(&self, path: &str) -> bool {
+        if self.excludes.iter().any(|e| e == path) {
+            return true;
+        }
+        // Excluding a prim also excludes its properties and descendants.
+        let prim_path = path.split('.').next().unwrap_or(path);
+        self.expansion_rule != ExpansionRule::ExplicitOnly
+            && self.excludes.iter().any(|e| contains(prim_path, e))
+    }
+}
+
+/// Returns true if `path` is `root` or below it; "/" contains every path.
+fn contains(path: &str, root: &str) -> bool {
+    root == "/" || is_within(path, root)
+}
+
+/// Returns the length of the longest of `paths` containing `path`.
+fn nearest<'p>(path: &str, paths: impl Iterator<Item = &'p str>) -> Option<usize> {
+    paths.filter(|p| contains(path, p)).map(str::len).max()
+}
+
+/// Computes the members of the collection `name` on the prim at `owner`.
+fn included_collection(
+    stage: &Stage,
+    owner: &str,
+    name: &str,
+    visited: &mut HashSet<String>,
+) -> Vec<String> {
+    let collection = stage
+        .find(owner)
+        .ok()
+        .and_then(|mut prims| prims.next())
+        .and_then(|prim| prim.collections().into_iter().find(|c| c.name == name));
+    match collection {
+        Some(collection) => collection.members(stage, visited),
+        None => Vec::new(),
+    }
+}
+
+/// Returns the collections authored on a prim spec, in authored order.
+///
+/// Collections are found from `CollectionAPI:<name>` applied schemas and
+/// from `collection:<name>:*` properties.
+pub(crate) fn collections(spec: &PrimSpec) -> Vec<Collection> {
+    let mut names: Vec<String> = prim::applied_schemas(spec)
+        .iter()
+        .filter_map(|schema| schema.strip_prefix("CollectionAPI:"))
+        .map(String::from)
+        .collect();
+    for property in &spec.properties {
+        if let Some((name, _)) = property
+            .name
+            .strip_prefix("collection:")
+            .and_then(|rest| rest.rsplit_once(':'))
+        {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    names
+        .into_iter()
+        .map(|name| {
+            let property = |suffix: &str| format!("collection:{}:{}", name, suffix);
+            let targets = |suffix: &str| {
+                spec.property(&property(suffix))
+                    .map(|p| p.targets.clone())
+                    .unwrap_or_default()
+            };
+            // UsdLux link collections include the whole stage by default.
+            let include_root_fallback = matches!(name.as_str(), "lightLink" | "shadowLink");
+            Collection {
+                expansion_rule: spec
+                    .value(&property("expansionRule"))
+                    .and_then(|v| v.as_str().and_then(ExpansionRule::from_token))
+                    .unwrap_or_default(),
+                include_root: spec
+                    .value(&property("includeRoot"))
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(include_root_fallback),
+                includes: targets("includes"),
+                excludes: targets("excludes"),
+                name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda;
+
+    #[test]
+    fn test_collections() {
+        let spec = usda::parse_prim(
+            r#"def SphereLight "Key" (
+    prepend apiSchemas = ["CollectionAPI:lightLink", "CollectionAPI:props"]
+)
+{
+    uniform token collection:props:expansionRule = "explicitOnly"
+    rel collection:props:includes = [</World/Chair>, </World/Table>]
+    uniform bool collection:lightLink:includeRoot = 0
+    rel collection:lightLink:includes = </World>
+    rel collection:lightLink:excludes = </World/Table>
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let collections = collections(&spec);
+        assert_eq!(collections.len(), 2);
+        let link = &collections[0];
+        assert_eq!(link.name, "lightLink");
+        assert_eq!(link.expansion_rule, ExpansionRule::ExpandPrims);
+        assert!(!link.include_root);
+        assert!(link.is_member(&["/World"], "/World/Chair/Seat"));
+        assert!(!link.is_member(&["/World"], "/World/Table/Leg"));
+        assert!(!link.is_member(&["/World"], "/Other"));
+
+        let props = &collections[1];
+        assert_eq!(props.expansion_rule, ExpansionRule::ExplicitOnly);
+        assert_eq!(props.includes, vec!["/World/Chair", "/World/Table"]);
+        assert!(props.is_member(&["/World/Chair"], "/World/Chair"));
+        assert!(!props.is_member(&["/World/Chair"], "/World/Chair/Seat"));
+    }
+
+    const LINKS: &str = r#"#usda 1.0
+
+def Xform "World"
+{
+    def Xform "Chair"
+    {
+        def Mesh "Seat"
+        {
+            double3 xformOp:translate = (0, 1, 0)
+            uniform token[] xformOpOrder = ["xformOp:translate"]
+        }
+    }
+
+    def Xform "Table"
+    {
+        def Mesh "Leg"
+        {
+        }
+    }
+}
+
+def Xform "Lights"
+{
+    def SphereLight "Key" (
+        prepend apiSchemas = ["CollectionAPI:lightLink", "CollectionAPI:shadowLink"]
+    )
+    {
+        rel collection:shadowLink:excludes = </World/Table>
+    }
+
+    def SphereLight "Fill" (
+        prepend apiSchemas = ["CollectionAPI:lightLink", "CollectionAPI:props", "CollectionAPI:chair"]
+    )
+    {
+        uniform bool collection:lightLink:includeRoot = 0
+        rel collection:lightLink:includes = [</World>, </World/Table/Leg>]
+        rel collection:lightLink:excludes = </World/Table>
+        uniform token collection:props:expansionRule = "explicitOnly"
+        rel collection:props:includes = [</World/Chair>, </World/Table>, </World/Table/Leg>]
+        rel collection:props:excludes = </World/Table>
+        uniform token collection:chair:expansionRule = "expandPrimsAndProperties"
+        rel collection:chair:includes = </World/Chair>
+        rel collection:chair:excludes = </World/Chair/Seat.xformOpOrder>
+    }
+
+    def SphereLight "Rim" (
+        prepend apiSchemas = ["CollectionAPI:lightLink"]
+    )
+    {
+        uniform bool collection:lightLink:includeRoot = 0
+        rel collection:lightLink:includes = [</Lights/Fill.collection:props>, </World/Chair/Seat>]
+    }
+}
+"#;
+
+    fn collection(stage: &Stage, light: &str, name: &str) -> Collection {
+        let prim = stage.find(light).unwrap().next().unwrap();
+        prim.collections()
+            .into_iter()
+            .find(|c| c.name == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compute_members() {
+        let stage = Stage::from_usda(LINKS.as_bytes()).unwrap();
+        let members =
+            |light: &str, name: &str| collection(&stage, light, name).compute_members(&stage);
+
+        // lightLink and shadowLink include the whole stage by default.
+        let key = collection(&stage, "/Lights/Key", "lightLink");
+        assert!(key.include_root);
+        assert_eq!(members("/Lights/Key", "lightLink").len(), 9);
+        assert_eq!(
+            members("/Lights/Key", "shadowLink"),
+            [
+                "/World",
+                "/World/Chair",
+                "/World/Chair/Seat",
+                "/Lights",
+                "/Lights/Key",
+                "/Lights/Fill",
+                "/Lights/Rim",
+            ]
+        );
+
+        // expandPrims: the nearest authored ancestor decides.
+        assert_eq!(
+            members("/Lights/Fill", "lightLink"),
+            [
+                "/World",
+                "/World/Chair",
+                "/World/Chair/Seat",
+                "/World/Table/Leg",
+            ]
+        );
+
+        // explicitOnly: no descendants, and excludes remove exact paths.
+        assert_eq!(
+            members("/Lights/Fill", "props"),
+            ["/World/Chair", "/World/Table/Leg"]
+        );
+
+        // expandPrimsAndProperties: properties precede their prim.
+        assert_eq!(
+            members("/Lights/Fill", "chair"),
+            [
+                "/World/Chair",
+                "/World/Chair/Seat.xformOp:translate",
+                "/World/Chair/Seat",
+            ]
+        );
+
+        // Included collections contribute their members.
+        assert_eq!(
+            members("/Lights/Rim", "lightLink"),
+            ["/World/Chair/Seat", "/World/Chair", "/World/Table/Leg"]
+        );
+    }
+}
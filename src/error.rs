@@ -41,6 +41,22 @@ pub enum Error {
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    /// USDA text could not be parsed.
+    #[error("Failed to parse USDA: {0}")]
+    ParseError(String),
+
+    /// Mesh topology is malformed.
+    #[error("Invalid mesh: {0}")]
+    InvalidMesh(String),
+
+    /// A stage could not be exported.
+    #[error("Export failed: {0}")]
+    ExportError(String),
+
+    /// A file could not be imported.
+    #[error("Import failed: {0}")]
+    ImportError(String),
+
     /// Index out of bounds.
     #[error("Index out of bounds: {index} >= {len}")]
     IndexOutOfBounds { index: usize, len: usize },
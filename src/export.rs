@@ -0,0 +1,6 @@
+//! Exporters to other scene formats.
+//!
+//! Each exporter is behind a cargo feature of the same name.
+
+#[cfg(feature = "gltf")]
+pub mod gltf;
@@ -0,0 +1,1191 @@
+//! glTF 2.0 export.
+//!
+//! A [`GltfExporter`] writes a stage as `.gltf` (JSON with an external
+//! buffer) or `.glb` (binary). The prim hierarchy becomes the node
+//! hierarchy, with each prim's local transform; meshes are split into one
+//! primitive per bound material, and materials come from
+//! [`MaterialExtractor`]. Instanced meshes are written once and placed by one
+//! node per instance.
+//!
+//! The scene is converted to glTF's Y-up, right-handed meters by a single
+//! root node, so mesh data and local transforms are written as authored.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use gltf_json as json;
+use json::validation::{Checked::Valid, USize64};
+
+use crate::bounds::BoundingBox;
+use crate::error::{Error, Result};
+use crate::material::{Material, MaterialExtractor};
+use crate::mesh::{matrix_multiply, InstancedMesh, Mesh, MeshExtractor, Orientation};
+use crate::normalize::{normalize, Convention};
+use crate::prim::{self, Purpose, Visibility};
+use crate::stage::Stage;
+use crate::xform;
+
+/// How textures referenced by materials are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Textures {
+    /// PNG and JPEG images are copied into the glTF buffer; writing fails
+    /// if one cannot be read. Images in other formats are referenced by
+    /// path.
+    #[default]
+    Embed,
+    /// Images are referenced by their authored asset paths.
+    External,
+}
+
+/// Writes a stage as glTF 2.0.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::export::gltf::{GltfExporter, Textures};
+/// use tinyusdz_rs::Stage;
+///
+/// let stage = Stage::open("assets/chair.usda").unwrap();
+/// GltfExporter::new(&stage)
+///     .base_dir("assets")
+///     .textures(Textures::Embed)
+///     .write("chair.glb")
+///     .unwrap();
+/// ```
+pub struct GltfExporter<'a> {
+    stage: &'a Stage,
+    convention: Convention,
+    purposes: Vec<Purpose>,
+    options: Options,
+}
+
+/// Settings that affect how a scene is written.
+#[derive(Debug, Clone, Default)]
+struct Options {
+    textures: Textures,
+    base_dir: Option<PathBuf>,
+    archive: Option<PathBuf>,
+}
+
+impl<'a> GltfExporter<'a> {
+    /// Creates an exporter for the given stage.
+    pub fn new(stage: &'a Stage) -> Self {
+        GltfExporter {
+            stage,
+            convention: Convention::default(),
+            purposes: vec![Purpose::Default, Purpose::Render],
+            options: Options::default(),
+        }
+    }
+
+    /// Sets how textures are written.
+    pub fn textures(mut self, textures: Textures) -> Self {
+        self.options.textures = textures;
+        self
+    }
+
+    /// Sets the directory relative texture paths are resolved against when
+    /// embedding them, usually the directory of the USD file. Defaults to
+    /// the current directory.
+    pub fn base_dir(mut self, base_dir: impl Into<PathBuf>) -> Self {
+        self.options.base_dir = Some(base_dir.into());
+        self
+    }
+
+    /// Reads embedded textures from a USDZ archive, usually the file the
+    /// stage was opened from. Texture paths are then relative to the root
+    /// of the archive instead of [`base_dir`](Self::base_dir).
+    pub fn archive(mut self, archive: impl Into<PathBuf>) -> Self {
+        self.options.archive = Some(archive.into());
+        self
+    }
+
+    /// Sets the convention of the exported scene. Defaults to glTF's Y-up,
+    /// right-handed meters; other conventions produce non-standard files.
+    pub fn convention(mut self, convention: Convention) -> Self {
+        self.convention = convention;
+        self
+    }
+
+    /// Sets the purposes of the prims to export. Defaults to `default` and
+    /// `render`, leaving out `proxy` and `guide` geometry.
+    pub fn purposes(mut self, purposes: &[Purpose]) -> Self {
+        self.purposes = purposes.to_vec();
+        self
+    }
+
+    /// Writes the stage to a file, as binary glTF if the extension is
+    /// `.glb`. Otherwise the JSON is written to `path` and the buffer to a
+    /// `.bin` file next to it.
+    pub fn write<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if path.extension().is_some_and(|ext| ext == "glb") {
+            return Ok(std::fs::write(path, self.to_glb()?)?);
+        }
+
+        let bin_path = path.with_extension("bin");
+        let uri = bin_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .ok_or_else(|| Error::InvalidPath("Path is not valid UTF-8".into()))?;
+        let (json, buffer) = self.to_gltf(uri)?;
+        std::fs::write(path, json)?;
+        std::fs::write(bin_path, buffer)?;
+        Ok(())
+    }
+
+    /// Returns the stage as binary glTF.
+    pub fn to_glb(&self) -> Result<Vec<u8>> {
+        let (root, buffer) = build(&self.scene()?, &self.options, None)?;
+        to_glb(&root, buffer)
+    }
+
+    /// Returns the stage as glTF JSON and the contents of its buffer, which
+    /// the JSON references as `buffer_uri`.
+    pub fn to_gltf(&self, buffer_uri: &str) -> Result<(String, Vec<u8>)> {
+        let (root, buffer) = build(&self.scene()?, &self.options, Some(buffer_uri))?;
+        let json = json::serialize::to_string_pretty(&root)
+            .map_err(|e| Error::ExportError(e.to_string()))?;
+        Ok((json, buffer))
+    }
+
+    /// Collects the prim hierarchy, meshes and materials of the stage.
+    ///
+    /// Inactive and invisible subtrees are left out, as are subtrees with a
+    /// non-default purpose that is not exported.
+    fn scene(&self) -> Result<Scene> {
+        let mut nodes = Vec::new();
+        let mut node_paths = HashMap::new();
+        let root = (None, Purpose::Default);
+        self.stage.walk(root, |visit, (parent, parent_purpose)| {
+            // Abstract prims and shading networks have no place in the scene.
+            if visit.spec.specifier == "class"
+                || matches!(
+                    visit.spec.type_name.as_str(),
+                    "Material" | "NodeGraph" | "Shader"
+                )
+            {
+                return None;
+            }
+            let purpose = match parent_purpose {
+                Purpose::Default => prim::purpose(visit.spec),
+                inherited => *inherited,
+            };
+            if prim::bool_meta(visit.spec, "active") == Some(false)
+                || prim::visibility(visit.spec) == Visibility::Invisible
+                || (purpose != Purpose::Default && !self.purposes.contains(&purpose))
+            {
+                return None;
+            }
+            if visit.spec.type_name == "GeomSubset" {
+                return Some((*parent, purpose));
+            }
+
+            let (local, resets) = xform::local_transform(visit.spec);
+            node_paths.insert(visit.path.clone(), nodes.len());
+            nodes.push(SceneNode {
+                name: visit.spec.name.clone(),
+                parent: if resets { None } else { *parent },
+                local,
+                mesh: None,
+            });
+            Some((Some(nodes.len() - 1), purpose))
+        });
+
+        let (meshes, instanced) = MeshExtractor::new(self.stage)
+            .purposes(&self.purposes)
+            .extract();
+        for (path, mesh) in meshes {
+            if let Some(&node) = node_paths.get(&path) {
+                nodes[node].mesh = Some(mesh);
+            }
+        }
+
+        Ok(Scene {
+            nodes,
+            instanced,
+            materials: MaterialExtractor::new(self.stage).collect(),
+            correction: normalize(self.stage, &self.convention)?.matrix(),
+        })
+    }
+}
+
+/// The data written to a glTF document.
+#[derive(Debug, Clone)]
+struct Scene {
+    /// Nodes for prims, parents before children.
+    nodes: Vec<SceneNode>,
+    instanced: Vec<InstancedMesh>,
+    materials: Vec<Material>,
+    /// Applied to the whole scene by the root node.
+    correction: [[f64; 4]; 4],
+}
+
+#[derive(Debug, Clone)]
+struct SceneNode {
+    name: String,
+    parent: Option<usize>,
+    local: [[f64; 4]; 4],
+    mesh: Option<Mesh>,
+}
+
+/// Builds the glTF document and its single buffer. The buffer is embedded
+/// (GLB) unless `buffer_uri` is given.
+fn build(
+    scene: &Scene,
+    options: &Options,
+    buffer_uri: Option<&str>,
+) -> Result<(json::Root, Vec<u8>)> {
+    let mut builder = Builder {
+        root: json::Root::default(),
+        buffer: Vec::new(),
+        options,
+        materials: &scene.materials,
+        archive: None,
+        material_indices: HashMap::new(),
+        image_indices: HashMap::new(),
+    };
+
+    let mut children: Vec<Vec<u32>> = vec![Vec::new(); scene.nodes.len()];
+    let mut roots = Vec::new();
+    for (i, node) in scene.nodes.iter().enumerate() {
+        match node.parent {
+            Some(parent) => children[parent].push(i as u32),
+            None => roots.push(i as u32),
+        }
+    }
+
+    // Prim nodes keep their indices, so children can be linked up front.
+    for (node, children) in scene.nodes.iter().zip(children) {
+        let mesh = match &node.mesh {
+            Some(mesh) => builder.push_mesh(mesh)?,
+            None => None,
+        };
+        builder.push_node(&node.name, node.local, mesh, children);
+    }
+
+    for instanced in &scene.instanced {
+        let prototype = &instanced.prototype;
+        if instanced.transforms.is_empty() {
+            continue;
+        }
+        let Some(mesh) = builder.push_mesh(prototype)? else {
+            continue;
+        };
+        for (i, transform) in instanced.transforms.iter().enumerate() {
+            let world = matrix_multiply(prototype.world_transform, *transform);
+            let name = format!("{}_{}", prototype.name, i);
+            roots.push(builder.push_node(&name, world, Some(mesh), Vec::new()));
+        }
+    }
+
+    if scene.correction != xform::identity() && !roots.is_empty() {
+        roots = vec![builder.push_node("Root", scene.correction, None, roots)];
+    }
+
+    let mut root = builder.root;
+    let buffer = builder.buffer;
+    root.scenes.push(json::Scene {
+        name: Some("Scene".to_string()),
+        nodes: roots.into_iter().map(json::Index::new).collect(),
+        extensions: None,
+        extras: Default::default(),
+    });
+    root.scene = Some(json::Index::new(0));
+    if !buffer.is_empty() {
+        root.buffers.push(json::Buffer {
+            byte_length: USize64(buffer.len() as u64),
+            uri: buffer_uri.map(String::from),
+            name: None,
+            extensions: None,
+            extras: Default::default(),
+        });
+    }
+    Ok((root, buffer))
+}
+
+struct Builder<'s> {
+    root: json::Root,
+    buffer: Vec<u8>,
+    options: &'s Options,
+    materials: &'s [Material],
+    /// The contents of the USDZ archive textures are read from.
+    archive: Option<Vec<u8>>,
+    /// glTF material per bound USD material path.
+    material_indices: HashMap<String, u32>,
+    /// glTF image per texture path.
+    image_indices: HashMap<String, u32>,
+}
+
+impl<'s> Builder<'s> {
+    /// Appends a node and returns its index. USD matrices are row-vector,
+    /// so their rows are glTF's columns.
+    fn push_node(
+        &mut self,
+        name: &str,
+        matrix: [[f64; 4]; 4],
+        mesh: Option<u32>,
+        children: Vec<u32>,
+    ) -> u32 {
+        let matrix = (matrix != xform::identity()).then(|| {
+            let mut columns = [0.0f32; 16];
+            for (c, value) in columns.iter_mut().zip(matrix.iter().flatten()) {
+                *c = *value as f32;
+            }
+            columns
+        });
+
+        self.root.nodes.push(json::Node {
+            camera: None,
+            children: (!children.is_empty())
+                .then(|| children.into_iter().map(json::Index::new).collect()),
+            skin: None,
+            matrix,
+            mesh: mesh.map(json::Index::new),
+            rotation: None,
+            scale: None,
+            translation: None,
+            weights: None,
+            name: Some(name.to_string()),
+            extensions: None,
+            extras: Default::default(),
+        });
+        self.root.nodes.len() as u32 - 1
+    }
+
+    /// Appends a glTF mesh with one primitive per bound material and returns
+    /// its index, or `None` if the mesh has no faces.
+    fn push_mesh(&mut self, mesh: &Mesh) -> Result<Option<u32>> {
+        let mut primitives = Vec::new();
+        for part in mesh.split_by_subsets()? {
+            let material = part
+                .material_binding
+                .as_ref()
+                .map(|path| self.push_material(path))
+                .transpose()?;
+            if let Some(primitive) = self.push_primitive(&part, material)? {
+                primitives.push(primitive);
+            }
+        }
+        if primitives.is_empty() {
+            return Ok(None);
+        }
+
+        let target_count = primitives[0].targets.as_ref().map_or(0, Vec::len);
+        self.root.meshes.push(json::Mesh {
+            name: Some(mesh.name.clone()),
+            primitives,
+            weights: (target_count > 0).then(|| vec![0.0; target_count]),
+            extensions: None,
+            extras: Default::default(),
+        });
+        Ok(Some(self.root.meshes.len() as u32 - 1))
+    }
+
+    /// Writes a mesh's vertex and index data and returns its primitive.
+    fn push_primitive(
+        &mut self,
+        mesh: &Mesh,
+        material: Option<u32>,
+    ) -> Result<Option<json::mesh::Primitive>> {
+        let mesh = prepare_mesh(mesh)?;
+        if mesh.points.is_empty() || mesh.face_vertex_indices.is_empty() {
+            return Ok(None);
+        }
+
+        let bounds = mesh.local_bounds();
+        let mut attributes = BTreeMap::new();
+        let positions = self.push_attribute(
+            &mesh.points,
+            json::accessor::Type::Vec3,
+            Some((bounds.min.map(|c| c as f32), bounds.max.map(|c| c as f32))),
+        );
+        attributes.insert(Valid(json::mesh::Semantic::Positions), positions);
+
+        if let Some(normals) = &mesh.normals {
+            let normals = self.push_attribute(normals, json::accessor::Type::Vec3, None);
+            attributes.insert(Valid(json::mesh::Semantic::Normals), normals);
+        }
+        if let Some(uvs) = &mesh.uvs {
+            // glTF places the UV origin at the top-left, USD at the bottom-left.
+            let flipped: Vec<[f32; 2]> = uvs.iter().map(|uv| [uv[0], 1.0 - uv[1]]).collect();
+            let uvs = self.push_attribute(&flipped, json::accessor::Type::Vec2, None);
+            attributes.insert(Valid(json::mesh::Semantic::TexCoords(0)), uvs);
+        }
+        if let Some(tangents) = &mesh.tangents {
+            // Flipping V mirrors texture space, which inverts the bitangent.
+            let flipped: Vec<[f32; 4]> =
+                tangents.iter().map(|t| [t[0], t[1], t[2], -t[3]]).collect();
+            let tangents = self.push_attribute(&flipped, json::accessor::Type::Vec4, None);
+            attributes.insert(Valid(json::mesh::Semantic::Tangents), tangents);
+        }
+
+        // Blend shapes become morph targets. glTF has no inbetweens, so only
+        // the full-weight offsets are written.
+        for shape in &mesh.blend_shapes {
+            shape.validate()?;
+        }
+        let targets: Vec<json::mesh::MorphTarget> = mesh
+            .blend_shapes
+            .iter()
+            .map(|shape| {
+                let bounds = BoundingBox::from_points(&shape.offsets);
+                let bounds = if bounds.is_empty() {
+                    ([0.0; 3], [0.0; 3])
+                } else {
+                    (bounds.min.map(|c| c as f32), bounds.max.map(|c| c as f32))
+                };
+                let positions =
+                    self.push_attribute(&shape.offsets, json::accessor::Type::Vec3, Some(bounds));
+                let normals = shape
+                    .normal_offsets
+                    .as_ref()
+                    .filter(|_| mesh.has_normals())
+                    .map(|offsets| self.push_attribute(offsets, json::accessor::Type::Vec3, None));
+                json::mesh::MorphTarget {
+                    positions: Some(positions),
+                    normals,
+                    tangents: None,
+                }
+            })
+            .collect();
+
+        let indices: Vec<u8> = mesh
+            .face_vertex_indices
+            .iter()
+            .flat_map(|&i| (i as u32).to_le_bytes())
+            .collect();
+        let view = self.push_view(&indices, Some(json::buffer::Target::ElementArrayBuffer));
+        let indices = self.push_accessor(
+            view,
+            mesh.face_vertex_indices.len(),
+            json::accessor::ComponentType::U32,
+            json::accessor::Type::Scalar,
+            None,
+        );
+
+        Ok(Some(json::mesh::Primitive {
+            attributes,
+            indices: Some(indices),
+            material: material.map(json::Index::new),
+            mode: Valid(json::mesh::Mode::Triangles),
+            targets: (!targets.is_empty()).then_some(targets),
+            extensions: None,
+            extras: Default::default(),
+        }))
+    }
+
+    /// Returns the glTF material for a bound USD material path, adding it on
+    /// first use. Materials are matched to extracted materials by prim path,
+    /// so materials sharing a name in different scopes stay distinct.
+    fn push_material(&mut self, path: &str) -> Result<u32> {
+        if let Some(&index) = self.material_indices.get(path) {
+            return Ok(index);
+        }
+
+        let material = self
+            .materials
+            .iter()
+            .find(|m| m.path == path)
+            .cloned()
+            .unwrap_or_else(|| Material::new(path.rsplit('/').next().unwrap_or(path)));
+
+        // glTF multiplies textures by their factors, so a textured channel
+        // without a value is written with a factor of one.
+        let [r, g, b] = material.diffuse_color.unwrap_or([1.0; 3]);
+        let opacity = material.opacity.unwrap_or(1.0);
+        let textured = material.metallic_roughness_texture.is_some();
+        let metallic = material
+            .metallic
+            .unwrap_or(if textured { 1.0 } else { 0.0 });
+        let roughness = material
+            .roughness
+            .unwrap_or(if textured { 1.0 } else { 0.5 });
+        let emissive = material
+            .emissive_color
+            .unwrap_or(if material.emissive_texture.is_some() {
+                [1.0; 3]
+            } else {
+                [0.0; 3]
+            });
+        let pbr = json::material::PbrMetallicRoughness {
+            base_color_factor: json::material::PbrBaseColorFactor([r, g, b, opacity]),
+            base_color_texture: self.push_texture_info(material.diffuse_texture.as_deref())?,
+            metallic_factor: json::material::StrengthFactor(metallic),
+            roughness_factor: json::material::StrengthFactor(roughness),
+            metallic_roughness_texture: self
+                .push_texture_info(material.metallic_roughness_texture.as_deref())?,
+            extensions: None,
+            extras: Default::default(),
+        };
+        let normal_texture = self
+            .push_texture(material.normal_texture.as_deref())?
+            .map(|index| json::material::NormalTexture {
+                index,
+                scale: 1.0,
+                tex_coord: 0,
+                extensions: None,
+                extras: Default::default(),
+            });
+        let occlusion_texture = self
+            .push_texture(material.occlusion_texture.as_deref())?
+            .map(|index| json::material::OcclusionTexture {
+                index,
+                strength: json::material::StrengthFactor(1.0),
+                tex_coord: 0,
+                extensions: None,
+                extras: Default::default(),
+            });
+
+        let emissive_texture = self.push_texture_info(material.emissive_texture.as_deref())?;
+        self.root.materials.push(json::Material {
+            alpha_cutoff: None,
+            alpha_mode: Valid(if material.is_transparent() {
+                json::material::AlphaMode::Blend
+            } else {
+                json::material::AlphaMode::Opaque
+            }),
+            double_sided: false,
+            name: Some(material.name.clone()),
+            pbr_metallic_roughness: pbr,
+            normal_texture,
+            occlusion_texture,
+            emissive_texture,
+            emissive_factor: json::material::EmissiveFactor(emissive),
+            extensions: None,
+            extras: Default::default(),
+        });
+        let index = self.root.materials.len() as u32 - 1;
+        self.material_indices.insert(path.to_string(), index);
+        Ok(index)
+    }
+
+    fn push_texture_info(&mut self, path: Option<&str>) -> Result<Option<json::texture::Info>> {
+        Ok(self.push_texture(path)?.map(|index| json::texture::Info {
+            index,
+            tex_coord: 0,
+            extensions: None,
+            extras: Default::default(),
+        }))
+    }
+
+    /// Adds a texture for an image path, sharing images between textures.
+    fn push_texture(&mut self, path: Option<&str>) -> Result<Option<json::Index<json::Texture>>> {
+        let Some(path) = path else {
+            return Ok(None);
+        };
+        let image = match self.image_indices.get(path) {
+            Some(&image) => image,
+            None => {
+                let image = self.push_image(path)?;
+                self.image_indices.insert(path.to_string(), image);
+                image
+            }
+        };
+
+        self.root.textures.push(json::Texture {
+            name: None,
+            sampler: None,
+            source: json::Index::new(image),
+            extensions: None,
+            extras: Default::default(),
+        });
+        Ok(Some(json::Index::new(self.root.textures.len() as u32 - 1)))
+    }
+
+    fn push_image(&mut self, path: &str) -> Result<u32> {
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(String::from);
+        let embedded = match (self.options.textures, mime_type(path)) {
+            (Textures::Embed, Some(mime_type)) => Some((self.read_image(path)?, mime_type)),
+            _ => None,
+        };
+
+        let image = match embedded {
+            Some((data, mime_type)) => json::Image {
+                buffer_view: Some(self.push_view(&data, None)),
+                mime_type: Some(json::image::MimeType(mime_type.to_string())),
+                name,
+                uri: None,
+                extensions: None,
+                extras: Default::default(),
+            },
+            None => json::Image {
+                buffer_view: None,
+                mime_type: None,
+                name,
+                uri: Some(path.replace('\\', "/")),
+                extensions: None,
+                extras: Default::default(),
+            },
+        };
+        self.root.images.push(image);
+        Ok(self.root.images.len() as u32 - 1)
+    }
+
+    /// Reads an image file from the archive or the base directory.
+    fn read_image(&mut self, path: &str) -> Result<Vec<u8>> {
+        if let Some(archive) = &self.options.archive {
+            if self.archive.is_none() {
+                self.archive = Some(std::fs::read(archive)?);
+            }
+            let data = self.archive.as_deref().unwrap_or_default();
+            return usdz_entry(data, path).map(<[u8]>::to_vec).ok_or_else(|| {
+                Error::ExportError(format!(
+                    "Texture {} not found in {}",
+                    path,
+                    archive.display()
+                ))
+            });
+        }
+
+        let file = match &self.options.base_dir {
+            Some(dir) => dir.join(path),
+            None => PathBuf::from(path),
+        };
+        std::fs::read(&file).map_err(|e| {
+            Error::ExportError(format!("Cannot read texture {}: {}", file.display(), e))
+        })
+    }
+
+    /// Appends `data` to the buffer as a new buffer view.
+    fn push_view(
+        &mut self,
+        data: &[u8],
+        target: Option<json::buffer::Target>,
+    ) -> json::Index<json::buffer::View> {
+        let offset = self.buffer.len();
+        self.buffer.extend_from_slice(data);
+        // Keep every view 4-byte aligned.
+        self.buffer.resize(align4(self.buffer.len()), 0);
+
+        self.root.buffer_views.push(json::buffer::View {
+            buffer: json::Index::new(0),
+            byte_length: USize64(data.len() as u64),
+            byte_offset: Some(USize64(offset as u64)),
+            byte_stride: None,
+            target: target.map(Valid),
+            name: None,
+            extensions: None,
+            extras: Default::default(),
+        });
+        json::Index::new(self.root.buffer_views.len() as u32 - 1)
+    }
+
+    fn push_accessor(
+        &mut self,
+        view: json::Index<json::buffer::View>,
+        count: usize,
+        component_type: json::accessor::ComponentType,
+        type_: json::accessor::Type,
+        bounds: Option<(Vec<f32>, Vec<f32>)>,
+    ) -> json::Index<json::Accessor> {
+        self.root.accessors.push(json::Accessor {
+            buffer_view: Some(view),
+            byte_offset: Some(USize64(0)),
+            count: USize64(count as u64),
+            component_type: Valid(json::accessor::GenericComponentType(component_type)),
+            type_: Valid(type_),
+            min: bounds
+                .as_ref()
+                .map(|(min, _)| json::Value::from(min.clone())),
+            max: bounds.map(|(_, max)| json::Value::from(max)),
+            normalized: false,
+            sparse: None,
+            name: None,
+            extensions: None,
+            extras: Default::default(),
+        });
+        json::Index::new(self.root.accessors.len() as u32 - 1)
+    }
+
+    /// Writes a float vertex attribute and returns its accessor.
+    fn push_attribute<const N: usize>(
+        &mut self,
+        values: &[[f32; N]],
+        type_: json::accessor::Type,
+        bounds: Option<([f32; N], [f32; N])>,
+    ) -> json::Index<json::Accessor> {
+        let bytes: Vec<u8> = values
+            .iter()
+            .flatten()
+            .flat_map(|c| c.to_le_bytes())
+            .collect();
+        let view = self.push_view(&bytes, Some(json::buffer::Target::ArrayBuffer));
+        self.push_accessor(
+            view,
+            values.len(),
+            json::accessor::ComponentType::F32,
+            type_,
+            bounds.map(|(min, max)| (min.to_vec(), max.to_vec())),
+        )
+    }
+}
+
+/// Triangulates a mesh with counter-clockwise winding and, when it has
+/// normals, UVs, tangents or blend shapes, splits it into one vertex per
+/// corner so every attribute shares the index buffer.
+fn prepare_mesh(mesh: &Mesh) -> Result<Mesh> {
+    let mut mesh = mesh.clone();
+    if mesh.has_normals() && mesh.has_uvs() && !mesh.has_tangents() {
+        mesh.compute_tangents()?;
+    }
+
+    let mut mesh = mesh.triangulate()?;
+    if mesh.has_normals() || mesh.has_uvs() || mesh.has_tangents() || !mesh.blend_shapes.is_empty()
+    {
+        mesh = mesh.unweld()?;
+    }
+    if mesh.orientation == Orientation::LeftHanded {
+        for triangle in mesh.face_vertex_indices.chunks_exact_mut(3) {
+            triangle.swap(1, 2);
+        }
+    }
+    Ok(mesh)
+}
+
+/// Finds a file in a USDZ archive. USDZ archives are zip files whose
+/// entries are stored uncompressed, so an entry's data follows its local
+/// header.
+fn usdz_entry<'d>(archive: &'d [u8], path: &str) -> Option<&'d [u8]> {
+    let path = path.trim_start_matches("./");
+    let u16_at = |at: usize| {
+        Some(u16::from_le_bytes(
+            archive.get(at..at + 2)?.try_into().ok()?,
+        ))
+    };
+    let u32_at = |at: usize| {
+        Some(u32::from_le_bytes(
+            archive.get(at..at + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let mut offset = 0;
+    // The local headers end where the central directory starts.
+    while u32_at(offset)? == 0x0403_4b50 {
+        let compression = u16_at(offset + 8)?;
+        let size = u32_at(offset + 18)? as usize;
+        let name_len = u16_at(offset + 26)? as usize;
+        let extra_len = u16_at(offset + 28)? as usize;
+        let name = archive.get(offset + 30..offset + 30 + name_len)?;
+        let start = offset + 30 + name_len + extra_len;
+        if name == path.as_bytes() {
+            return (compression == 0).then(|| archive.get(start..start + size))?;
+        }
+        offset = start + size;
+    }
+    None
+}
+
+/// Returns the glTF image MIME type for a texture path, if supported.
+fn mime_type(path: &str) -> Option<&'static str> {
+    let extension = Path::new(path).extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        _ => None,
+    }
+}
+
+/// Packs a document and its buffer into a GLB container.
+pub(crate) fn to_glb(root: &json::Root, buffer: Vec<u8>) -> Result<Vec<u8>> {
+    let mut json = json::serialize::to_vec(root).map_err(|e| Error::ExportError(e.to_string()))?;
+    // Chunks are 4-byte aligned; JSON is padded with spaces, binary with zeros.
+    json.resize(align4(json.len()), b' ');
+    let mut bin = buffer;
+    bin.resize(align4(bin.len()), 0);
+
+    let bin_chunk = if bin.is_empty() { 0 } else { 8 + bin.len() };
+    let length = 12 + 8 + json.len() + bin_chunk;
+    let length =
+        u32::try_from(length).map_err(|_| Error::ExportError("GLB exceeds 4 GiB".into()))?;
+
+    let mut glb = Vec::with_capacity(length as usize);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&length.to_le_bytes());
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend_from_slice(&json);
+    if !bin.is_empty() {
+        glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+        glb.extend_from_slice(b"BIN\0");
+        glb.extend_from_slice(&bin);
+    }
+    Ok(glb)
+}
+
+fn align4(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::{ElementType, GeomSubset};
+    use crate::normalize::{Handedness, Normalization};
+    use crate::stage::Axis;
+
+    /// Two quads side by side, each bound to its own material.
+    fn two_quads() -> Mesh {
+        let mut mesh = Mesh::new("Quads");
+        mesh.points = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [2.0, 1.0, 0.0],
+        ];
+        mesh.face_vertex_counts = vec![4, 4];
+        mesh.face_vertex_indices = vec![0, 1, 2, 3, 1, 4, 5, 2];
+        mesh.normals = Some(vec![[0.0, 0.0, 1.0]; 6]);
+        mesh.uvs = Some(vec![
+            [0.0, 0.0],
+            [0.5, 0.0],
+            [0.5, 1.0],
+            [0.0, 1.0],
+            [1.0, 0.0],
+            [1.0, 1.0],
+        ]);
+        mesh.subsets = ["Red", "Blue"]
+            .iter()
+            .enumerate()
+            .map(|(face, name)| GeomSubset {
+                name: name.to_string(),
+                element_type: ElementType::Face,
+                indices: vec![face as i32],
+                family_name: Some(GeomSubset::MATERIAL_BIND_FAMILY.into()),
+                material_binding: Some(format!("/World/Looks/{}", name)),
+            })
+            .collect();
+        mesh
+    }
+
+    fn scene() -> Scene {
+        let mut red = Material::new("Red");
+        red.path = "/World/Looks/Red".into();
+        red.diffuse_color = Some([1.0, 0.0, 0.0]);
+        red.diffuse_texture = Some("textures/red.png".into());
+        let mut blue = Material::new("Blue");
+        blue.path = "/World/Looks/Blue".into();
+
+        let z_up_cm = Convention {
+            up_axis: Axis::Z,
+            meters_per_unit: 0.01,
+            handedness: Handedness::Right,
+        };
+        Scene {
+            nodes: vec![
+                SceneNode {
+                    name: "World".into(),
+                    parent: None,
+                    local: xform::identity(),
+                    mesh: None,
+                },
+                SceneNode {
+                    name: "Quads".into(),
+                    parent: Some(0),
+                    local: xform::translation([0.0, 0.0, 100.0]),
+                    mesh: Some(two_quads()),
+                },
+            ],
+            instanced: Vec::new(),
+            materials: vec![red, blue],
+            correction: Normalization::new(&z_up_cm, &Convention::default()).matrix(),
+        }
+    }
+
+    #[test]
+    fn test_glb_round_trip() {
+        let options = Options {
+            textures: Textures::External,
+            base_dir: None,
+            archive: None,
+        };
+        let (root, buffer) = build(&scene(), &options, None).unwrap();
+        let glb = to_glb(&root, buffer).unwrap();
+        let gltf = gltf::Gltf::from_slice(&glb).unwrap();
+        let document = &gltf.document;
+
+        // Root (unit and up-axis correction) -> World -> Quads.
+        let scene = document.default_scene().unwrap();
+        let root = scene.nodes().next().unwrap();
+        assert_eq!(root.name(), Some("Root"));
+        let world = root.children().next().unwrap();
+        let quads = world.children().next().unwrap();
+        assert_eq!(quads.name(), Some("Quads"));
+        let (translation, _, _) = quads.transform().decomposed();
+        assert_eq!(translation, [0.0, 0.0, 100.0]);
+
+        let mesh = quads.mesh().unwrap();
+        assert_eq!(mesh.primitives().len(), 2);
+        for (primitive, name) in mesh.primitives().zip(["Red", "Blue"]) {
+            assert_eq!(primitive.material().name(), Some(name));
+            let reader = primitive.reader(|_| gltf.blob.as_deref());
+            assert_eq!(reader.read_indices().unwrap().into_u32().count(), 6);
+            assert!(reader.read_normals().unwrap().all(|n| n == [0.0, 0.0, 1.0]));
+            assert_eq!(reader.read_tex_coords(0).unwrap().into_f32().count(), 6);
+        }
+
+        let red = document.materials().next().unwrap();
+        let pbr = red.pbr_metallic_roughness();
+        assert_eq!(pbr.base_color_factor(), [1.0, 0.0, 0.0, 1.0]);
+        let texture = pbr.base_color_texture().unwrap().texture();
+        match texture.source().source() {
+            gltf::image::Source::Uri { uri, .. } => assert_eq!(uri, "textures/red.png"),
+            gltf::image::Source::View { .. } => panic!("texture should be external"),
+        }
+    }
+
+    #[test]
+    fn test_embedded_texture() {
+        let dir = std::env::temp_dir().join("tinyusdz_rs_gltf_export");
+        std::fs::create_dir_all(dir.join("textures")).unwrap();
+        std::fs::write(dir.join("textures/red.png"), b"\x89PNG\r\n\x1a\n").unwrap();
+
+        let options = Options {
+            textures: Textures::Embed,
+            base_dir: Some(dir),
+            archive: None,
+        };
+        let (root, buffer) = build(&scene(), &options, Some("scene.bin")).unwrap();
+        let json = json::serialize::to_vec(&root).unwrap();
+        let document = gltf::Gltf::from_slice(&json).unwrap().document;
+
+        assert_eq!(document.buffers().len(), 1);
+        assert_eq!(document.buffers().next().unwrap().length(), buffer.len());
+        let image = document.images().next().unwrap();
+        match image.source() {
+            gltf::image::Source::View { mime_type, .. } => assert_eq!(mime_type, "image/png"),
+            gltf::image::Source::Uri { .. } => panic!("texture should be embedded"),
+        }
+    }
+
+    #[test]
+    fn test_missing_texture() {
+        let options = Options {
+            textures: Textures::Embed,
+            base_dir: Some(std::env::temp_dir().join("tinyusdz_rs_missing")),
+            archive: None,
+        };
+        assert!(matches!(
+            build(&scene(), &options, None),
+            Err(Error::ExportError(_))
+        ));
+    }
+
+    #[test]
+    fn test_usdz_texture() {
+        // A stored entry: local header, name, then data.
+        let stored = |name: &str, data: &[u8]| {
+            let mut entry = 0x0403_4b50u32.to_le_bytes().to_vec();
+            entry.extend_from_slice(&[20, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+            entry.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            entry.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            entry.extend_from_slice(&(name.len() as u16).to_le_bytes());
+            entry.extend_from_slice(&0u16.to_le_bytes());
+            entry.extend_from_slice(name.as_bytes());
+            entry.extend_from_slice(data);
+            entry
+        };
+        let mut archive = stored("scene.usdc", b"PXR-USDC");
+        archive.extend(stored("textures/red.png", b"\x89PNG\r\n\x1a\n"));
+        // Start of the central directory.
+        archive.extend_from_slice(&0x0201_4b50u32.to_le_bytes());
+
+        assert_eq!(
+            usdz_entry(&archive, "./textures/red.png"),
+            Some(&b"\x89PNG\r\n\x1a\n"[..])
+        );
+        assert_eq!(usdz_entry(&archive, "textures/blue.png"), None);
+
+        let dir = std::env::temp_dir().join("tinyusdz_rs_gltf_usdz");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("scene.usdz");
+        std::fs::write(&path, &archive).unwrap();
+        let options = Options {
+            textures: Textures::Embed,
+            base_dir: None,
+            archive: Some(path),
+        };
+        let (root, _) = build(&scene(), &options, None).unwrap();
+        assert!(root.images[0].buffer_view.is_some());
+    }
+
+    #[test]
+    fn test_materials_keyed_by_path() {
+        // Two materials named "Red" in different scopes.
+        let mut scene = scene();
+        let mut other = Material::new("Red");
+        other.path = "/World/Other/Red".into();
+        other.diffuse_color = Some([0.0, 1.0, 0.0]);
+        scene.materials.push(other);
+        let mesh = scene.nodes[1].mesh.as_mut().unwrap();
+        mesh.subsets[1].material_binding = Some("/World/Other/Red".into());
+
+        let options = Options {
+            textures: Textures::External,
+            base_dir: None,
+            archive: None,
+        };
+        let (root, _) = build(&scene, &options, None).unwrap();
+        let colors: Vec<_> = root
+            .materials
+            .iter()
+            .map(|m| m.pbr_metallic_roughness.base_color_factor.0)
+            .collect();
+        assert_eq!(colors, [[1.0, 0.0, 0.0, 1.0], [0.0, 1.0, 0.0, 1.0]]);
+    }
+
+    #[test]
+    fn test_textured_material_factors() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Mesh "Triangle"
+{
+    int[] faceVertexCounts = [3]
+    int[] faceVertexIndices = [0, 1, 2]
+    point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+    rel material:binding = </Looks/Wood>
+}
+
+def Scope "Looks"
+{
+    def Material "Wood"
+    {
+        token outputs:surface.connect = </Looks/Wood/Surface.outputs:surface>
+
+        def Shader "Surface"
+        {
+            uniform token info:id = "UsdPreviewSurface"
+            color3f inputs:diffuseColor.connect = </Looks/Wood/Albedo.outputs:rgb>
+            color3f inputs:emissiveColor.connect = </Looks/Wood/Glow.outputs:rgb>
+            float inputs:roughness.connect = </Looks/Wood/Rough.outputs:r>
+            float inputs:metallic = 0
+            token outputs:surface
+        }
+
+        def Shader "Albedo"
+        {
+            uniform token info:id = "UsdUVTexture"
+            asset inputs:file = @textures/albedo.png@
+            float3 outputs:rgb
+        }
+
+        def Shader "Glow"
+        {
+            uniform token info:id = "UsdUVTexture"
+            asset inputs:file = @textures/glow.png@
+            float3 outputs:rgb
+        }
+
+        def Shader "Rough"
+        {
+            uniform token info:id = "UsdUVTexture"
+            asset inputs:file = @textures/rough.png@
+            float outputs:r
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        let glb = GltfExporter::new(&stage)
+            .textures(Textures::External)
+            .to_glb()
+            .unwrap();
+        let document = gltf::Gltf::from_slice(&glb).unwrap().document;
+        let wood = document.materials().next().unwrap();
+        assert_eq!(wood.name(), Some("Wood"));
+
+        // Textured channels are not scaled by the UsdPreviewSurface
+        // fallbacks; the authored metallic value still applies.
+        let pbr = wood.pbr_metallic_roughness();
+        assert!(pbr.base_color_texture().is_some());
+        assert_eq!(pbr.base_color_factor(), [1.0; 4]);
+        assert!(pbr.metallic_roughness_texture().is_some());
+        assert_eq!(pbr.metallic_factor(), 0.0);
+        assert_eq!(pbr.roughness_factor(), 1.0);
+        assert!(wood.emissive_texture().is_some());
+        assert_eq!(wood.emissive_factor(), [1.0; 3]);
+    }
+
+    #[test]
+    fn test_skip_hidden_prims() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Xform "World"
+{
+    def Mesh "Shown"
+    {
+        int[] faceVertexCounts = [3]
+        int[] faceVertexIndices = [0, 1, 2]
+        point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+    }
+
+    def Xform "Hidden"
+    {
+        token visibility = "invisible"
+
+        def Mesh "Inner"
+        {
+        }
+    }
+
+    def Mesh "Off" (
+        active = false
+    )
+    {
+    }
+
+    def Xform "Rig"
+    {
+        uniform token purpose = "guide"
+
+        def Mesh "Handle"
+        {
+        }
+    }
+
+    def Mesh "Proxy"
+    {
+        uniform token purpose = "proxy"
+    }
+
+    def Mesh "Render"
+    {
+        uniform token purpose = "render"
+    }
+}
+"#,
+        )
+        .unwrap();
+        let names = |exporter: GltfExporter<'_>| {
+            let glb = exporter.to_glb().unwrap();
+            let document = gltf::Gltf::from_slice(&glb).unwrap().document;
+            let mut names: Vec<_> = document
+                .nodes()
+                .filter_map(|node| node.name().map(String::from))
+                .collect();
+            names.sort();
+            (names, document.meshes().len())
+        };
+
+        // Invisible and inactive subtrees, guides and proxies are left out,
+        // along with their meshes.
+        let (nodes, meshes) = names(GltfExporter::new(&stage));
+        assert_eq!(nodes, ["Render", "Root", "Shown", "World"]);
+        assert_eq!(meshes, 1);
+
+        // Default prims stay in the hierarchy but have no meshes when the
+        // default purpose is not exported.
+        let (nodes, meshes) = names(GltfExporter::new(&stage).purposes(&[Purpose::Guide]));
+        assert_eq!(nodes, ["Handle", "Rig", "Root", "Shown", "World"]);
+        assert_eq!(meshes, 0);
+    }
+}
@@ -0,0 +1,6 @@
+//! Importers from other scene formats.
+//!
+//! Each importer is behind a cargo feature of the same name.
+
+#[cfg(feature = "gltf")]
+pub mod gltf;
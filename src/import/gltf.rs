@@ -0,0 +1,1348 @@
+//! glTF 2.0 import.
+//!
+//! A [`GltfImporter`] converts a glTF scene to a USD layer:
+//!
+//! - nodes become `Xform` prims with their local transforms,
+//! - mesh primitives become `Mesh` prims with normals, `primvars:st` and
+//!   `primvars:displayColor`,
+//! - PBR materials become `UsdPreviewSurface` networks with `UsdUVTexture`
+//!   readers,
+//! - skins become a `Skeleton` under a `SkelRoot`, with skinned meshes bound
+//!   to it.
+//!
+//! Note: The tinyusdz C API cannot author stages, so the layer is written as
+//! USDA text and loaded back with [`Stage::from_usda`]. Animations, cameras
+//! and lights are not imported.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Error, Result};
+use crate::mesh::matrix_multiply;
+use crate::stage::Stage;
+use crate::xform;
+
+/// Converts glTF files to USD.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::import::gltf::GltfImporter;
+///
+/// let importer = GltfImporter::new().texture_dir("textures");
+/// std::fs::write("chair.usda", importer.to_usda("chair.glb").unwrap()).unwrap();
+///
+/// let stage = importer.import("chair.glb").unwrap();
+/// for prim in stage.traverse() {
+///     println!("{} ({})", prim.name(), prim.type_name());
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct GltfImporter {
+    texture_dir: Option<PathBuf>,
+    output_dir: Option<PathBuf>,
+}
+
+impl GltfImporter {
+    /// Creates an importer with default settings.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory embedded images are written to, so materials can
+    /// reference them. Without it, embedded images are not imported.
+    pub fn texture_dir(mut self, texture_dir: impl Into<PathBuf>) -> Self {
+        self.texture_dir = Some(texture_dir.into());
+        self
+    }
+
+    /// Sets the directory the USDA layer will be saved in. Texture paths are
+    /// written relative to it, and default to being relative to the glTF
+    /// file's directory (or the current directory for in-memory data).
+    pub fn output_dir(mut self, output_dir: impl Into<PathBuf>) -> Self {
+        self.output_dir = Some(output_dir.into());
+        self
+    }
+
+    /// Imports a `.gltf` or `.glb` file as a stage.
+    pub fn import<P: AsRef<Path>>(&self, path: P) -> Result<Stage> {
+        Stage::from_usda(self.to_usda(path)?.as_bytes())
+    }
+
+    /// Imports glTF data in memory as a stage.
+    ///
+    /// External buffers and images cannot be resolved, so the data should
+    /// be GLB or use embedded buffers.
+    pub fn import_slice(&self, data: &[u8]) -> Result<Stage> {
+        Stage::from_usda(self.to_usda_from_slice(data)?.as_bytes())
+    }
+
+    /// Converts a `.gltf` or `.glb` file to USDA text.
+    ///
+    /// Texture paths are relative to the output directory, so the layer
+    /// should be saved there (by default, next to the glTF file).
+    pub fn to_usda<P: AsRef<Path>>(&self, path: P) -> Result<String> {
+        let path = path.as_ref();
+        let gltf::Gltf { document, blob } = gltf::Gltf::open(path).map_err(import_error)?;
+        let base_dir = path
+            .parent()
+            .map(|dir| dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf()));
+        let buffers =
+            gltf::import_buffers(&document, base_dir.as_deref(), blob).map_err(import_error)?;
+        self.convert(&document, &buffers, base_dir.as_deref())
+    }
+
+    /// Converts glTF data in memory to USDA text.
+    pub fn to_usda_from_slice(&self, data: &[u8]) -> Result<String> {
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(data).map_err(import_error)?;
+        let buffers = gltf::import_buffers(&document, None, blob).map_err(import_error)?;
+        self.convert(&document, &buffers, None)
+    }
+
+    fn convert(
+        &self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        base_dir: Option<&Path>,
+    ) -> Result<String> {
+        let textures = self.texture_paths(document, buffers, base_dir)?;
+        Converter::new(document, buffers, textures).write()
+    }
+
+    /// Returns the asset path of every image relative to the output
+    /// directory, writing embedded images to the texture directory.
+    fn texture_paths(
+        &self,
+        document: &gltf::Document,
+        buffers: &[gltf::buffer::Data],
+        base_dir: Option<&Path>,
+    ) -> Result<Vec<Option<String>>> {
+        let output_dir = absolute(
+            self.output_dir
+                .as_deref()
+                .or(base_dir)
+                .unwrap_or(Path::new(".")),
+        );
+        let asset_path = |path: &Path| {
+            let path = absolute(path);
+            let path = relative_path(&path, &output_dir).unwrap_or(path);
+            path.to_string_lossy().replace('\\', "/")
+        };
+        let mut paths = Vec::new();
+        for image in document.images() {
+            let path = match image.source() {
+                gltf::image::Source::Uri { uri, .. } if !uri.starts_with("data:") => {
+                    let path = match base_dir {
+                        Some(dir) => dir.join(uri),
+                        None => PathBuf::from(uri),
+                    };
+                    Some(asset_path(&path))
+                }
+                gltf::image::Source::View { view, mime_type } => match &self.texture_dir {
+                    Some(dir) => {
+                        let extension = if mime_type == "image/jpeg" {
+                            "jpg"
+                        } else {
+                            "png"
+                        };
+                        let name = image.name().map(sanitize).unwrap_or_default();
+                        let path =
+                            dir.join(format!("image_{}_{}.{}", image.index(), name, extension));
+                        let data = &buffers[view.buffer().index()];
+                        std::fs::create_dir_all(dir)?;
+                        std::fs::write(&path, &data[view.offset()..view.offset() + view.length()])?;
+                        Some(asset_path(&path))
+                    }
+                    None => None,
+                },
+                // Data URIs would need decoding to be written out.
+                gltf::image::Source::Uri { .. } => None,
+            };
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+}
+
+fn import_error(e: gltf::Error) -> Error {
+    Error::ImportError(e.to_string())
+}
+
+/// Makes a path absolute, resolving symlinks where it exists.
+fn absolute(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| {
+        std::env::current_dir()
+            .map(|dir| dir.join(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    })
+}
+
+/// Returns `path` relative to `dir`, or `None` when they share no root
+/// (e.g. different Windows drives). Both paths must be absolute.
+fn relative_path(path: &Path, dir: &Path) -> Option<PathBuf> {
+    let path: Vec<_> = path.components().collect();
+    let dir: Vec<_> = dir.components().collect();
+    let common = path.iter().zip(&dir).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return None;
+    }
+    let mut relative = PathBuf::new();
+    for _ in common..dir.len() {
+        relative.push("..");
+    }
+    relative.extend(&path[common..]);
+    Some(relative)
+}
+
+/// Writes one glTF document as USDA.
+struct Converter<'a> {
+    document: &'a gltf::Document,
+    buffers: &'a [gltf::buffer::Data],
+    /// Asset path per image.
+    textures: Vec<Option<String>>,
+    out: Usda,
+    /// Prim path per material.
+    materials: Vec<String>,
+    /// Prim path per skin.
+    skeletons: Vec<String>,
+    /// Old to new joint index per skin, with joints ordered parents first.
+    joint_orders: Vec<Vec<usize>>,
+    world_transforms: HashMap<usize, [[f64; 4]; 4]>,
+}
+
+const ROOT: &str = "/Root";
+
+impl<'a> Converter<'a> {
+    fn new(
+        document: &'a gltf::Document,
+        buffers: &'a [gltf::buffer::Data],
+        textures: Vec<Option<String>>,
+    ) -> Self {
+        Converter {
+            document,
+            buffers,
+            textures,
+            out: Usda::default(),
+            materials: Vec::new(),
+            skeletons: Vec::new(),
+            joint_orders: Vec::new(),
+            world_transforms: HashMap::new(),
+        }
+    }
+
+    fn write(mut self) -> Result<String> {
+        let scene = self
+            .document
+            .default_scene()
+            .or_else(|| self.document.scenes().next());
+        let roots: Vec<gltf::Node<'a>> = scene.map(|s| s.nodes().collect()).unwrap_or_default();
+        for node in &roots {
+            self.compute_world_transforms(node, xform::identity());
+        }
+
+        self.out.line("#usda 1.0");
+        self.out.line("(");
+        self.out.line("    defaultPrim = \"Root\"");
+        self.out.line("    metersPerUnit = 1");
+        self.out.line("    upAxis = \"Y\"");
+        self.out.line(")");
+        self.out.line("");
+
+        // Skinned meshes and their skeletons must share a SkelRoot.
+        let root_type = if self.document.skins().len() > 0 {
+            "SkelRoot"
+        } else {
+            "Xform"
+        };
+        self.out.begin(&format!("def {} \"Root\"", root_type), &[]);
+        let mut names = UniqueNames::default();
+
+        let materials_scope = names.get("Materials");
+        let skeleton_names: Vec<String> = self
+            .document
+            .skins()
+            .map(|skin| names.get(skin.name().unwrap_or("Skeleton")))
+            .collect();
+        self.skeletons = skeleton_names
+            .iter()
+            .map(|name| format!("{}/{}", ROOT, name))
+            .collect();
+
+        let mut skinned = Vec::new();
+        for node in &roots {
+            self.write_node(node, ROOT, &mut names, &mut skinned)?;
+        }
+        for node in skinned {
+            let name = names.get(&mesh_name(&node));
+            self.write_mesh_prims(&node, &name)?;
+        }
+        for (skin, name) in self.document.skins().zip(&skeleton_names) {
+            self.write_skeleton(&skin, name);
+        }
+        if self.document.materials().len() > 0 {
+            self.write_materials(&materials_scope);
+        }
+
+        self.out.end();
+        Ok(self.out.text)
+    }
+
+    fn compute_world_transforms(&mut self, node: &gltf::Node<'_>, parent: [[f64; 4]; 4]) {
+        let world = matrix_multiply(node_matrix(node), parent);
+        self.world_transforms.insert(node.index(), world);
+        for child in node.children() {
+            self.compute_world_transforms(&child, world);
+        }
+    }
+
+    /// Writes a node as an Xform. Skinned meshes are collected to be written
+    /// under the SkelRoot, since glTF ignores their node's transform.
+    fn write_node(
+        &mut self,
+        node: &gltf::Node<'a>,
+        parent_path: &str,
+        names: &mut UniqueNames,
+        skinned: &mut Vec<gltf::Node<'a>>,
+    ) -> Result<()> {
+        let name = names.get(&node_name(node));
+        let path = format!("{}/{}", parent_path, name);
+        self.out.begin(&format!("def Xform \"{}\"", name), &[]);
+
+        let matrix = node_matrix(node);
+        if matrix != xform::identity() {
+            self.out.line(format!(
+                "matrix4d xformOp:transform = {}",
+                format_matrix(&matrix)
+            ));
+            self.out
+                .line("uniform token[] xformOpOrder = [\"xformOp:transform\"]");
+        }
+
+        let mut child_names = UniqueNames::default();
+        if node.mesh().is_some() {
+            if node.skin().is_some() {
+                skinned.push(node.clone());
+            } else {
+                let mesh_name = child_names.get(&mesh_name(node));
+                self.write_mesh_prims(node, &mesh_name)?;
+            }
+        }
+        for child in node.children() {
+            self.write_node(&child, &path, &mut child_names, skinned)?;
+        }
+
+        self.out.end();
+        Ok(())
+    }
+
+    /// Writes a node's mesh as one Mesh prim per primitive, grouped under an
+    /// Xform when there are several.
+    fn write_mesh_prims(&mut self, node: &gltf::Node<'_>, name: &str) -> Result<()> {
+        let Some(mesh) = node.mesh() else {
+            return Ok(());
+        };
+        let primitives: Vec<_> = mesh.primitives().collect();
+        if let Some(primitive) = primitives.iter().find(|p| !is_triangles(p.mode())) {
+            return Err(Error::ImportError(format!(
+                "mesh {:?} has {:?} primitives, which cannot be imported as a Mesh",
+                name,
+                primitive.mode()
+            )));
+        }
+        match primitives.as_slice() {
+            [] => {}
+            [primitive] => self.write_primitive(primitive, node, name)?,
+            _ => {
+                self.out.begin(&format!("def Xform \"{}\"", name), &[]);
+                for (i, primitive) in primitives.iter().enumerate() {
+                    self.write_primitive(primitive, node, &format!("{}_{}", name, i))?;
+                }
+                self.out.end();
+            }
+        }
+        Ok(())
+    }
+
+    fn write_primitive(
+        &mut self,
+        primitive: &gltf::Primitive<'_>,
+        node: &gltf::Node<'_>,
+        name: &str,
+    ) -> Result<()> {
+        let reader = primitive.reader(|buffer| Some(&self.buffers[buffer.index()]));
+        let Some(points) = reader.read_positions() else {
+            return Ok(());
+        };
+        let points: Vec<[f32; 3]> = points.collect();
+        let indices: Vec<u32> = match reader.read_indices() {
+            Some(indices) => indices.into_u32().collect(),
+            None => (0..points.len() as u32).collect(),
+        };
+        let indices = triangle_list(primitive.mode(), indices);
+        if !indices.chunks_exact(3).remainder().is_empty() {
+            return Err(Error::ImportError(format!(
+                "mesh {:?} has {} triangle indices, which is not a multiple of 3",
+                name,
+                indices.len()
+            )));
+        }
+        if let Some(index) = indices.iter().find(|&&i| i as usize >= points.len()) {
+            return Err(Error::ImportError(format!(
+                "mesh {:?} has index {} but {} points",
+                name,
+                index,
+                points.len()
+            )));
+        }
+        let material = primitive.material().index();
+        let skin = node.skin();
+
+        let mut schemas = Vec::new();
+        if material.is_some() {
+            schemas.push("\"MaterialBindingAPI\"");
+        }
+        if skin.is_some() {
+            schemas.push("\"SkelBindingAPI\"");
+        }
+        let metadata = if schemas.is_empty() {
+            Vec::new()
+        } else {
+            vec![format!("prepend apiSchemas = [{}]", schemas.join(", "))]
+        };
+        self.out.begin(&format!("def Mesh \"{}\"", name), &metadata);
+
+        let out = &mut self.out;
+        out.line(format!(
+            "int[] faceVertexCounts = [{}]",
+            join(indices.chunks_exact(3).map(|_| "3"))
+        ));
+        out.line(format!(
+            "int[] faceVertexIndices = [{}]",
+            join(indices.iter())
+        ));
+        out.line(format!(
+            "point3f[] points = [{}]",
+            join(points.iter().map(format_vec3))
+        ));
+        if let Some(normals) = reader.read_normals() {
+            out.line(format!(
+                "normal3f[] normals = [{}] (",
+                join(normals.map(|n| format_vec3(&n)))
+            ));
+            out.line("    interpolation = \"vertex\"");
+            out.line(")");
+        }
+        let mut set = 0;
+        while let Some(uvs) = reader.read_tex_coords(set) {
+            // glTF places the UV origin at the top-left, USD at the bottom-left.
+            let st = uvs.into_f32().map(|[u, v]| format!("({}, {})", u, 1.0 - v));
+            out.line(format!(
+                "texCoord2f[] primvars:{} = [{}] (",
+                primvar_name(set),
+                join(st)
+            ));
+            out.line("    interpolation = \"vertex\"");
+            out.line(")");
+            set += 1;
+        }
+        if let Some(colors) = reader.read_colors(0) {
+            let colors = colors.into_rgb_f32();
+            out.line(format!(
+                "color3f[] primvars:displayColor = [{}] (",
+                join(colors.map(|c| format_vec3(&c)))
+            ));
+            out.line("    interpolation = \"vertex\"");
+            out.line(")");
+        }
+        out.line("uniform token subdivisionScheme = \"none\"");
+
+        if let Some(material) = material {
+            let path = self.material_path(material);
+            self.out.line(format!("rel material:binding = <{}>", path));
+        }
+        if let Some(skin) = skin {
+            self.write_skin_binding(&reader, &skin, name)?;
+        }
+
+        self.out.end();
+        Ok(())
+    }
+
+    fn write_skin_binding<'s, F>(
+        &mut self,
+        reader: &gltf::mesh::Reader<'s, 's, F>,
+        skin: &gltf::Skin<'_>,
+        name: &str,
+    ) -> Result<()>
+    where
+        F: Clone + Fn(gltf::Buffer<'s>) -> Option<&'s [u8]>,
+    {
+        let order = self.joint_order(skin);
+        let (Some(joints), Some(weights)) = (reader.read_joints(0), reader.read_weights(0)) else {
+            return Ok(());
+        };
+        let indices = joints
+            .into_u16()
+            .flatten()
+            .map(|j| {
+                order.get(j as usize).copied().ok_or_else(|| {
+                    Error::ImportError(format!(
+                        "mesh {:?} uses joint {} but its skin has {} joints",
+                        name,
+                        j,
+                        order.len()
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let weights = weights.into_f32().flatten();
+
+        let out = &mut self.out;
+        out.line(format!(
+            "int[] primvars:skel:jointIndices = [{}] (",
+            join(indices.iter())
+        ));
+        out.line("    elementSize = 4");
+        out.line("    interpolation = \"vertex\"");
+        out.line(")");
+        out.line(format!(
+            "float[] primvars:skel:jointWeights = [{}] (",
+            join(weights)
+        ));
+        out.line("    elementSize = 4");
+        out.line("    interpolation = \"vertex\"");
+        out.line(")");
+        out.line(format!(
+            "matrix4d primvars:skel:geomBindTransform = {}",
+            format_matrix(&xform::identity())
+        ));
+        out.line(format!(
+            "rel skel:skeleton = <{}>",
+            self.skeletons[skin.index()]
+        ));
+        Ok(())
+    }
+
+    /// Returns the new index of each joint of a skin, ordering joints so
+    /// parents come before their children as UsdSkel requires.
+    fn joint_order(&mut self, skin: &gltf::Skin<'_>) -> Vec<usize> {
+        if self.joint_orders.len() <= skin.index() {
+            self.joint_orders = self
+                .document
+                .skins()
+                .map(|skin| {
+                    let joints: Vec<usize> = skin.joints().map(|j| j.index()).collect();
+                    let parents = joint_parents(self.document, &joints);
+                    let depth = |mut joint: usize| {
+                        let mut depth = 0;
+                        while let Some(parent) = parents[joint] {
+                            joint = parent;
+                            depth += 1;
+                        }
+                        depth
+                    };
+                    let mut sorted: Vec<usize> = (0..joints.len()).collect();
+                    sorted.sort_by_key(|&j| depth(j));
+                    let mut order = vec![0; joints.len()];
+                    for (new, &old) in sorted.iter().enumerate() {
+                        order[old] = new;
+                    }
+                    order
+                })
+                .collect();
+        }
+        self.joint_orders[skin.index()].clone()
+    }
+
+    fn write_skeleton(&mut self, skin: &gltf::Skin<'_>, name: &str) {
+        let joints: Vec<gltf::Node<'_>> = skin.joints().collect();
+        let indices: Vec<usize> = joints.iter().map(|j| j.index()).collect();
+        let parents = joint_parents(self.document, &indices);
+        let order = self.joint_order(skin);
+
+        // Joint paths are built from joint names along the joint hierarchy.
+        let mut tokens = vec![String::new(); joints.len()];
+        let mut used = HashSet::new();
+        let mut by_depth: Vec<usize> = (0..joints.len()).collect();
+        by_depth.sort_by_key(|&j| order[j]);
+        for &j in &by_depth {
+            let base = sanitize(joints[j].name().unwrap_or(&format!("joint{}", j)));
+            let parent = parents[j].map(|p| tokens[p].clone());
+            let mut token = match &parent {
+                Some(parent) => format!("{}/{}", parent, base),
+                None => base.clone(),
+            };
+            let mut n = 1;
+            while !used.insert(token.clone()) {
+                token = match &parent {
+                    Some(parent) => format!("{}/{}_{}", parent, base, n),
+                    None => format!("{}_{}", base, n),
+                };
+                n += 1;
+            }
+            tokens[j] = token;
+        }
+
+        let reader = skin.reader(|buffer| Some(&self.buffers[buffer.index()]));
+        let inverse_binds: Vec<[[f32; 4]; 4]> = reader
+            .read_inverse_bind_matrices()
+            .map(|m| m.collect())
+            .unwrap_or_default();
+        let mut bind = Vec::new();
+        let mut rest = Vec::new();
+        for &j in &by_depth {
+            let inverse = inverse_binds
+                .get(j)
+                .map(to_f64)
+                .unwrap_or_else(xform::identity);
+            bind.push(xform::invert(&inverse).unwrap_or_else(xform::identity));
+
+            // Rest transforms are relative to the parent joint, which need
+            // not be the node's direct parent.
+            let world = self.world_of(indices[j]);
+            let local = match parents[j] {
+                Some(p) => {
+                    let parent = self.world_of(indices[p]);
+                    matrix_multiply(
+                        world,
+                        xform::invert(&parent).unwrap_or_else(xform::identity),
+                    )
+                }
+                None => world,
+            };
+            rest.push(local);
+        }
+
+        self.out.begin(&format!("def Skeleton \"{}\"", name), &[]);
+        self.out.line(format!(
+            "uniform token[] joints = [{}]",
+            join(by_depth.iter().map(|&j| format!("\"{}\"", tokens[j])))
+        ));
+        self.out.line(format!(
+            "uniform matrix4d[] bindTransforms = [{}]",
+            join(bind.iter().map(format_matrix))
+        ));
+        self.out.line(format!(
+            "uniform matrix4d[] restTransforms = [{}]",
+            join(rest.iter().map(format_matrix))
+        ));
+        self.out.end();
+    }
+
+    fn world_of(&self, node: usize) -> [[f64; 4]; 4] {
+        self.world_transforms
+            .get(&node)
+            .copied()
+            .unwrap_or_else(xform::identity)
+    }
+
+    fn material_path(&mut self, material: usize) -> String {
+        if self.materials.is_empty() {
+            let mut names = UniqueNames::default();
+            self.materials = self
+                .document
+                .materials()
+                .map(|m| {
+                    let name = m
+                        .name()
+                        .map(String::from)
+                        .unwrap_or_else(|| format!("Material_{}", m.index().unwrap_or(0)));
+                    format!("{}/Materials/{}", ROOT, names.get(&name))
+                })
+                .collect();
+        }
+        self.materials[material].clone()
+    }
+
+    /// Writes every material as a UsdPreviewSurface network.
+    fn write_materials(&mut self, scope: &str) {
+        self.out.begin(&format!("def Scope \"{}\"", scope), &[]);
+        let materials: Vec<gltf::Material<'_>> = self.document.materials().collect();
+        for material in materials {
+            let Some(index) = material.index() else {
+                continue;
+            };
+            let path = self.material_path(index);
+            let name = path.rsplit('/').next().unwrap_or_default().to_string();
+            self.write_material(&material, &path, &name);
+        }
+        self.out.end();
+    }
+
+    fn write_material(&mut self, material: &gltf::Material<'_>, path: &str, name: &str) {
+        use gltf::material::AlphaMode;
+
+        let pbr = material.pbr_metallic_roughness();
+        let [r, g, b, a] = pbr.base_color_factor();
+        let mut inputs = vec![
+            format!("color3f inputs:diffuseColor = ({}, {}, {})", r, g, b),
+            format!("float inputs:opacity = {}", a),
+            format!("float inputs:metallic = {}", pbr.metallic_factor()),
+            format!("float inputs:roughness = {}", pbr.roughness_factor()),
+            format!(
+                "color3f inputs:emissiveColor = {}",
+                format_vec3(&material.emissive_factor())
+            ),
+        ];
+        if material.alpha_mode() == AlphaMode::Mask {
+            inputs.push(format!(
+                "float inputs:opacityThreshold = {}",
+                material.alpha_cutoff().unwrap_or(0.5)
+            ));
+        }
+
+        let info = |t: gltf::texture::Info<'_>| (t.texture().source().index(), t.tex_coord());
+        let base_color_outputs: &[(&str, &str)] = if material.alpha_mode() == AlphaMode::Opaque {
+            &[("color3f inputs:diffuseColor", "rgb")]
+        } else {
+            &[
+                ("color3f inputs:diffuseColor", "rgb"),
+                ("float inputs:opacity", "a"),
+            ]
+        };
+        // glTF packs roughness in green and metallic in blue.
+        let slots = [
+            (
+                TextureSlot::BaseColor,
+                pbr.base_color_texture().map(info),
+                base_color_outputs,
+            ),
+            (
+                TextureSlot::MetallicRoughness,
+                pbr.metallic_roughness_texture().map(info),
+                &[
+                    ("float inputs:roughness", "g"),
+                    ("float inputs:metallic", "b"),
+                ][..],
+            ),
+            (
+                TextureSlot::Normal,
+                material
+                    .normal_texture()
+                    .map(|t| (t.texture().source().index(), t.tex_coord())),
+                &[("normal3f inputs:normal", "rgb")][..],
+            ),
+            (
+                TextureSlot::Occlusion,
+                material
+                    .occlusion_texture()
+                    .map(|t| (t.texture().source().index(), t.tex_coord())),
+                &[("float inputs:occlusion", "r")][..],
+            ),
+            (
+                TextureSlot::Emissive,
+                material.emissive_texture().map(info),
+                &[("color3f inputs:emissiveColor", "rgb")][..],
+            ),
+        ];
+
+        // Only textures whose image could be imported are connected.
+        let mut textures = Vec::new();
+        for (slot, texture, outputs) in slots {
+            let Some((image, tex_coord)) = texture else {
+                continue;
+            };
+            let Some(Some(file)) = self.textures.get(image) else {
+                continue;
+            };
+            for (input, output) in outputs {
+                inputs.push(format!(
+                    "{}.connect = <{}/{}.outputs:{}>",
+                    input,
+                    path,
+                    slot.shader_name(),
+                    output
+                ));
+            }
+            textures.push((slot, file.clone(), tex_coord));
+        }
+
+        self.out.begin(&format!("def Material \"{}\"", name), &[]);
+        self.out.line(format!(
+            "token outputs:surface.connect = <{}/PreviewSurface.outputs:surface>",
+            path
+        ));
+
+        self.out.begin("def Shader \"PreviewSurface\"", &[]);
+        self.out
+            .line("uniform token info:id = \"UsdPreviewSurface\"");
+        for input in &inputs {
+            self.out.line(input);
+        }
+        self.out.line("token outputs:surface");
+        self.out.end();
+
+        let mut tex_coords = Vec::new();
+        for (slot, file, tex_coord) in &textures {
+            self.write_texture(path, *slot, file, *tex_coord);
+            if !tex_coords.contains(tex_coord) {
+                tex_coords.push(*tex_coord);
+            }
+        }
+        for tex_coord in tex_coords {
+            self.out
+                .begin(&format!("def Shader \"TexCoordReader{}\"", tex_coord), &[]);
+            self.out
+                .line("uniform token info:id = \"UsdPrimvarReader_float2\"");
+            self.out.line(format!(
+                "string inputs:varname = \"{}\"",
+                primvar_name(tex_coord)
+            ));
+            self.out.line("float2 outputs:result");
+            self.out.end();
+        }
+
+        self.out.end();
+    }
+
+    fn write_texture(&mut self, material: &str, slot: TextureSlot, file: &str, tex_coord: u32) {
+        self.out
+            .begin(&format!("def Shader \"{}\"", slot.shader_name()), &[]);
+        self.out.line("uniform token info:id = \"UsdUVTexture\"");
+        self.out.line(format!("asset inputs:file = @{}@", file));
+        self.out.line(format!(
+            "float2 inputs:st.connect = <{}/TexCoordReader{}.outputs:result>",
+            material, tex_coord
+        ));
+        let color_space = match slot {
+            TextureSlot::BaseColor | TextureSlot::Emissive => "sRGB",
+            _ => "raw",
+        };
+        self.out.line(format!(
+            "token inputs:sourceColorSpace = \"{}\"",
+            color_space
+        ));
+        if slot == TextureSlot::Normal {
+            // Normal maps store [-1, 1] in [0, 1].
+            self.out.line("float4 inputs:scale = (2, 2, 2, 1)");
+            self.out.line("float4 inputs:bias = (-1, -1, -1, 0)");
+        }
+        self.out.line("token inputs:wrapS = \"repeat\"");
+        self.out.line("token inputs:wrapT = \"repeat\"");
+        for output in [
+            "float3 outputs:rgb",
+            "float outputs:r",
+            "float outputs:g",
+            "float outputs:b",
+            "float outputs:a",
+        ] {
+            self.out.line(output);
+        }
+        self.out.end();
+    }
+}
+
+/// The glTF material textures, each written as a UsdUVTexture shader.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TextureSlot {
+    BaseColor,
+    MetallicRoughness,
+    Normal,
+    Occlusion,
+    Emissive,
+}
+
+impl TextureSlot {
+    fn shader_name(self) -> &'static str {
+        match self {
+            TextureSlot::BaseColor => "BaseColorTexture",
+            TextureSlot::MetallicRoughness => "MetallicRoughnessTexture",
+            TextureSlot::Normal => "NormalTexture",
+            TextureSlot::Occlusion => "OcclusionTexture",
+            TextureSlot::Emissive => "EmissiveTexture",
+        }
+    }
+}
+
+fn is_triangles(mode: gltf::mesh::Mode) -> bool {
+    use gltf::mesh::Mode;
+    matches!(
+        mode,
+        Mode::Triangles | Mode::TriangleStrip | Mode::TriangleFan
+    )
+}
+
+/// Converts triangle strip and fan indices to a triangle list, keeping the
+/// winding order glTF specifies.
+fn triangle_list(mode: gltf::mesh::Mode, indices: Vec<u32>) -> Vec<u32> {
+    use gltf::mesh::Mode;
+    let triangles = indices.len().saturating_sub(2);
+    match mode {
+        Mode::TriangleStrip => (0..triangles)
+            .flat_map(|i| {
+                let odd = i % 2;
+                [indices[i], indices[i + 1 + odd], indices[i + 2 - odd]]
+            })
+            .collect(),
+        Mode::TriangleFan => (0..triangles)
+            .flat_map(|i| [indices[i + 1], indices[i + 2], indices[0]])
+            .collect(),
+        _ => indices,
+    }
+}
+
+/// Returns the primvar a glTF `TEXCOORD_n` set is written as.
+fn primvar_name(tex_coord: u32) -> String {
+    match tex_coord {
+        0 => "st".to_string(),
+        n => format!("st{}", n),
+    }
+}
+
+/// Returns the nearest ancestor of each joint that is also a joint of the
+/// same skin, by position in `joints`.
+fn joint_parents(document: &gltf::Document, joints: &[usize]) -> Vec<Option<usize>> {
+    let mut node_parents = HashMap::new();
+    for node in document.nodes() {
+        for child in node.children() {
+            node_parents.insert(child.index(), node.index());
+        }
+    }
+    joints
+        .iter()
+        .map(|&joint| {
+            let mut node = joint;
+            while let Some(&parent) = node_parents.get(&node) {
+                if let Some(position) = joints.iter().position(|&j| j == parent) {
+                    return Some(position);
+                }
+                node = parent;
+            }
+            None
+        })
+        .collect()
+}
+
+/// Returns a node's local transform as a USD row-vector matrix. glTF
+/// matrices are column-major, so their columns are USD's rows.
+fn node_matrix(node: &gltf::Node<'_>) -> [[f64; 4]; 4] {
+    to_f64(&node.transform().matrix())
+}
+
+fn to_f64(m: &[[f32; 4]; 4]) -> [[f64; 4]; 4] {
+    m.map(|row| row.map(f64::from))
+}
+
+fn node_name(node: &gltf::Node<'_>) -> String {
+    node.name()
+        .map(String::from)
+        .unwrap_or_else(|| format!("Node_{}", node.index()))
+}
+
+fn mesh_name(node: &gltf::Node<'_>) -> String {
+    node.mesh()
+        .map(|mesh| {
+            mesh.name()
+                .map(String::from)
+                .unwrap_or_else(|| format!("Mesh_{}", mesh.index()))
+        })
+        .unwrap_or_default()
+}
+
+/// Makes a valid USD prim name: letters, digits and underscores, not
+/// starting with a digit.
+fn sanitize(name: &str) -> String {
+    let mut sanitized: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if !sanitized.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+        sanitized.insert(0, '_');
+    }
+    sanitized
+}
+
+/// Hands out unique sanitized names among siblings.
+#[derive(Default)]
+struct UniqueNames {
+    used: HashSet<String>,
+}
+
+impl UniqueNames {
+    fn get(&mut self, name: &str) -> String {
+        let base = sanitize(name);
+        let mut name = base.clone();
+        let mut n = 1;
+        while !self.used.insert(name.clone()) {
+            name = format!("{}_{}", base, n);
+            n += 1;
+        }
+        name
+    }
+}
+
+/// An indenting USDA text writer.
+#[derive(Default)]
+struct Usda {
+    text: String,
+    depth: usize,
+}
+
+impl Usda {
+    fn line(&mut self, line: impl AsRef<str>) {
+        let line = line.as_ref();
+        if !line.is_empty() {
+            for _ in 0..self.depth {
+                self.text.push_str("    ");
+            }
+        }
+        self.text.push_str(line);
+        self.text.push('\n');
+    }
+
+    /// Opens a prim with optional metadata lines.
+    fn begin(&mut self, header: &str, metadata: &[String]) {
+        if metadata.is_empty() {
+            self.line(header);
+        } else {
+            self.line(format!("{} (", header));
+            self.depth += 1;
+            for entry in metadata {
+                self.line(entry);
+            }
+            self.depth -= 1;
+            self.line(")");
+        }
+        self.line("{");
+        self.depth += 1;
+    }
+
+    fn end(&mut self) {
+        self.depth -= 1;
+        self.line("}");
+        if self.depth > 0 {
+            self.line("");
+        }
+    }
+}
+
+fn join<T: std::fmt::Display>(items: impl Iterator<Item = T>) -> String {
+    let mut out = String::new();
+    for (i, item) in items.enumerate() {
+        if i > 0 {
+            out.push_str(", ");
+        }
+        let _ = write!(out, "{}", item);
+    }
+    out
+}
+
+fn format_vec3(v: &[f32; 3]) -> String {
+    format!("({}, {}, {})", v[0], v[1], v[2])
+}
+
+fn format_matrix(m: &[[f64; 4]; 4]) -> String {
+    let rows = m
+        .iter()
+        .map(|row| format!("({}, {}, {}, {})", row[0], row[1], row[2], row[3]));
+    format!("( {} )", join(rows))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::{self, PrimSpec};
+    use crate::value::Value;
+    use gltf_json as json;
+
+    fn bytes<T: Copy>(values: &[T], to_le: fn(T) -> Vec<u8>) -> Vec<u8> {
+        values.iter().flat_map(|&v| to_le(v)).collect()
+    }
+
+    /// Packs a glTF document and its binary buffer as GLB.
+    fn glb(document: &str, buffer: Vec<u8>) -> Vec<u8> {
+        let root: json::Root = json::deserialize::from_str(document).unwrap();
+        crate::export::gltf::to_glb(&root, buffer).unwrap()
+    }
+
+    fn child<'a>(prim: &'a PrimSpec, name: &str) -> &'a PrimSpec {
+        prim.children.iter().find(|c| c.name == name).unwrap()
+    }
+
+    /// A textured triangle bound to a red material.
+    fn triangle() -> Vec<u8> {
+        let mut buffer = bytes(&[0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], |v| {
+            v.to_le_bytes().to_vec()
+        });
+        buffer.extend(bytes(&[0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0], |v| {
+            v.to_le_bytes().to_vec()
+        }));
+        buffer.extend(bytes(&[0u16, 1, 2], |v| v.to_le_bytes().to_vec()));
+        glb(
+            r#"{
+                "asset": {"version": "2.0"},
+                "scene": 0,
+                "scenes": [{"nodes": [0]}],
+                "nodes": [{"name": "My Triangle", "mesh": 0, "translation": [0, 0, 5]}],
+                "meshes": [{"name": "Tri", "primitives": [{
+                    "attributes": {"POSITION": 0, "TEXCOORD_0": 1},
+                    "indices": 2,
+                    "material": 0
+                }]}],
+                "materials": [{"name": "Red", "pbrMetallicRoughness": {
+                    "baseColorFactor": [1, 0, 0, 1],
+                    "baseColorTexture": {"index": 0},
+                    "metallicFactor": 0
+                }}],
+                "textures": [{"source": 0}],
+                "images": [{"uri": "red.png"}],
+                "accessors": [
+                    {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                     "min": [0, 0, 0], "max": [1, 1, 0]},
+                    {"bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC2"},
+                    {"bufferView": 2, "componentType": 5123, "count": 3, "type": "SCALAR"}
+                ],
+                "bufferViews": [
+                    {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+                    {"buffer": 0, "byteOffset": 36, "byteLength": 24},
+                    {"buffer": 0, "byteOffset": 60, "byteLength": 6}
+                ],
+                "buffers": [{"byteLength": 66}]
+            }"#,
+            buffer,
+        )
+    }
+
+    #[test]
+    fn test_mesh_and_material() {
+        let data = triangle();
+        let text = GltfImporter::new().to_usda_from_slice(&data).unwrap();
+        let layer = usda::parse_layer(&text).unwrap();
+        let root = &layer.prims[0];
+        assert_eq!(
+            (root.type_name.as_str(), root.name.as_str()),
+            ("Xform", "Root")
+        );
+
+        let node = child(root, "My_Triangle");
+        match node.value("xformOp:transform") {
+            Some(Value::Matrix4d(m)) => assert_eq!(m[3], [0.0, 0.0, 5.0, 1.0]),
+            other => panic!("unexpected transform {:?}", other),
+        }
+
+        let mesh = child(node, "Tri");
+        assert_eq!(mesh.type_name, "Mesh");
+        assert_eq!(
+            mesh.value("faceVertexIndices"),
+            Some(Value::IntArray(vec![0, 1, 2]))
+        );
+        // V is flipped to USD's bottom-left origin.
+        match mesh.value("primvars:st") {
+            Some(Value::Float2Array(st)) => assert_eq!(st[0], [0.0, 1.0]),
+            other => panic!("unexpected st {:?}", other),
+        }
+        let binding = mesh.property("material:binding").unwrap();
+        assert_eq!(binding.targets, ["/Root/Materials/Red"]);
+
+        let material = child(child(root, "Materials"), "Red");
+        let surface = child(material, "PreviewSurface");
+        assert_eq!(
+            surface.value("info:id"),
+            Some(Value::Token("UsdPreviewSurface".into()))
+        );
+        assert_eq!(surface.value("inputs:metallic"), Some(Value::Float(0.0)));
+        let diffuse = surface.property("inputs:diffuseColor").unwrap();
+        assert_eq!(
+            diffuse.targets,
+            ["/Root/Materials/Red/BaseColorTexture.outputs:rgb"]
+        );
+        let texture = child(material, "BaseColorTexture");
+        assert_eq!(
+            texture.value("inputs:file"),
+            Some(Value::Asset("red.png".into()))
+        );
+        let reader = child(material, "TexCoordReader0");
+        assert_eq!(
+            reader.value("inputs:varname"),
+            Some(Value::String("st".into()))
+        );
+    }
+
+    /// A triangle skinned to a two-joint skin with the given `JOINTS_0`.
+    fn skinned_triangle(joints: [u8; 4]) -> Vec<u8> {
+        let mut buffer = bytes(&[0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0], |v| {
+            v.to_le_bytes().to_vec()
+        });
+        buffer.extend(joints.repeat(3));
+        buffer.extend(bytes(&[0.5f32, 0.5, 0.0, 0.0].repeat(3), |v| {
+            v.to_le_bytes().to_vec()
+        }));
+        // Joints are listed child first: Spine at y = 2, then Hips at y = 1.
+        for y in [-2.0f32, -1.0] {
+            let mut inverse_bind = [0.0f32; 16];
+            for i in 0..4 {
+                inverse_bind[i * 5] = 1.0;
+            }
+            inverse_bind[13] = y;
+            buffer.extend(bytes(&inverse_bind, |v| v.to_le_bytes().to_vec()));
+        }
+        glb(
+            r#"{
+                "asset": {"version": "2.0"},
+                "scene": 0,
+                "scenes": [{"nodes": [0, 1]}],
+                "nodes": [
+                    {"name": "Body", "mesh": 0, "skin": 0},
+                    {"name": "Hips", "translation": [0, 1, 0], "children": [2]},
+                    {"name": "Spine", "translation": [0, 1, 0]}
+                ],
+                "meshes": [{"name": "Body", "primitives": [{
+                    "attributes": {"POSITION": 0, "JOINTS_0": 1, "WEIGHTS_0": 2}
+                }]}],
+                "skins": [{"name": "Rig", "joints": [2, 1], "inverseBindMatrices": 3}],
+                "accessors": [
+                    {"bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3",
+                     "min": [0, 0, 0], "max": [1, 1, 0]},
+                    {"bufferView": 1, "componentType": 5121, "count": 3, "type": "VEC4"},
+                    {"bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC4"},
+                    {"bufferView": 3, "componentType": 5126, "count": 2, "type": "MAT4"}
+                ],
+                "bufferViews": [
+                    {"buffer": 0, "byteOffset": 0, "byteLength": 36},
+                    {"buffer": 0, "byteOffset": 36, "byteLength": 12},
+                    {"buffer": 0, "byteOffset": 48, "byteLength": 48},
+                    {"buffer": 0, "byteOffset": 96, "byteLength": 128}
+                ],
+                "buffers": [{"byteLength": 224}]
+            }"#,
+            buffer,
+        )
+    }
+
+    #[test]
+    fn test_skin() {
+        let data = skinned_triangle([0, 1, 0, 0]);
+        let text = GltfImporter::new().to_usda_from_slice(&data).unwrap();
+        let layer = usda::parse_layer(&text).unwrap();
+        let root = &layer.prims[0];
+        assert_eq!(root.type_name, "SkelRoot");
+
+        // Joints are reordered parents first.
+        let skeleton = child(root, "Rig");
+        assert_eq!(
+            skeleton.value("joints"),
+            Some(Value::TokenArray(vec!["Hips".into(), "Hips/Spine".into()]))
+        );
+        match skeleton.value("bindTransforms") {
+            Some(Value::Matrix4dArray(m)) => {
+                assert_eq!(m[0][3], [0.0, 1.0, 0.0, 1.0]);
+                assert_eq!(m[1][3], [0.0, 2.0, 0.0, 1.0]);
+            }
+            other => panic!("unexpected bindTransforms {:?}", other),
+        }
+        match skeleton.value("restTransforms") {
+            Some(Value::Matrix4dArray(m)) => assert_eq!(m[1][3], [0.0, 1.0, 0.0, 1.0]),
+            other => panic!("unexpected restTransforms {:?}", other),
+        }
+
+        // The skinned mesh sits directly under the SkelRoot.
+        let mesh = root
+            .children
+            .iter()
+            .find(|c| c.type_name == "Mesh")
+            .unwrap();
+        // Indices are remapped to the reordered joints, including unused
+        // zero-weight influences.
+        assert_eq!(
+            mesh.value("primvars:skel:jointIndices"),
+            Some(Value::IntArray([1, 0, 1, 1].repeat(3)))
+        );
+        let skeleton = mesh.property("skel:skeleton").unwrap();
+        assert_eq!(skeleton.targets, ["/Root/Rig"]);
+    }
+
+    #[test]
+    fn test_import_as_stage() {
+        // The written layer loads back through the stage.
+        let stage = GltfImporter::new().import_slice(&triangle()).unwrap();
+        let mesh = stage.prim_at_path("/Root/My_Triangle/Tri").unwrap();
+        assert!(mesh.is_mesh());
+
+        let materials = crate::material::MaterialExtractor::new(&stage).collect();
+        assert_eq!(materials.len(), 1);
+        assert_eq!(materials[0].path, "/Root/Materials/Red");
+        assert_eq!(materials[0].diffuse_texture.as_deref(), Some("red.png"));
+    }
+
+    #[test]
+    fn test_texture_paths_relative_to_output() {
+        let text = GltfImporter::new()
+            .output_dir("out")
+            .to_usda_from_slice(&triangle())
+            .unwrap();
+        assert!(text.contains("asset inputs:file = @../red.png@"));
+
+        assert_eq!(
+            relative_path(Path::new("/a/b/c.png"), Path::new("/a/d")),
+            Some(PathBuf::from("../b/c.png"))
+        );
+        assert_eq!(
+            relative_path(Path::new("/a/c.png"), Path::new("/a")),
+            Some(PathBuf::from("c.png"))
+        );
+    }
+
+    /// A quad as one primitive of the given mode, without indices.
+    fn quad(mode: u32) -> Vec<u8> {
+        let buffer = bytes(
+            &[
+                0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1.0, 1.0, 0.0,
+            ],
+            |v| v.to_le_bytes().to_vec(),
+        );
+        glb(
+            &format!(
+                r#"{{
+                    "asset": {{"version": "2.0"}},
+                    "scene": 0,
+                    "scenes": [{{"nodes": [0]}}],
+                    "nodes": [{{"name": "Quad", "mesh": 0}}],
+                    "meshes": [{{"name": "Quad", "primitives": [{{
+                        "attributes": {{"POSITION": 0}},
+                        "mode": {}
+                    }}]}}],
+                    "accessors": [
+                        {{"bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3",
+                         "min": [0, 0, 0], "max": [1, 1, 0]}}
+                    ],
+                    "bufferViews": [{{"buffer": 0, "byteOffset": 0, "byteLength": 48}}],
+                    "buffers": [{{"byteLength": 48}}]
+                }}"#,
+                mode
+            ),
+            buffer,
+        )
+    }
+
+    #[test]
+    fn test_strips_and_fans() {
+        let indices = |mode| {
+            let text = GltfImporter::new().to_usda_from_slice(&quad(mode)).unwrap();
+            let layer = usda::parse_layer(&text).unwrap();
+            child(child(&layer.prims[0], "Quad"), "Quad").value("faceVertexIndices")
+        };
+        // Strip triangles alternate winding to stay consistent.
+        assert_eq!(indices(5), Some(Value::IntArray(vec![0, 1, 2, 1, 3, 2])));
+        assert_eq!(indices(6), Some(Value::IntArray(vec![1, 2, 0, 2, 3, 0])));
+
+        // Points and lines are not meshes.
+        assert!(matches!(
+            GltfImporter::new().to_usda_from_slice(&quad(0)),
+            Err(Error::ImportError(_))
+        ));
+        // Four unindexed vertices are not a triangle list.
+        assert!(matches!(
+            GltfImporter::new().to_usda_from_slice(&quad(4)),
+            Err(Error::ImportError(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_joint_index() {
+        // The skin has two joints.
+        assert!(matches!(
+            GltfImporter::new().to_usda_from_slice(&skinned_triangle([0, 2, 0, 0])),
+            Err(Error::ImportError(_))
+        ));
+    }
+}
@@ -0,0 +1,256 @@
+//! UsdGeom PointInstancer support.
+
+use crate::mesh::matrix_multiply;
+use crate::stage::Stage;
+use crate::usda::PrimSpec;
+use crate::value::Value;
+use crate::xform;
+
+/// A point instancer extracted from a USD PointInstancer prim.
+///
+/// Per-instance tracks hold time samples of instance-ordered arrays. An
+/// attribute with only a default value is stored as a single sample at
+/// time 0.
+#[derive(Debug, Clone, Default)]
+pub struct PointInstancer {
+    /// The name of the instancer.
+    pub name: String,
+    /// The prim path of the instancer.
+    pub path: String,
+    /// Paths of the prototype prims (`prototypes`).
+    pub prototypes: Vec<String>,
+    /// Prototype index of each instance (`protoIndices`).
+    pub proto_indices: Vec<i32>,
+    /// Stable instance ids (`ids`); instances are identified by index when
+    /// `None`.
+    pub ids: Option<Vec<i64>>,
+    /// Instance positions.
+    pub positions: Vec<(f64, Vec<[f32; 3]>)>,
+    /// Instance orientations as quaternions `(x, y, z, w)`.
+    pub orientations: Vec<(f64, Vec<[f32; 4]>)>,
+    /// Instance scales.
+    pub scales: Vec<(f64, Vec<[f32; 3]>)>,
+    /// Ids of instances that are not rendered (`invisibleIds`).
+    pub invisible_ids: Vec<i64>,
+    /// World transformation matrix (4x4).
+    pub world_transform: [[f64; 4]; 4],
+}
+
+impl PointInstancer {
+    /// Returns the number of instances.
+    pub fn instance_count(&self) -> usize {
+        self.proto_indices.len()
+    }
+
+    /// Returns the id of an instance: its authored id, or its index.
+    pub fn instance_id(&self, instance: usize) -> i64 {
+        self.ids
+            .as_ref()
+            .and_then(|ids| ids.get(instance).copied())
+            .unwrap_or(instance as i64)
+    }
+
+    /// Returns true if an instance is rendered.
+    pub fn is_visible(&self, instance: usize) -> bool {
+        !self.invisible_ids.contains(&self.instance_id(instance))
+    }
+
+    /// Returns the prototype path of an instance.
+    pub fn prototype_of(&self, instance: usize) -> Option<&str> {
+        let index = usize::try_from(*self.proto_indices.get(instance)?).ok()?;
+        self.prototypes.get(index).map(String::as_str)
+    }
+
+    /// Returns the transform of every instance at `time`, relative to the
+    /// instancer.
+    ///
+    /// Each instance is scaled, then rotated, then translated. Positions and
+    /// scales are interpolated linearly and orientations spherically between
+    /// the surrounding samples. The transform of the prototype prim itself
+    /// is not included.
+    pub fn instance_transforms(&self, time: f64) -> Vec<[[f64; 4]; 4]> {
+        let positions = xform::sample_track(&self.positions, time, xform::lerp3);
+        let orientations = xform::sample_track(&self.orientations, time, xform::slerp);
+        let scales = xform::sample_track(&self.scales, time, xform::lerp3);
+
+        (0..self.instance_count())
+            .map(|i| {
+                let s = scales
+                    .get(i)
+                    .map_or_else(xform::identity, |s| xform::scale(s.map(|c| c as f64)));
+                let r = orientations.get(i).map_or_else(xform::identity, |q| {
+                    xform::quat_to_matrix(q.map(|c| c as f64))
+                });
+                let t = positions
+                    .get(i)
+                    .map_or_else(xform::identity, |p| xform::translation(p.map(|c| c as f64)));
+                matrix_multiply(matrix_multiply(s, r), t)
+            })
+            .collect()
+    }
+
+    /// Reads an instancer from a PointInstancer prim's spec.
+    pub(crate) fn from_spec(spec: &PrimSpec, path: &str) -> PointInstancer {
+        let ints = |name: &str| match spec.value(name) {
+            Some(Value::IntArray(v)) => Some(v.into_iter().map(i64::from).collect()),
+            Some(Value::Int64Array(v)) => Some(v),
+            _ => None,
+        };
+
+        PointInstancer {
+            name: spec.name.clone(),
+            path: path.to_string(),
+            prototypes: spec
+                .property("prototypes")
+                .map(|p| p.targets.clone())
+                .unwrap_or_default(),
+            proto_indices: match spec.value("protoIndices") {
+                Some(Value::IntArray(v)) => v,
+                _ => Vec::new(),
+            },
+            ids: ints("ids"),
+            positions: spec.track("positions", |v| match v {
+                Value::Float3Array(v) => Some(v),
+                _ => None,
+            }),
+            orientations: spec.track("orientations", |v| match v {
+                Value::Float4Array(v) => Some(v),
+                _ => None,
+            }),
+            scales: spec.track("scales", |v| match v {
+                Value::Float3Array(v) => Some(v),
+                _ => None,
+            }),
+            invisible_ids: ints("invisibleIds").unwrap_or_default(),
+            world_transform: xform::identity(),
+        }
+    }
+}
+
+/// Utility to extract point instancers from a USD stage.
+pub struct InstancerExtractor<'a> {
+    stage: &'a Stage,
+}
+
+impl<'a> InstancerExtractor<'a> {
+    /// Creates a new instancer extractor for the given stage.
+    pub fn new(stage: &'a Stage) -> Self {
+        InstancerExtractor { stage }
+    }
+
+    /// Returns an iterator over all point instancers in the stage.
+    pub fn instancers(&self) -> impl Iterator<Item = PointInstancer> + '_ {
+        let mut instancers = Vec::new();
+        self.stage.walk_transforms(|visit, _, world| {
+            if visit.spec.type_name == "PointInstancer" {
+                let mut instancer = PointInstancer::from_spec(visit.spec, &visit.path);
+                instancer.world_transform = *world;
+                instancers.push(instancer);
+            }
+        });
+        instancers.into_iter()
+    }
+
+    /// Extracts all point instancers and returns them as a vector.
+    pub fn collect(&self) -> Vec<PointInstancer> {
+        self.instancers().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::parse_prim;
+
+    #[test]
+    fn test_instance_transforms() {
+        let spec = parse_prim(
+            r#"def PointInstancer "Forest"
+{
+    int64[] ids = [10, 11, 12]
+    int64[] invisibleIds = [11]
+    quath[] orientations = [(1, 0, 0, 0), (0.7071, 0, 0, 0.7071), (1, 0, 0, 0)]
+    point3f[] positions = [(0, 0, 0), (5, 0, 0), (0, 0, 5)]
+    int[] protoIndices = [0, 1, 0]
+    rel prototypes = [</Forest/Protos/Pine>, </Forest/Protos/Oak>]
+    float3[] scales = [(1, 1, 1), (1, 1, 1), (2, 2, 2)]
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let instancer = PointInstancer::from_spec(&spec, "/Forest");
+        assert_eq!(instancer.instance_count(), 3);
+        assert_eq!(instancer.prototype_of(1), Some("/Forest/Protos/Oak"));
+        assert!(instancer.is_visible(0));
+        assert!(!instancer.is_visible(1));
+
+        let transforms = instancer.instance_transforms(0.0);
+        let p = xform::transform_point(&transforms[1], [1.0, 0.0, 0.0]);
+        assert!((p[0] - 5.0).abs() < 1e-3 && (p[1] - 1.0).abs() < 1e-3);
+        let p = xform::transform_point(&transforms[2], [1.0, 0.0, 0.0]);
+        assert_eq!(p, [2.0, 0.0, 5.0]);
+    }
+
+    const FOREST: &str = r#"#usda 1.0
+
+def PointInstancer "Forest"
+{
+    int64[] invisibleIds = [2]
+    point3f[] positions = [(0, 0, 0), (10, 0, 0), (20, 0, 0)]
+    int[] protoIndices = [0, 0, 0]
+    rel prototypes = </Forest/Protos/Tree>
+    double3 xformOp:translate = (0, 0, 100)
+    uniform token[] xformOpOrder = ["xformOp:translate"]
+
+    def Scope "Protos"
+    {
+        def Mesh "Tree"
+        {
+            int[] faceVertexCounts = [3]
+            int[] faceVertexIndices = [0, 1, 2]
+            point3f[] points = [(0, 0, 0), (1, 0, 0), (1, 1, 1)]
+            double3 xformOp:translate = (0, 1, 0)
+            uniform token[] xformOpOrder = ["xformOp:translate"]
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_mesh_extractor_instancing() {
+        use crate::mesh::{Instancing, MeshExtractor};
+
+        let stage = Stage::from_usda(FOREST.as_bytes()).unwrap();
+
+        // One copy per visible instance; id 2 is invisible.
+        let meshes = MeshExtractor::new(&stage).collect();
+        let copies: Vec<(&str, [f64; 4])> = meshes
+            .iter()
+            .map(|m| (m.name.as_str(), m.world_transform[3]))
+            .collect();
+        assert_eq!(
+            copies,
+            vec![
+                ("Tree_0", [0.0, 1.0, 100.0, 1.0]),
+                ("Tree_1", [10.0, 1.0, 100.0, 1.0]),
+            ]
+        );
+
+        // The prototype is kept once, relative to its parent.
+        let extractor = MeshExtractor::new(&stage).instancing(Instancing::Prototypes);
+        assert_eq!(extractor.meshes().count(), 0);
+        let instanced = extractor.instanced_meshes();
+        assert_eq!(instanced.len(), 1);
+        assert_eq!(instanced[0].instancer, "/Forest");
+        assert_eq!(
+            instanced[0].prototype.world_transform[3],
+            [0.0, 1.0, 0.0, 1.0]
+        );
+        let positions: Vec<[f64; 4]> = instanced[0].transforms.iter().map(|t| t[3]).collect();
+        assert_eq!(
+            positions,
+            vec![[0.0, 0.0, 100.0, 1.0], [10.0, 0.0, 100.0, 1.0]]
+        );
+    }
+}
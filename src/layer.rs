@@ -0,0 +1,309 @@
+//! USD layer inspection.
+//!
+//! A [`Layer`] holds what a single file authors, before composition: its
+//! metadata and its prim specs, including `over` and `class` prims and
+//! unselected variants.
+//!
+//! Note: USDA files are parsed directly. USDC and USDZ files are loaded with
+//! tinyusdz, which does not compose them, and read back from their USDA
+//! representation.
+
+use std::path::Path;
+
+use crate::attribute::Attribute;
+use crate::error::{Error, Result};
+use crate::prim::{self, Reference, Specifier, VariantSet};
+use crate::stage::Stage;
+use crate::usda::{self, find_meta, LayerSpec, PrimSpec};
+use crate::value::Value;
+
+/// A single USD layer, as authored.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::Layer;
+///
+/// let layer = Layer::open("shot.usda").unwrap();
+/// println!("sublayers: {:?}", layer.sublayers());
+/// for prim in layer.root_prims() {
+///     println!("{:?} {} ({})", prim.specifier(), prim.path(), prim.type_name());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Layer {
+    identifier: String,
+    spec: LayerSpec,
+}
+
+impl Layer {
+    /// Opens a layer from the filesystem.
+    ///
+    /// Automatically detects the format (USDA, USDC, or USDZ).
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let identifier = path
+            .to_str()
+            .ok_or_else(|| Error::InvalidPath("Path is not valid UTF-8".into()))?
+            .to_string();
+
+        let data = std::fs::read(path)?;
+        let mut layer = if data.starts_with(b"#usda") {
+            Self::from_usda(&data)?
+        } else {
+            Self::from_stage(&Stage::open(path)?)?
+        };
+        layer.identifier = identifier;
+        Ok(layer)
+    }
+
+    /// Reads a layer from USDA (ASCII) data in memory.
+    pub fn from_usda(data: &[u8]) -> Result<Self> {
+        let text = std::str::from_utf8(data)?;
+        Self::parse(text)
+    }
+
+    /// Reads a layer from USDC (binary Crate) data in memory.
+    pub fn from_usdc(data: &[u8]) -> Result<Self> {
+        Self::from_stage(&Stage::from_usdc(data)?)
+    }
+
+    /// Reads the root layer of USDZ (ZIP archive) data in memory.
+    pub fn from_usdz(data: &[u8]) -> Result<Self> {
+        Self::from_stage(&Stage::from_usdz(data)?)
+    }
+
+    fn from_stage(stage: &Stage) -> Result<Self> {
+        Self::parse(&stage.to_string()?)
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        Ok(Layer {
+            identifier: String::new(),
+            spec: usda::parse_layer(text).map_err(Error::ParseError)?,
+        })
+    }
+
+    /// Returns the path the layer was opened from, or an empty string for
+    /// layers read from memory.
+    pub fn identifier(&self) -> &str {
+        &self.identifier
+    }
+
+    /// Returns the keys of the layer metadata, in authored order.
+    pub fn metadata_keys(&self) -> Vec<&str> {
+        self.spec.metadata.iter().map(|m| m.key.as_str()).collect()
+    }
+
+    /// Returns a layer metadata value by key (e.g. "upAxis").
+    pub fn metadata(&self, key: &str) -> Option<Value> {
+        find_meta(&self.spec.metadata, key).map(usda::Node::to_untyped_value)
+    }
+
+    /// Returns the name of the default prim, if authored.
+    pub fn default_prim(&self) -> Option<&str> {
+        find_meta(&self.spec.metadata, "defaultPrim").and_then(usda::Node::as_str)
+    }
+
+    /// Returns the asset paths of the layer's sublayers, strongest first.
+    pub fn sublayers(&self) -> Vec<String> {
+        self.spec.sublayers()
+    }
+
+    /// Returns the root prim specs of the layer, in authored order.
+    pub fn root_prims(&self) -> Vec<LayerPrim<'_>> {
+        self.spec
+            .prims
+            .iter()
+            .map(|spec| LayerPrim::new(spec, String::new()))
+            .collect()
+    }
+
+    /// Returns the prim spec at an absolute path (e.g. "/World/Mesh").
+    ///
+    /// Prims inside variants are not searched.
+    pub fn prim_at_path(&self, path: &str) -> Option<LayerPrim<'_>> {
+        let mut names = path.strip_prefix('/')?.split('/');
+        let first = names.next()?;
+        let mut prim = self.root_prims().into_iter().find(|p| p.name() == first)?;
+        for name in names {
+            prim = prim.children().into_iter().find(|p| p.name() == name)?;
+        }
+        Some(prim)
+    }
+}
+
+/// A prim spec authored in a [`Layer`].
+#[derive(Debug, Clone)]
+pub struct LayerPrim<'a> {
+    spec: &'a PrimSpec,
+    path: String,
+}
+
+impl<'a> LayerPrim<'a> {
+    fn new(spec: &'a PrimSpec, parent: String) -> Self {
+        LayerPrim {
+            path: format!("{}/{}", parent, spec.name),
+            spec,
+        }
+    }
+
+    /// Returns the element name of the prim.
+    pub fn name(&self) -> &'a str {
+        &self.spec.name
+    }
+
+    /// Returns the full path of the prim within the layer.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns the authored type name, or an empty string for typeless prims.
+    pub fn type_name(&self) -> &'a str {
+        &self.spec.type_name
+    }
+
+    /// Returns how the prim is specified (`def`, `over` or `class`).
+    pub fn specifier(&self) -> Specifier {
+        Specifier::from_token(&self.spec.specifier).unwrap_or_default()
+    }
+
+    /// Returns the child prim specs, in authored order.
+    pub fn children(&self) -> Vec<LayerPrim<'a>> {
+        self.spec
+            .children
+            .iter()
+            .map(|child| LayerPrim::new(child, self.path.clone()))
+            .collect()
+    }
+
+    /// Returns the names of the properties authored on the prim.
+    pub fn property_names(&self) -> Vec<&'a str> {
+        self.spec
+            .properties
+            .iter()
+            .map(|p| p.name.as_str())
+            .collect()
+    }
+
+    /// Returns the attributes authored on the prim.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        self.spec
+            .properties
+            .iter()
+            .filter(|p| !p.is_relationship())
+            .map(Attribute::from_spec)
+            .collect()
+    }
+
+    /// Returns an attribute by name (e.g. "points").
+    pub fn attribute(&self, name: &str) -> Option<Attribute> {
+        self.spec
+            .property(name)
+            .filter(|p| !p.is_relationship())
+            .map(Attribute::from_spec)
+    }
+
+    /// Returns the targets of a relationship (e.g. "material:binding").
+    pub fn relationship_targets(&self, name: &str) -> Option<&'a [String]> {
+        self.spec
+            .property(name)
+            .filter(|p| p.is_relationship())
+            .map(|p| p.targets.as_slice())
+    }
+
+    /// Returns the keys of the prim metadata, in authored order.
+    pub fn metadata_keys(&self) -> Vec<&'a str> {
+        self.spec.metadata.iter().map(|m| m.key.as_str()).collect()
+    }
+
+    /// Returns a prim metadata value by key (e.g. "kind").
+    pub fn metadata(&self, key: &str) -> Option<Value> {
+        find_meta(&self.spec.metadata, key).map(usda::Node::to_untyped_value)
+    }
+
+    /// Returns the references authored on the prim.
+    pub fn references(&self) -> Vec<Reference> {
+        prim::references(self.spec, "references")
+    }
+
+    /// Returns the payloads authored on the prim.
+    pub fn payloads(&self) -> Vec<Reference> {
+        prim::references(self.spec, "payload")
+    }
+
+    /// Returns the paths of the prims this prim inherits from.
+    pub fn inherits(&self) -> Vec<String> {
+        prim::target_paths(self.spec, "inherits")
+    }
+
+    /// Returns the paths of the prims this prim specializes.
+    pub fn specializes(&self) -> Vec<String> {
+        prim::target_paths(self.spec, "specializes")
+    }
+
+    /// Returns the variant sets authored on the prim.
+    pub fn variant_sets(&self) -> Vec<VariantSet> {
+        prim::variant_sets(self.spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_specs() {
+        let layer = Layer::from_usda(
+            br#"#usda 1.0
+(
+    defaultPrim = "World"
+    metersPerUnit = 0.01
+    subLayers = [@./lighting.usda@]
+)
+
+def Xform "World" (
+    kind = "assembly"
+)
+{
+    over "Chair" (
+        prepend references = @./chair.usd@
+    )
+    {
+        rel material:binding = </World/Looks/Oak>
+        double3 xformOp:translate = (1, 0, 0)
+    }
+}
+
+class "_class_Prop"
+{
+}
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(layer.default_prim(), Some("World"));
+        assert_eq!(layer.metadata("metersPerUnit"), Some(Value::Double(0.01)));
+        assert_eq!(layer.sublayers(), vec!["./lighting.usda"]);
+
+        let roots = layer.root_prims();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[1].specifier(), Specifier::Class);
+        assert_eq!(
+            roots[0].metadata("kind"),
+            Some(Value::String("assembly".into()))
+        );
+
+        let chair = layer.prim_at_path("/World/Chair").unwrap();
+        assert_eq!(chair.path(), "/World/Chair");
+        assert_eq!(chair.specifier(), Specifier::Over);
+        assert_eq!(
+            chair.references()[0].asset_path.as_deref(),
+            Some("./chair.usd")
+        );
+        assert_eq!(
+            chair.relationship_targets("material:binding"),
+            Some(&["/World/Looks/Oak".to_string()][..])
+        );
+        assert_eq!(chair.attributes().len(), 1);
+    }
+}
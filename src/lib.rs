@@ -88,20 +88,53 @@
 //! ```
 
 pub mod attribute;
+pub mod bounds;
+pub mod camera;
+pub mod collection;
 pub mod error;
+pub mod export;
+pub mod import;
+pub mod instancer;
+pub mod layer;
+pub mod light;
 pub mod material;
 pub mod mesh;
+pub mod normalize;
 pub mod prim;
+pub mod query;
+pub mod skel;
 pub mod stage;
+mod subdivision;
+pub mod traversal;
+mod usda;
 pub mod value;
+pub mod xform;
 
 // Re-exports
 pub use attribute::Attribute;
+pub use bounds::BoundingBox;
+pub use camera::{Camera, CameraExtractor, Projection};
+pub use collection::{Collection, ExpansionRule};
 pub use error::{Error, Result};
+pub use instancer::{InstancerExtractor, PointInstancer};
+pub use layer::{Layer, LayerPrim};
+pub use light::{Light, LightExtractor, LightParams};
 pub use material::{Material, MaterialExtractor};
-pub use mesh::{Mesh, MeshExtractor};
-pub use prim::Prim;
-pub use stage::Stage;
+pub use mesh::{
+    ElementType, GeomSubset, InstancedMesh, Instancing, InterpolateBoundary, Interpolation, Mesh,
+    MeshExtractor, NormalMode, Orientation, SubdivisionScheme,
+};
+pub use normalize::{normalize, Convention, Handedness, Normalization};
+pub use prim::{
+    Kind, Prim, PrimType, PrototypeId, Purpose, Reference, Specifier, VariantSet, Visibility,
+};
+pub use query::{PathPattern, PrimQuery};
+pub use skel::{
+    skin_mesh, BlendShape, Inbetween, JointInfluences, SkelAnimation, SkelExtractor, SkelRoot,
+    Skeleton,
+};
+pub use stage::{Axis, LoadOptions, Stage};
+pub use traversal::{Traversal, TraversalOptions};
 pub use value::{Value, ValueType};
 
 /// Detects the format of a USD file by its path.
@@ -0,0 +1,479 @@
+//! UsdLux light extraction.
+//!
+//! Both the `inputs:`-prefixed attribute names and the older unprefixed
+//! names are recognized.
+
+use crate::stage::Stage;
+use crate::usda::PrimSpec;
+use crate::value::Value;
+use crate::xform;
+
+/// Parameters shared by all light types.
+#[derive(Debug, Clone)]
+pub struct LightParams {
+    /// The name of the light.
+    pub name: String,
+    /// Scale applied to the light's emission.
+    pub intensity: f32,
+    /// Exposure in stops; emission is scaled by `2^exposure`.
+    pub exposure: f32,
+    /// Linear RGB color of the emission.
+    pub color: [f32; 3],
+    /// Whether `color_temperature` tints the emission.
+    pub enable_color_temperature: bool,
+    /// Color temperature in Kelvin.
+    pub color_temperature: f32,
+    /// Whether emission is divided by the light's surface area.
+    pub normalize: bool,
+    /// Multiplier for the diffuse response.
+    pub diffuse: f32,
+    /// Multiplier for the specular response.
+    pub specular: f32,
+    /// Emission cone shaping.
+    pub shaping: Shaping,
+    /// World transformation matrix (4x4).
+    pub world_transform: [[f64; 4]; 4],
+}
+
+impl LightParams {
+    /// Creates light parameters with the UsdLux defaults.
+    pub fn new(name: impl Into<String>) -> Self {
+        LightParams {
+            name: name.into(),
+            intensity: 1.0,
+            exposure: 0.0,
+            color: [1.0, 1.0, 1.0],
+            enable_color_temperature: false,
+            color_temperature: 6500.0,
+            normalize: false,
+            diffuse: 1.0,
+            specular: 1.0,
+            shaping: Shaping::default(),
+            world_transform: xform::identity(),
+        }
+    }
+
+    /// Returns the emitted RGB radiance scale: color, color temperature,
+    /// intensity and exposure combined.
+    pub fn emission(&self) -> [f32; 3] {
+        let scale = self.intensity * self.exposure.exp2();
+        let tint = if self.enable_color_temperature {
+            color_temperature_to_rgb(self.color_temperature)
+        } else {
+            [1.0; 3]
+        };
+        [0, 1, 2].map(|i| self.color[i] * tint[i] * scale)
+    }
+}
+
+/// Emission cone shaping (`ShapingAPI`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Shaping {
+    /// Cone half-angle in degrees; 90 emits over the full hemisphere.
+    pub cone_angle: f32,
+    /// Softness of the cone edge, from 0 (hard) to 1.
+    pub cone_softness: f32,
+    /// Focus of the emission towards the light's -Z axis.
+    pub focus: f32,
+}
+
+impl Default for Shaping {
+    fn default() -> Self {
+        Shaping {
+            cone_angle: 90.0,
+            cone_softness: 0.0,
+            focus: 0.0,
+        }
+    }
+}
+
+/// Parameterization of a dome light texture (`texture:format`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextureFormat {
+    /// Guess the format from the texture (UsdLux default).
+    #[default]
+    Automatic,
+    /// Latitude-longitude (equirectangular) map.
+    Latlong,
+    /// Mirrored ball map.
+    MirroredBall,
+    /// Angular map.
+    Angular,
+    /// Cube map laid out as a vertical cross.
+    CubeMapVerticalCross,
+}
+
+impl TextureFormat {
+    /// Parses a UsdLux texture format token (e.g. "latlong").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "automatic" => Some(TextureFormat::Automatic),
+            "latlong" => Some(TextureFormat::Latlong),
+            "mirroredBall" => Some(TextureFormat::MirroredBall),
+            "angular" => Some(TextureFormat::Angular),
+            "cubeMapVerticalCross" => Some(TextureFormat::CubeMapVerticalCross),
+            _ => None,
+        }
+    }
+}
+
+/// A light extracted from a UsdLux light prim.
+///
+/// Lights emit along their local -Z axis.
+#[derive(Debug, Clone)]
+pub enum Light {
+    /// A light at infinity, such as the sun.
+    Distant {
+        /// Common light parameters.
+        params: LightParams,
+        /// Angular diameter of the light in degrees.
+        angle: f32,
+    },
+    /// A spherical light.
+    Sphere {
+        /// Common light parameters.
+        params: LightParams,
+        /// Radius of the sphere.
+        radius: f32,
+        /// Whether renderers should treat the light as a point light.
+        treat_as_point: bool,
+    },
+    /// A rectangular area light in the XY plane.
+    Rect {
+        /// Common light parameters.
+        params: LightParams,
+        /// Width along X.
+        width: f32,
+        /// Height along Y.
+        height: f32,
+        /// Texture modulating the emission.
+        texture: Option<String>,
+    },
+    /// A circular area light in the XY plane.
+    Disk {
+        /// Common light parameters.
+        params: LightParams,
+        /// Radius of the disk.
+        radius: f32,
+    },
+    /// A cylindrical light along the X axis.
+    Cylinder {
+        /// Common light parameters.
+        params: LightParams,
+        /// Length along X.
+        length: f32,
+        /// Radius of the cylinder.
+        radius: f32,
+    },
+    /// A light emitting inward from a distant sphere, such as an environment.
+    Dome {
+        /// Common light parameters.
+        params: LightParams,
+        /// Environment texture.
+        texture: Option<String>,
+        /// Parameterization of `texture`.
+        format: TextureFormat,
+    },
+    /// A rectangular opening that guides a dome light's sampling.
+    Portal {
+        /// Common light parameters.
+        params: LightParams,
+        /// Width along X.
+        width: f32,
+        /// Height along Y.
+        height: f32,
+    },
+}
+
+impl Light {
+    /// Returns the parameters shared by all light types.
+    pub fn params(&self) -> &LightParams {
+        match self {
+            Light::Distant { params, .. }
+            | Light::Sphere { params, .. }
+            | Light::Rect { params, .. }
+            | Light::Disk { params, .. }
+            | Light::Cylinder { params, .. }
+            | Light::Dome { params, .. }
+            | Light::Portal { params, .. } => params,
+        }
+    }
+
+    /// Returns the parameters shared by all light types, mutably.
+    pub fn params_mut(&mut self) -> &mut LightParams {
+        match self {
+            Light::Distant { params, .. }
+            | Light::Sphere { params, .. }
+            | Light::Rect { params, .. }
+            | Light::Disk { params, .. }
+            | Light::Cylinder { params, .. }
+            | Light::Dome { params, .. }
+            | Light::Portal { params, .. } => params,
+        }
+    }
+
+    /// Returns the name of the light.
+    pub fn name(&self) -> &str {
+        &self.params().name
+    }
+
+    /// Returns the USD type name of the light (e.g. "SphereLight").
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Light::Distant { .. } => "DistantLight",
+            Light::Sphere { .. } => "SphereLight",
+            Light::Rect { .. } => "RectLight",
+            Light::Disk { .. } => "DiskLight",
+            Light::Cylinder { .. } => "CylinderLight",
+            Light::Dome { .. } => "DomeLight",
+            Light::Portal { .. } => "PortalLight",
+        }
+    }
+
+    /// Returns the world transformation matrix.
+    pub fn world_transform(&self) -> &[[f64; 4]; 4] {
+        &self.params().world_transform
+    }
+
+    /// Returns the world-space emission direction (the light's -Z axis).
+    pub fn direction(&self) -> [f64; 3] {
+        let d = xform::transform_vector(self.world_transform(), [0.0, 0.0, -1.0]);
+        let len = (d[0] * d[0] + d[1] * d[1] + d[2] * d[2]).sqrt();
+        if len == 0.0 {
+            return [0.0, 0.0, -1.0];
+        }
+        d.map(|c| c / len)
+    }
+}
+
+/// Converts a blackbody color temperature in Kelvin to linear Rec. 709 RGB.
+///
+/// The result is normalized to unit luminance, so it only tints a light.
+/// Temperatures are clamped to the 1000K-15000K range of the approximation.
+pub fn color_temperature_to_rgb(kelvin: f32) -> [f32; 3] {
+    // Krystek's rational approximation of the Planckian locus in CIE 1960 UCS.
+    let t = kelvin.clamp(1000.0, 15000.0) as f64;
+    let u = (0.860117757 + 1.54118254e-4 * t + 1.28641212e-7 * t * t)
+        / (1.0 + 8.42420235e-4 * t + 7.08145163e-7 * t * t);
+    let v = (0.317398726 + 4.22806245e-5 * t + 4.20481691e-8 * t * t)
+        / (1.0 - 2.89741816e-5 * t + 1.61456053e-7 * t * t);
+
+    let d = 2.0 * u - 8.0 * v + 4.0;
+    let (x, y) = (3.0 * u / d, 2.0 * v / d);
+    let xyz = [x / y, 1.0, (1.0 - x - y) / y];
+
+    let rgb = [
+        3.2404542 * xyz[0] - 1.5371385 * xyz[1] - 0.4985314 * xyz[2],
+        -0.9692660 * xyz[0] + 1.8760108 * xyz[1] + 0.0415560 * xyz[2],
+        0.0556434 * xyz[0] - 0.2040259 * xyz[1] + 1.0572252 * xyz[2],
+    ]
+    .map(|c: f64| c.max(0.0));
+
+    let luminance = 0.2126 * rgb[0] + 0.7152 * rgb[1] + 0.0722 * rgb[2];
+    rgb.map(|c| (c / luminance) as f32)
+}
+
+/// Utility to extract lights from a USD stage.
+pub struct LightExtractor<'a> {
+    stage: &'a Stage,
+}
+
+impl<'a> LightExtractor<'a> {
+    /// Creates a new light extractor for the given stage.
+    pub fn new(stage: &'a Stage) -> Self {
+        LightExtractor { stage }
+    }
+
+    /// Returns an iterator over all lights in the stage.
+    ///
+    /// Prims of light types not listed in [`Light`] are skipped.
+    pub fn lights(&self) -> impl Iterator<Item = Light> + '_ {
+        let mut lights = Vec::new();
+        self.stage.walk_transforms(|visit, _, world| {
+            if let Some(mut light) = Self::extract_light(visit.spec) {
+                light.params_mut().world_transform = *world;
+                lights.push(light);
+            }
+        });
+        lights.into_iter()
+    }
+
+    /// Extracts light data from a light prim's spec.
+    fn extract_light(spec: &PrimSpec) -> Option<Light> {
+        // Prefer the `inputs:` names introduced in USD 21.05.
+        let input = |name: &str| {
+            spec.value(&format!("inputs:{}", name))
+                .or_else(|| spec.value(name))
+        };
+        let float = |name: &str, default: f32| {
+            input(name)
+                .as_ref()
+                .and_then(Value::as_f64)
+                .map_or(default, |v| v as f32)
+        };
+        let bool = |name: &str| {
+            input(name)
+                .as_ref()
+                .and_then(Value::as_bool)
+                .unwrap_or(false)
+        };
+        let asset = |name: &str| {
+            input(name)
+                .as_ref()
+                .and_then(Value::as_asset)
+                .filter(|path| !path.is_empty())
+                .map(str::to_string)
+        };
+
+        let mut params = LightParams::new(&spec.name);
+        // DistantLight overrides the UsdLux intensity fallback to approximate
+        // sunlight.
+        let intensity = match spec.type_name.as_str() {
+            "DistantLight" => 50000.0,
+            _ => params.intensity,
+        };
+        params.intensity = float("intensity", intensity);
+        params.exposure = float("exposure", params.exposure);
+        if let Some(color) = input("color").as_ref().and_then(Value::as_double3) {
+            params.color = color.map(|c| c as f32);
+        }
+        params.enable_color_temperature = bool("enableColorTemperature");
+        params.color_temperature = float("colorTemperature", params.color_temperature);
+        params.normalize = bool("normalize");
+        params.diffuse = float("diffuse", params.diffuse);
+        params.specular = float("specular", params.specular);
+        params.shaping = Shaping {
+            cone_angle: float("shaping:cone:angle", 90.0),
+            cone_softness: float("shaping:cone:softness", 0.0),
+            focus: float("shaping:focus", 0.0),
+        };
+
+        let light = match spec.type_name.as_str() {
+            "DistantLight" => Light::Distant {
+                angle: float("angle", 0.53),
+                params,
+            },
+            "SphereLight" => Light::Sphere {
+                radius: float("radius", 0.5),
+                treat_as_point: bool("treatAsPoint"),
+                params,
+            },
+            "RectLight" => Light::Rect {
+                width: float("width", 1.0),
+                height: float("height", 1.0),
+                texture: asset("texture:file"),
+                params,
+            },
+            "DiskLight" => Light::Disk {
+                radius: float("radius", 0.5),
+                params,
+            },
+            "CylinderLight" => Light::Cylinder {
+                length: float("length", 1.0),
+                radius: float("radius", 0.5),
+                params,
+            },
+            "DomeLight" => Light::Dome {
+                texture: asset("texture:file"),
+                format: input("texture:format")
+                    .as_ref()
+                    .and_then(Value::as_str)
+                    .and_then(TextureFormat::from_token)
+                    .unwrap_or_default(),
+                params,
+            },
+            "PortalLight" => Light::Portal {
+                width: float("width", 1.0),
+                height: float("height", 1.0),
+                params,
+            },
+            _ => return None,
+        };
+        Some(light)
+    }
+
+    /// Extracts all lights and returns them as a vector.
+    pub fn collect(&self) -> Vec<Light> {
+        self.lights().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::parse_prim;
+
+    #[test]
+    fn test_extract_lights() {
+        let spec = parse_prim(
+            r#"def SphereLight "Key"
+{
+    color3f inputs:color = (1, 0.5, 0.25)
+    float inputs:exposure = 1
+    float inputs:intensity = 10
+    float inputs:radius = 2
+    float inputs:shaping:cone:angle = 30
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let light = LightExtractor::extract_light(&spec).unwrap();
+        let Light::Sphere { params, radius, .. } = &light else {
+            panic!("expected a sphere light, got {:?}", light);
+        };
+        assert_eq!(*radius, 2.0);
+        assert_eq!(params.shaping.cone_angle, 30.0);
+        assert_eq!(params.emission(), [20.0, 10.0, 5.0]);
+
+        let spec = parse_prim(
+            r#"def DomeLight "Sky"
+{
+    asset texture:file = @./sky.exr@
+    token texture:format = "latlong"
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let light = LightExtractor::extract_light(&spec).unwrap();
+        let Light::Dome {
+            texture, format, ..
+        } = &light
+        else {
+            panic!("expected a dome light, got {:?}", light);
+        };
+        assert_eq!(texture.as_deref(), Some("./sky.exr"));
+        assert_eq!(*format, TextureFormat::Latlong);
+    }
+
+    #[test]
+    fn test_distant_light_intensity_fallback() {
+        let intensity = |text: &str| {
+            let spec = parse_prim(text, false).unwrap();
+            LightExtractor::extract_light(&spec)
+                .unwrap()
+                .params()
+                .intensity
+        };
+        assert_eq!(intensity("def DistantLight \"Sun\"\n{\n}"), 50000.0);
+        assert_eq!(
+            intensity("def DistantLight \"Sun\"\n{\n    float inputs:intensity = 3\n}"),
+            3.0
+        );
+        assert_eq!(intensity("def DiskLight \"Fill\"\n{\n}"), 1.0);
+    }
+
+    #[test]
+    fn test_color_temperature_to_rgb() {
+        // Close to white near D65, reddish when warm, bluish when cold.
+        let white = color_temperature_to_rgb(6500.0);
+        assert!(white.iter().all(|&c| (c - 1.0).abs() < 0.05), "{:?}", white);
+
+        let warm = color_temperature_to_rgb(2700.0);
+        assert!(warm[0] > warm[1] && warm[1] > warm[2]);
+
+        let cold = color_temperature_to_rgb(12000.0);
+        assert!(cold[2] > cold[0]);
+    }
+}
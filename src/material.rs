@@ -1,20 +1,21 @@
 //! USD Material handling (UsdPreviewSurface).
 //!
-//! Note: Due to limitations in the current tinyusdz C API, material property
-//! extraction is not yet fully supported. The property/attribute value
-//! extraction functions are not yet implemented.
+//! Materials are read from the `UsdPreviewSurface` shader connected to a
+//! Material prim's `outputs:surface`. Shader inputs connected to
+//! `UsdUVTexture` shaders become texture paths.
 
-use crate::prim::Prim;
 use crate::stage::Stage;
+use crate::usda::PrimSpec;
+use crate::value::Value;
 
 /// A material extracted from USD (UsdPreviewSurface).
-///
-/// Note: Due to C API limitations, material property extraction is not yet
-/// fully functional. Materials will contain default values.
 #[derive(Debug, Clone)]
 pub struct Material {
     /// The name of the material.
     pub name: String,
+    /// The prim path of the material, or empty for materials that were not
+    /// read from a stage.
+    pub path: String,
     /// Diffuse/albedo color (RGB).
     pub diffuse_color: Option<[f32; 3]>,
     /// Emissive color (RGB).
@@ -48,6 +49,7 @@ impl Material {
     pub fn new(name: impl Into<String>) -> Self {
         Material {
             name: name.into(),
+            path: String::new(),
             diffuse_color: Some([0.8, 0.8, 0.8]), // Default gray
             emissive_color: None,
             metallic: Some(0.0),
@@ -92,13 +94,25 @@ impl Default for Material {
 
 /// Utility to extract materials from a USD stage.
 ///
-/// Note: Due to limitations in the current tinyusdz C API, material property
-/// extraction is not yet fully supported. Materials will be identified but
-/// may contain only default values.
+/// Values are read from the `UsdPreviewSurface` shader connected to the
+/// material's `outputs:surface`, or else the first `UsdPreviewSurface`
+/// shader beneath the material. Connections to material inputs are followed,
+/// and inputs connected to a `UsdUVTexture` shader yield its `inputs:file`
+/// and no value. Unauthored inputs keep the UsdPreviewSurface defaults.
 pub struct MaterialExtractor<'a> {
     stage: &'a Stage,
 }
 
+/// The longest connection chain followed before giving up, which guards
+/// against connection cycles.
+const MAX_CONNECTION_DEPTH: usize = 16;
+
+/// What a shader input resolves to.
+enum Input {
+    Value(Value),
+    Texture(String),
+}
+
 impl<'a> MaterialExtractor<'a> {
     /// Creates a new material extractor for the given stage.
     pub fn new(stage: &'a Stage) -> Self {
@@ -106,40 +120,226 @@ impl<'a> MaterialExtractor<'a> {
     }
 
     /// Returns an iterator over all materials in the stage.
-    ///
-    /// Note: Due to C API limitations, materials will have names but
-    /// may not contain actual material property values.
     pub fn materials(&self) -> impl Iterator<Item = Material> + '_ {
         self.stage.traverse().filter_map(|prim| {
             if prim.is_material() {
-                Some(Self::extract_material(&prim))
+                Some(self.extract_material(prim.spec()?, &prim.path()))
             } else {
                 None
             }
         })
     }
 
-    /// Extracts material data from a Material prim.
-    fn extract_material(prim: &Prim<'_>) -> Material {
-        let mat = Material::new(prim.name());
+    /// Extracts material data from a Material prim's spec.
+    fn extract_material(&self, spec: &PrimSpec, path: &str) -> Material {
+        let mut mat = Material {
+            name: spec.name.clone(),
+            path: path.to_string(),
+            diffuse_color: Some([0.18; 3]),
+            emissive_color: Some([0.0; 3]),
+            metallic: Some(0.0),
+            roughness: Some(0.5),
+            opacity: Some(1.0),
+            ior: Some(1.5),
+            clearcoat: Some(0.0),
+            clearcoat_roughness: Some(0.01),
+            ..Material::new(spec.name.clone())
+        };
+        let Some(shader) = self.surface_shader(spec) else {
+            return mat;
+        };
+
+        let input = |name: &str| self.resolve(&shader, &format!("inputs:{}", name));
 
-        // Note: Due to C API limitations, we can only get property names
-        // but not their values. The property extraction functions
-        // are not yet implemented in tinyusdz.
-        let _property_names = prim.property_names();
+        for (name, value, texture) in [
+            (
+                "diffuseColor",
+                &mut mat.diffuse_color,
+                &mut mat.diffuse_texture,
+            ),
+            (
+                "emissiveColor",
+                &mut mat.emissive_color,
+                &mut mat.emissive_texture,
+            ),
+        ] {
+            match input(name) {
+                Some(Input::Value(v)) => {
+                    if let Some(c) = v.as_double3() {
+                        *value = Some(c.map(|c| c as f32));
+                    }
+                }
+                Some(Input::Texture(file)) => {
+                    *value = None;
+                    *texture = Some(file);
+                }
+                None => {}
+            }
+        }
 
-        // Future: When C API supports it, extract shader parameters:
-        // - inputs:diffuseColor
-        // - inputs:metallic
-        // - inputs:roughness
-        // - inputs:opacity
-        // - etc.
+        for (name, value) in [
+            ("metallic", &mut mat.metallic),
+            ("roughness", &mut mat.roughness),
+            ("opacity", &mut mat.opacity),
+            ("ior", &mut mat.ior),
+            ("clearcoat", &mut mat.clearcoat),
+            ("clearcoatRoughness", &mut mat.clearcoat_roughness),
+        ] {
+            match input(name) {
+                Some(Input::Value(v)) => {
+                    if let Some(f) = v.as_f64() {
+                        *value = Some(f as f32);
+                    }
+                }
+                // glTF packs metallic and roughness into one texture.
+                Some(Input::Texture(file)) if matches!(name, "metallic" | "roughness") => {
+                    *value = None;
+                    mat.metallic_roughness_texture = Some(file);
+                }
+                _ => {}
+            }
+        }
 
+        if let Some(Input::Texture(file)) = input("normal") {
+            mat.normal_texture = Some(file);
+        }
+        if let Some(Input::Texture(file)) = input("occlusion") {
+            mat.occlusion_texture = Some(file);
+        }
         mat
     }
 
+    /// Finds the UsdPreviewSurface shader of a material.
+    fn surface_shader(&self, material: &PrimSpec) -> Option<PrimSpec> {
+        let connected = material
+            .property("outputs:surface")
+            .and_then(|output| output.targets.first())
+            .and_then(|target| self.spec_at(split_property_path(target).0));
+        connected
+            .or_else(|| find_shader(material, "UsdPreviewSurface").cloned())
+            .filter(|shader| shader_id(shader) == Some("UsdPreviewSurface"))
+    }
+
+    /// Resolves a shader input, following connections to material or node
+    /// graph inputs and stopping at texture shaders.
+    fn resolve(&self, spec: &PrimSpec, name: &str) -> Option<Input> {
+        let mut spec = spec.clone();
+        let mut name = name.to_string();
+        for _ in 0..MAX_CONNECTION_DEPTH {
+            let property = spec.property(&name)?;
+            let Some(target) = property.targets.first() else {
+                return spec.value(&name).map(Input::Value);
+            };
+
+            let (target_path, target_name) = split_property_path(target);
+            let target_name = target_name.to_string();
+            let target_spec = self.spec_at(target_path)?;
+            if shader_id(&target_spec) == Some("UsdUVTexture") {
+                return match target_spec.value("inputs:file") {
+                    Some(Value::Asset(file)) => Some(Input::Texture(file)),
+                    _ => None,
+                };
+            }
+            spec = target_spec;
+            name = target_name;
+        }
+        None
+    }
+
+    /// Returns the spec of the prim at `path`.
+    fn spec_at(&self, path: &str) -> Option<PrimSpec> {
+        self.stage.prim_at_path(path)?.spec().cloned()
+    }
+
     /// Extracts all materials and returns them as a vector.
     pub fn collect(&self) -> Vec<Material> {
         self.materials().collect()
     }
 }
+
+/// Finds the first shader with the given `info:id` beneath a prim.
+fn find_shader<'s>(spec: &'s PrimSpec, id: &str) -> Option<&'s PrimSpec> {
+    spec.children.iter().find_map(|child| {
+        if child.type_name == "Shader" && shader_id(child) == Some(id) {
+            Some(child)
+        } else {
+            find_shader(child, id)
+        }
+    })
+}
+
+/// Returns the `info:id` of a shader spec.
+fn shader_id(spec: &PrimSpec) -> Option<&str> {
+    spec.property("info:id")?.default.as_ref()?.as_str()
+}
+
+/// Splits a property path such as `/Mat/Shader.outputs:rgb` into the prim
+/// path and the property name.
+fn split_property_path(target: &str) -> (&str, &str) {
+    let start = target.rfind('/').map_or(0, |i| i + 1);
+    match target[start..].find('.') {
+        Some(dot) => (&target[..start + dot], &target[start + dot + 1..]),
+        None => (target, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_surface() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Scope "Looks"
+{
+    def Material "Red"
+    {
+        token outputs:surface.connect = </Looks/Red/Surface.outputs:surface>
+
+        def Shader "Surface"
+        {
+            uniform token info:id = "UsdPreviewSurface"
+            color3f inputs:diffuseColor = (1, 0, 0)
+            float inputs:metallic = 1
+            float inputs:roughness.connect = </Looks/Red.inputs:roughness>
+            normal3f inputs:normal.connect = </Looks/Red/Normal.outputs:rgb>
+            token outputs:surface
+        }
+
+        def Shader "Normal"
+        {
+            uniform token info:id = "UsdUVTexture"
+            asset inputs:file = @textures/normal.png@
+            float3 outputs:rgb
+        }
+
+        float inputs:roughness = 0.25
+    }
+
+    def Material "Empty"
+    {
+    }
+}
+"#,
+        )
+        .unwrap();
+        let materials = MaterialExtractor::new(&stage).collect();
+        assert_eq!(materials.len(), 2);
+
+        let red = &materials[0];
+        assert_eq!(red.path, "/Looks/Red");
+        assert_eq!(red.diffuse_color, Some([1.0, 0.0, 0.0]));
+        assert_eq!(red.metallic, Some(1.0));
+        assert_eq!(red.roughness, Some(0.25));
+        assert_eq!(red.normal_texture.as_deref(), Some("textures/normal.png"));
+        assert!(red.is_metallic());
+
+        // Unauthored inputs keep the UsdPreviewSurface defaults.
+        let empty = &materials[1];
+        assert_eq!(empty.diffuse_color, Some([0.18; 3]));
+        assert_eq!(empty.roughness, Some(0.5));
+        assert!(!empty.has_textures());
+    }
+}
@@ -1,21 +1,189 @@
 //! Mesh extraction from USD prims.
-//!
-//! Note: Due to limitations in the current tinyusdz C API, mesh geometry data
-//! (vertices, indices, UVs, normals) cannot be fully extracted yet.
-//! The property/attribute value extraction functions are not yet implemented.
-//!
-//! This module provides the infrastructure for mesh extraction which will work
-//! once the C API is more complete.
 
-use crate::prim::Prim;
-use crate::stage::Stage;
+use std::collections::{HashMap, HashSet};
+
+use crate::attribute::spec_interpolation;
+use crate::bounds::BoundingBox;
+use crate::error::{Error, Result};
+use crate::instancer::PointInstancer;
+use crate::prim::{self, PrototypeId, Purpose, Visibility};
+use crate::skel::{self, BlendShape, JointInfluences};
+use crate::stage::{is_within, Stage};
+use crate::usda::PrimSpec;
+use crate::value::Value;
+use crate::xform;
+
+/// Primvar interpolation mode, describing how values map onto a mesh.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Interpolation {
+    /// One value for the whole mesh.
+    Constant,
+    /// One value per face.
+    Uniform,
+    /// One value per point, interpolated across faces.
+    #[default]
+    Vertex,
+    /// One value per point, linearly interpolated.
+    Varying,
+    /// One value per face-vertex (corner).
+    FaceVarying,
+}
+
+impl Interpolation {
+    /// Parses a USD interpolation token (e.g. "faceVarying").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "constant" => Some(Interpolation::Constant),
+            "uniform" => Some(Interpolation::Uniform),
+            "vertex" => Some(Interpolation::Vertex),
+            "varying" => Some(Interpolation::Varying),
+            "faceVarying" => Some(Interpolation::FaceVarying),
+            _ => None,
+        }
+    }
+}
+
+/// Winding order of a mesh's faces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Orientation {
+    /// Counter-clockwise faces are front-facing (USD default).
+    #[default]
+    RightHanded,
+    /// Clockwise faces are front-facing.
+    LeftHanded,
+}
+
+impl Orientation {
+    /// Parses a USD orientation token (e.g. "leftHanded").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "rightHanded" => Some(Orientation::RightHanded),
+            "leftHanded" => Some(Orientation::LeftHanded),
+            _ => None,
+        }
+    }
+}
+
+/// Subdivision scheme of a mesh (`subdivisionScheme`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum SubdivisionScheme {
+    /// Catmull-Clark subdivision (USD default).
+    #[default]
+    CatmullClark,
+    /// Loop subdivision, for triangle meshes.
+    Loop,
+    /// Bilinear subdivision.
+    Bilinear,
+    /// The mesh is a polygonal mesh and is not subdivided.
+    None,
+}
+
+impl SubdivisionScheme {
+    /// Parses a USD subdivision scheme token (e.g. "catmullClark").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "catmullClark" => Some(SubdivisionScheme::CatmullClark),
+            "loop" => Some(SubdivisionScheme::Loop),
+            "bilinear" => Some(SubdivisionScheme::Bilinear),
+            "none" => Some(SubdivisionScheme::None),
+            _ => None,
+        }
+    }
+}
+
+/// Boundary interpolation rule (`interpolateBoundary`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum InterpolateBoundary {
+    /// Boundary edges are not interpolated. Treated like `EdgeOnly` when
+    /// computing points.
+    None,
+    /// Boundary edges are sharp creases.
+    EdgeOnly,
+    /// Boundary edges are sharp creases and corners are sharp (USD default).
+    #[default]
+    EdgeAndCorner,
+}
+
+impl InterpolateBoundary {
+    /// Parses a USD boundary interpolation token (e.g. "edgeOnly").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "none" => Some(InterpolateBoundary::None),
+            "edgeOnly" => Some(InterpolateBoundary::EdgeOnly),
+            "edgeAndCorner" => Some(InterpolateBoundary::EdgeAndCorner),
+            _ => None,
+        }
+    }
+}
+
+/// How [`Mesh::compute_normals`] generates normals.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NormalMode {
+    /// One normal per point, averaged over adjacent faces weighted by face
+    /// area and corner angle (`vertex` interpolation).
+    Smooth,
+    /// One normal per face (`uniform` interpolation).
+    Flat,
+    /// Per-corner normals that only average adjacent faces whose normals are
+    /// within the given angle in radians (`faceVarying` interpolation).
+    Crease(f32),
+}
+
+/// Kind of element a [`GeomSubset`] selects (`elementType`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ElementType {
+    /// Indices refer to faces (USD default).
+    #[default]
+    Face,
+    /// Indices refer to points.
+    Point,
+    /// Indices are pairs of points describing edges.
+    Edge,
+}
+
+impl ElementType {
+    /// Parses a USD element type token (e.g. "face").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "face" => Some(ElementType::Face),
+            "point" => Some(ElementType::Point),
+            "edge" => Some(ElementType::Edge),
+            _ => None,
+        }
+    }
+}
+
+/// A subset of a mesh's elements, from a `GeomSubset` child prim.
+///
+/// Subsets in the `materialBind` family assign materials to groups of faces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeomSubset {
+    /// The name of the subset prim.
+    pub name: String,
+    /// Kind of element `indices` refers to.
+    pub element_type: ElementType,
+    /// Indices of the selected elements.
+    pub indices: Vec<i32>,
+    /// The family this subset belongs to (e.g. "materialBind").
+    pub family_name: Option<String>,
+    /// Path of the material bound to the subset (`material:binding`).
+    pub material_binding: Option<String>,
+}
+
+impl GeomSubset {
+    /// Family name used for per-face material assignment.
+    pub const MATERIAL_BIND_FAMILY: &'static str = "materialBind";
+
+    /// Returns true if this subset assigns a material to faces.
+    pub fn is_material_bind(&self) -> bool {
+        self.element_type == ElementType::Face
+            && self.family_name.as_deref() == Some(Self::MATERIAL_BIND_FAMILY)
+    }
+}
 
 /// A mesh extracted from a USD Mesh prim.
 ///
 /// Provides access to vertex positions, face data, normals, and UVs.
-///
-/// Note: Due to C API limitations, geometry data extraction is not yet
-/// fully functional. The mesh will contain metadata but not actual geometry data.
 #[derive(Debug, Clone)]
 pub struct Mesh {
     /// The name of the mesh.
@@ -26,10 +194,44 @@ pub struct Mesh {
     pub face_vertex_counts: Vec<i32>,
     /// Indices into the points array for each face vertex.
     pub face_vertex_indices: Vec<i32>,
+    /// Indices of faces that are holes and should not be rendered.
+    pub hole_indices: Vec<i32>,
     /// Vertex normals (optional).
     pub normals: Option<Vec<[f32; 3]>>,
+    /// Interpolation of `normals`.
+    pub normals_interpolation: Interpolation,
     /// Texture coordinates (optional).
     pub uvs: Option<Vec<[f32; 2]>>,
+    /// Interpolation of `uvs`.
+    pub uvs_interpolation: Interpolation,
+    /// Tangents with handedness in `w` (optional, one per face-vertex).
+    pub tangents: Option<Vec<[f32; 4]>>,
+    /// Winding order of the faces.
+    pub orientation: Orientation,
+    /// Subdivision scheme used by [`Mesh::subdivide`].
+    pub subdivision_scheme: SubdivisionScheme,
+    /// Boundary interpolation rule used by [`Mesh::subdivide`].
+    pub interpolate_boundary: InterpolateBoundary,
+    /// Point indices of crease edge chains.
+    pub crease_indices: Vec<i32>,
+    /// Number of points in each crease chain.
+    pub crease_lengths: Vec<i32>,
+    /// Sharpness per crease, or per crease edge.
+    pub crease_sharpnesses: Vec<f32>,
+    /// Point indices of sharp corners.
+    pub corner_indices: Vec<i32>,
+    /// Sharpness of each corner.
+    pub corner_sharpnesses: Vec<f32>,
+    /// Path of the material bound to the mesh (`material:binding`).
+    pub material_binding: Option<String>,
+    /// `GeomSubset` children of the mesh.
+    pub subsets: Vec<GeomSubset>,
+    /// Path of the bound skeleton (`skel:skeleton`, possibly inherited).
+    pub skeleton: Option<String>,
+    /// Joint influences for skinning (optional).
+    pub joint_influences: Option<JointInfluences>,
+    /// Blend shapes, ordered as in `skel:blendShapes`.
+    pub blend_shapes: Vec<BlendShape>,
     /// Local transformation matrix (4x4).
     pub local_transform: [[f64; 4]; 4],
     /// World transformation matrix (4x4).
@@ -44,10 +246,27 @@ impl Mesh {
             points: Vec::new(),
             face_vertex_counts: Vec::new(),
             face_vertex_indices: Vec::new(),
+            hole_indices: Vec::new(),
             normals: None,
+            normals_interpolation: Interpolation::Vertex,
             uvs: None,
-            local_transform: identity_matrix(),
-            world_transform: identity_matrix(),
+            uvs_interpolation: Interpolation::Vertex,
+            tangents: None,
+            orientation: Orientation::RightHanded,
+            subdivision_scheme: SubdivisionScheme::CatmullClark,
+            interpolate_boundary: InterpolateBoundary::EdgeAndCorner,
+            crease_indices: Vec::new(),
+            crease_lengths: Vec::new(),
+            crease_sharpnesses: Vec::new(),
+            corner_indices: Vec::new(),
+            corner_sharpnesses: Vec::new(),
+            material_binding: None,
+            subsets: Vec::new(),
+            skeleton: None,
+            joint_influences: None,
+            blend_shapes: Vec::new(),
+            local_transform: xform::identity(),
+            world_transform: xform::identity(),
         }
     }
 
@@ -71,109 +290,1368 @@ impl Mesh {
         self.uvs.is_some()
     }
 
+    /// Returns the bounding box of the points in mesh space.
+    pub fn local_bounds(&self) -> BoundingBox {
+        BoundingBox::from_points(&self.points)
+    }
+
+    /// Returns the bounding box of the points transformed to world space.
+    pub fn world_bounds(&self) -> BoundingBox {
+        self.local_bounds().transformed(&self.world_transform)
+    }
+
+    /// Returns true if this mesh has tangents.
+    pub fn has_tangents(&self) -> bool {
+        self.tangents.is_some()
+    }
+
     /// Triangulates the mesh if it contains non-triangle faces.
     ///
-    /// Returns a new mesh with only triangular faces.
-    pub fn triangulate(&self) -> Mesh {
+    /// Faces are split by ear clipping, so concave polygons produce correct
+    /// triangles. Faces listed in `hole_indices` and faces with fewer than
+    /// three vertices are dropped. Normals and UVs are carried through
+    /// according to their interpolation.
+    ///
+    /// Returns an error if the face data references indices out of range.
+    pub fn triangulate(&self) -> Result<Mesh> {
         let mut result = Mesh::new(&self.name);
         result.points = self.points.clone();
         result.local_transform = self.local_transform;
         result.world_transform = self.world_transform;
+        result.normals_interpolation = self.normals_interpolation;
+        result.uvs_interpolation = self.uvs_interpolation;
+        result.orientation = self.orientation;
+        result.subdivision_scheme = self.subdivision_scheme;
+        result.interpolate_boundary = self.interpolate_boundary;
+        result.crease_indices = self.crease_indices.clone();
+        result.crease_lengths = self.crease_lengths.clone();
+        result.crease_sharpnesses = self.crease_sharpnesses.clone();
+        result.corner_indices = self.corner_indices.clone();
+        result.corner_sharpnesses = self.corner_sharpnesses.clone();
+        result.material_binding = self.material_binding.clone();
+        result.skeleton = self.skeleton.clone();
+        result.joint_influences = self.joint_influences.clone();
+        result.blend_shapes = self.blend_shapes.clone();
 
-        if self.face_vertex_indices.is_empty() {
-            return result;
+        let topology = self.triangulate_topology()?;
+        result.subsets = self.remap_subsets(&topology.triangle_faces);
+
+        result.face_vertex_counts = vec![3; topology.triangle_faces.len()];
+        result.face_vertex_indices = topology
+            .corners
+            .iter()
+            .map(|&corner| self.face_vertex_indices[corner])
+            .collect();
+
+        if let Some(normals) = &self.normals {
+            result.normals = Some(topology.remap(normals, self.normals_interpolation)?);
+        }
+        if let Some(uvs) = &self.uvs {
+            result.uvs = Some(topology.remap(uvs, self.uvs_interpolation)?);
         }
+        if let Some(tangents) = &self.tangents {
+            result.tangents = Some(topology.remap(tangents, Interpolation::FaceVarying)?);
+        }
+
+        Ok(result)
+    }
 
-        let mut new_indices = Vec::new();
-        let mut index_offset = 0;
+    /// Returns the mesh deformed by its blend shapes.
+    ///
+    /// `weights` are ordered like `blend_shapes`; missing weights count as 0.
+    /// Inbetween shapes are interpolated piecewise linearly. Normal offsets
+    /// are applied to `vertex` interpolated normals, which are then
+    /// renormalized.
+    ///
+    /// Returns an error if a shape refers to points outside the mesh or is
+    /// not [valid](BlendShape::validate).
+    pub fn apply_blend_shapes(&self, weights: &[f32]) -> Result<Mesh> {
+        let mut result = self.clone();
+        let point_count = self.points.len();
+        let deform_normals = matches!(
+            self.normals_interpolation,
+            Interpolation::Vertex | Interpolation::Varying
+        );
 
-        for &count in &self.face_vertex_counts {
-            if count < 3 {
-                index_offset += count as usize;
+        for (shape, &weight) in self.blend_shapes.iter().zip(weights) {
+            if weight == 0.0 {
                 continue;
             }
+            shape.validate()?;
+            let (offsets, normal_offsets) = shape.offsets_at(weight);
+            let has_normal_offsets = shape.normal_offsets.is_some()
+                || shape.inbetweens.iter().any(|i| i.normal_offsets.is_some());
+
+            for (i, offset) in offsets.iter().enumerate() {
+                let point = match &shape.point_indices {
+                    Some(indices) => indices.get(i).map_or(-1, |&p| p as i64),
+                    None => i as i64,
+                };
+                let point = usize::try_from(point)
+                    .ok()
+                    .filter(|&p| p < point_count)
+                    .ok_or(Error::IndexOutOfBounds {
+                        index: point.max(0) as usize,
+                        len: point_count,
+                    })?;
+
+                let p = &mut result.points[point];
+                for c in 0..3 {
+                    p[c] += offset[c];
+                }
+                if let (true, true, Some(normals)) =
+                    (deform_normals, has_normal_offsets, &mut result.normals)
+                {
+                    if let Some(n) = normals.get_mut(point) {
+                        for c in 0..3 {
+                            n[c] += normal_offsets[i][c];
+                        }
+                    }
+                }
+            }
+        }
+
+        if deform_normals {
+            if let Some(normals) = &mut result.normals {
+                for n in normals.iter_mut() {
+                    *n = normalize(*n);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Subdivides the mesh `levels` times using its `subdivision_scheme`.
+    ///
+    /// Catmull-Clark turns every n-gon into n quads; Loop splits every
+    /// triangle into four and requires a triangle mesh. Creases, corners and
+    /// `interpolate_boundary` are honoured, with semi-sharp values decaying
+    /// by one per level. `vertex` UVs are smoothed with the points while
+    /// `faceVarying` UVs are interpolated linearly within each face. Normals
+    /// are recomputed as smooth normals; tangents, joint influences and
+    /// blend shapes are dropped.
+    ///
+    /// A mesh with [`SubdivisionScheme::None`] is returned unchanged.
+    pub fn subdivide(&self, levels: u32) -> Result<Mesh> {
+        crate::subdivision::subdivide(self, levels)
+    }
+
+    /// Splits every face-vertex into its own point.
+    ///
+    /// The result has one point per entry of `face_vertex_indices`, and
+    /// normals and UVs become `vertex` interpolated. This is the layout
+    /// expected by formats such as glTF that index a single vertex stream.
+    pub fn unweld(&self) -> Result<Mesh> {
+        let mut result = Mesh::new(&self.name);
+        result.face_vertex_counts = self.face_vertex_counts.clone();
+        result.hole_indices = self.hole_indices.clone();
+        result.orientation = self.orientation;
+        result.material_binding = self.material_binding.clone();
+        result.subsets = self.subsets.clone();
+        result.local_transform = self.local_transform;
+        result.world_transform = self.world_transform;
+
+        result.skeleton = self.skeleton.clone();
+
+        result.points = self.corner_values(&self.points, Interpolation::Vertex)?;
+        if let Some(influences) = &self.joint_influences {
+            let corners = (0..self.face_vertex_indices.len())
+                .map(|corner| self.point_index(corner))
+                .collect::<Result<Vec<_>>>()?;
+            result.joint_influences = Some(influences.remap_points(&corners));
+        }
+        if !self.blend_shapes.is_empty() {
+            let corners = (0..self.face_vertex_indices.len())
+                .map(|corner| self.point_index(corner))
+                .collect::<Result<Vec<_>>>()?;
+            result.blend_shapes = self
+                .blend_shapes
+                .iter()
+                .map(|shape| shape.remap_points(&corners))
+                .collect();
+        }
+        result.face_vertex_indices = (0..self.face_vertex_indices.len() as i32).collect();
+        if let Some(normals) = &self.normals {
+            result.normals = Some(self.corner_values(normals, self.normals_interpolation)?);
+        }
+        if let Some(uvs) = &self.uvs {
+            result.uvs = Some(self.corner_values(uvs, self.uvs_interpolation)?);
+        }
+        result.tangents = self.tangents.clone();
+
+        Ok(result)
+    }
 
-            // Fan triangulation
-            let first = self.face_vertex_indices[index_offset];
-            for i in 1..(count as usize - 1) {
-                new_indices.push(first);
-                new_indices.push(self.face_vertex_indices[index_offset + i]);
-                new_indices.push(self.face_vertex_indices[index_offset + i + 1]);
+    /// Splits the mesh into one mesh per bound material.
+    ///
+    /// Faces are grouped by the `material_binding` of the `materialBind`
+    /// subsets that contain them, in the order the materials first appear.
+    /// Subsets without a binding, and faces outside every subset, use the
+    /// mesh's own material. Each result only keeps the points its faces use and has no
+    /// subsets. A mesh without `materialBind` subsets is returned as is.
+    pub fn split_by_subsets(&self) -> Result<Vec<Mesh>> {
+        let face_count = self.face_vertex_counts.len();
+        let mut assigned = vec![false; face_count];
+        let mut groups: Vec<(Option<String>, Vec<usize>)> = Vec::new();
+
+        for subset in self.subsets.iter().filter(|s| s.is_material_bind()) {
+            let material = subset
+                .material_binding
+                .clone()
+                .or_else(|| self.material_binding.clone());
+            let group = material_group(&mut groups, material);
+            for &face in &subset.indices {
+                let face = usize::try_from(face)
+                    .ok()
+                    .filter(|&f| f < face_count)
+                    .ok_or(Error::IndexOutOfBounds {
+                        index: face.max(0) as usize,
+                        len: face_count,
+                    })?;
+                // A face belongs to the first subset that lists it.
+                if !assigned[face] {
+                    assigned[face] = true;
+                    groups[group].1.push(face);
+                }
             }
+        }
 
-            index_offset += count as usize;
+        if groups.is_empty() {
+            return Ok(vec![self.clone()]);
         }
 
-        result.face_vertex_counts = vec![3; new_indices.len() / 3];
-        result.face_vertex_indices = new_indices;
+        let unassigned: Vec<usize> = (0..face_count).filter(|&f| !assigned[f]).collect();
+        if !unassigned.is_empty() {
+            let group = material_group(&mut groups, self.material_binding.clone());
+            groups[group].1.extend(unassigned);
+        }
 
-        result
+        groups
+            .into_iter()
+            .map(|(material, mut faces)| {
+                faces.sort_unstable();
+                let mut mesh = self.select_faces(&faces)?;
+                mesh.material_binding = material;
+                Ok(mesh)
+            })
+            .collect()
+    }
+
+    /// Builds a mesh from a sorted list of faces, compacting its points.
+    fn select_faces(&self, selected: &[usize]) -> Result<Mesh> {
+        let mut result = Mesh::new(&self.name);
+        result.orientation = self.orientation;
+        result.subdivision_scheme = self.subdivision_scheme;
+        result.interpolate_boundary = self.interpolate_boundary;
+        result.local_transform = self.local_transform;
+        result.world_transform = self.world_transform;
+        result.normals_interpolation = self.normals_interpolation;
+        result.uvs_interpolation = self.uvs_interpolation;
+
+        let faces = self.faces()?;
+        let holes: HashSet<i32> = self.hole_indices.iter().copied().collect();
+        let mut point_map: Vec<Option<i32>> = vec![None; self.points.len()];
+        let mut corners = Vec::new();
+
+        for (new_face, &face) in selected.iter().enumerate() {
+            let (_, offset, count) = faces[face];
+            result.face_vertex_counts.push(count as i32);
+            for corner in offset..offset + count {
+                let point = self.point_index(corner)?;
+                let index = *point_map[point].get_or_insert_with(|| {
+                    result.points.push(self.points[point]);
+                    result.points.len() as i32 - 1
+                });
+                result.face_vertex_indices.push(index);
+                corners.push(corner);
+            }
+            if holes.contains(&(face as i32)) {
+                result.hole_indices.push(new_face as i32);
+            }
+        }
+
+        let remap_point = |p: i32| {
+            usize::try_from(p)
+                .ok()
+                .and_then(|p| point_map.get(p).copied().flatten())
+        };
+        // Sharpness is authored either per crease or per crease edge.
+        let per_edge = self.crease_sharpnesses.len() != self.crease_lengths.len();
+        let (mut start, mut edge) = (0, 0);
+        for (crease, &length) in self.crease_lengths.iter().enumerate() {
+            let length = length.max(0) as usize;
+            let edges = length.max(1) - 1;
+            let chain: Option<Vec<i32>> = self
+                .crease_indices
+                .get(start..start + length)
+                .and_then(|chain| chain.iter().map(|&p| remap_point(p)).collect());
+            if let Some(chain) = chain {
+                result.crease_indices.extend(chain);
+                result.crease_lengths.push(length as i32);
+                let sharpnesses = if per_edge {
+                    self.crease_sharpnesses.get(edge..edge + edges)
+                } else {
+                    self.crease_sharpnesses.get(crease..crease + 1)
+                };
+                result
+                    .crease_sharpnesses
+                    .extend_from_slice(sharpnesses.unwrap_or_default());
+            }
+            start += length;
+            edge += edges;
+        }
+        for (i, &corner) in self.corner_indices.iter().enumerate() {
+            if let Some(corner) = remap_point(corner) {
+                result.corner_indices.push(corner);
+                result
+                    .corner_sharpnesses
+                    .push(self.corner_sharpnesses.get(i).copied().unwrap_or(0.0));
+            }
+        }
+
+        let points: Vec<usize> = {
+            let mut points = vec![0; result.points.len()];
+            for (old, new) in point_map.iter().enumerate() {
+                if let Some(new) = new {
+                    points[*new as usize] = old;
+                }
+            }
+            points
+        };
+        let select = |interpolation: Interpolation| match interpolation {
+            Interpolation::Constant => None,
+            Interpolation::Uniform => Some(selected),
+            Interpolation::Vertex | Interpolation::Varying => Some(&points[..]),
+            Interpolation::FaceVarying => Some(&corners[..]),
+        };
+        if let Some(normals) = &self.normals {
+            result.normals = Some(pick(normals, select(self.normals_interpolation))?);
+        }
+        if let Some(uvs) = &self.uvs {
+            result.uvs = Some(pick(uvs, select(self.uvs_interpolation))?);
+        }
+        if let Some(tangents) = &self.tangents {
+            result.tangents = Some(pick(tangents, Some(&corners))?);
+        }
+        result.skeleton = self.skeleton.clone();
+        result.joint_influences = self
+            .joint_influences
+            .as_ref()
+            .map(|influences| influences.remap_points(&points));
+        result.blend_shapes = self
+            .blend_shapes
+            .iter()
+            .map(|shape| shape.remap_points(&points))
+            .collect();
+
+        Ok(result)
+    }
+
+    /// Remaps face subsets onto a mesh whose face `i` came from
+    /// `parents[i]`. Other subsets are kept unchanged.
+    pub(crate) fn remap_subsets(&self, parents: &[usize]) -> Vec<GeomSubset> {
+        self.subsets
+            .iter()
+            .map(|subset| {
+                let mut subset = subset.clone();
+                if subset.element_type == ElementType::Face {
+                    let faces: HashSet<i32> = subset.indices.iter().copied().collect();
+                    subset.indices = (0..parents.len() as i32)
+                        .filter(|&f| faces.contains(&(parents[f as usize] as i32)))
+                        .collect();
+                }
+                subset
+            })
+            .collect()
+    }
+
+    /// Resolves a primvar to one value per face-vertex.
+    fn corner_values<T: Copy>(&self, values: &[T], interpolation: Interpolation) -> Result<Vec<T>> {
+        let get = |i: usize| {
+            values.get(i).copied().ok_or(Error::IndexOutOfBounds {
+                index: i,
+                len: values.len(),
+            })
+        };
+
+        let mut result = Vec::with_capacity(self.face_vertex_indices.len());
+        for (face, offset, count) in self.faces()? {
+            for corner in offset..offset + count {
+                result.push(match interpolation {
+                    Interpolation::Constant => get(0)?,
+                    Interpolation::Uniform => get(face)?,
+                    Interpolation::Vertex | Interpolation::Varying => {
+                        get(self.point_index(corner)?)?
+                    }
+                    Interpolation::FaceVarying => get(corner)?,
+                });
+            }
+        }
+        Ok(result)
+    }
+
+    /// Generates MikkTSpace-compatible tangents and stores them in `tangents`.
+    ///
+    /// Requires normals and UVs. Tangents are computed per face-vertex: each
+    /// triangle's texture-space tangent is projected onto the corner normal
+    /// and accumulated, weighted by corner angle, over all corners that share
+    /// position, normal, UV and texture-space orientation. The `w` component
+    /// holds the bitangent sign, so `bitangent = cross(normal, tangent) * w`.
+    pub fn compute_tangents(&mut self) -> Result<()> {
+        let normals = self
+            .normals
+            .as_deref()
+            .ok_or_else(|| Error::AttributeNotFound("normals".into()))?;
+        let uvs = self
+            .uvs
+            .as_deref()
+            .ok_or_else(|| Error::AttributeNotFound("uvs".into()))?;
+
+        let corner_normals: Vec<[f32; 3]> = self
+            .corner_values(normals, self.normals_interpolation)?
+            .into_iter()
+            .map(normalize)
+            .collect();
+        let corner_uvs = self.corner_values(uvs, self.uvs_interpolation)?;
+        let topology = self.triangulate_topology()?;
+
+        let corner_count = self.face_vertex_indices.len();
+        let mut groups: HashMap<TangentKey, usize> = HashMap::new();
+        let mut corner_group = vec![None; corner_count];
+        let mut sums: Vec<[f32; 3]> = Vec::new();
+        let mut group_orientation: Vec<bool> = Vec::new();
+
+        for triangle in topology.corners.chunks_exact(3) {
+            let triangle = [triangle[0], triangle[1], triangle[2]];
+            let p = triangle.map(|c| self.points[self.face_vertex_indices[c] as usize]);
+            let uv = triangle.map(|c| corner_uvs[c]);
+
+            let e1 = sub(p[1], p[0]);
+            let e2 = sub(p[2], p[0]);
+            let (du1, dv1) = (uv[1][0] - uv[0][0], uv[1][1] - uv[0][1]);
+            let (du2, dv2) = (uv[2][0] - uv[0][0], uv[2][1] - uv[0][1]);
+            let signed_area = du1 * dv2 - du2 * dv1;
+            let orientation = signed_area > 0.0;
+            let tangent = scale(
+                sub(scale(e1, dv2), scale(e2, dv1)),
+                if orientation { 1.0 } else { -1.0 },
+            );
+
+            for (k, &corner) in triangle.iter().enumerate() {
+                let key = TangentKey::new(
+                    self.face_vertex_indices[corner],
+                    corner_normals[corner],
+                    corner_uvs[corner],
+                    orientation,
+                );
+                let group = *groups.entry(key).or_insert_with(|| {
+                    sums.push([0.0; 3]);
+                    group_orientation.push(orientation);
+                    sums.len() - 1
+                });
+                corner_group[corner] = Some(group);
+
+                // Degenerate UV mappings contribute nothing, as in MikkTSpace.
+                if signed_area == 0.0 {
+                    continue;
+                }
+
+                let n = corner_normals[corner];
+                let projected = normalize(sub(tangent, scale(n, dot(n, tangent))));
+                let a = normalize(sub(p[(k + 1) % 3], p[k]));
+                let b = normalize(sub(p[(k + 2) % 3], p[k]));
+                let angle = dot(a, b).clamp(-1.0, 1.0).acos();
+                sums[group] = add(sums[group], scale(projected, angle));
+            }
+        }
+
+        let mut tangents = Vec::with_capacity(corner_count);
+        for corner in 0..corner_count {
+            let n = corner_normals[corner];
+            let (mut t, w) = match corner_group[corner] {
+                Some(group) => {
+                    let w = if group_orientation[group] { 1.0 } else { -1.0 };
+                    (normalize(sums[group]), w)
+                }
+                None => ([0.0; 3], 1.0),
+            };
+            if t == [0.0; 3] {
+                t = perpendicular(n);
+            }
+            tangents.push([t[0], t[1], t[2], w]);
+        }
+
+        self.tangents = Some(tangents);
+        Ok(())
+    }
+
+    /// Generates normals from the face geometry and stores them in `normals`.
+    ///
+    /// Any existing normals are replaced and `normals_interpolation` is set
+    /// to match the chosen [`NormalMode`]. Normals point away from the
+    /// front face, so `leftHanded` meshes get flipped normals.
+    pub fn compute_normals(&mut self, mode: NormalMode) -> Result<()> {
+        let faces = self.faces()?;
+        let holes: HashSet<i32> = self.hole_indices.iter().copied().collect();
+        let flip = if self.orientation == Orientation::LeftHanded {
+            -1.0
+        } else {
+            1.0
+        };
+
+        // Area-weighted face normals (the Newell normal has length 2 * area).
+        let mut face_normals = Vec::with_capacity(faces.len());
+        for &(_, offset, count) in &faces {
+            let mut normal = [0.0f32; 3];
+            for i in 0..count {
+                let p = self.points[self.point_index(offset + i)?];
+                let q = self.points[self.point_index(offset + (i + 1) % count)?];
+                normal[0] += (p[1] - q[1]) * (p[2] + q[2]);
+                normal[1] += (p[2] - q[2]) * (p[0] + q[0]);
+                normal[2] += (p[0] - q[0]) * (p[1] + q[1]);
+            }
+            face_normals.push(scale(normal, flip * 0.5));
+        }
+
+        match mode {
+            NormalMode::Flat => {
+                self.normals = Some(face_normals.iter().map(|&n| normalize(n)).collect());
+                self.normals_interpolation = Interpolation::Uniform;
+            }
+            NormalMode::Smooth => {
+                let mut normals = vec![[0.0f32; 3]; self.points.len()];
+                for &(face, offset, count) in &faces {
+                    if count < 3 || holes.contains(&(face as i32)) {
+                        continue;
+                    }
+                    for i in 0..count {
+                        let point = self.point_index(offset + i)?;
+                        let angle = self.corner_angle(offset, count, i)?;
+                        normals[point] = add(normals[point], scale(face_normals[face], angle));
+                    }
+                }
+                self.normals = Some(normals.into_iter().map(normalize).collect());
+                self.normals_interpolation = Interpolation::Vertex;
+            }
+            NormalMode::Crease(threshold) => {
+                let cos_threshold = threshold.cos();
+                let unit: Vec<[f32; 3]> = face_normals.iter().map(|&n| normalize(n)).collect();
+
+                // Weighted contributions of every face touching each point.
+                let mut incident: Vec<Vec<(usize, [f32; 3])>> = vec![Vec::new(); self.points.len()];
+                for &(face, offset, count) in &faces {
+                    if count < 3 || holes.contains(&(face as i32)) {
+                        continue;
+                    }
+                    for i in 0..count {
+                        let point = self.point_index(offset + i)?;
+                        let angle = self.corner_angle(offset, count, i)?;
+                        incident[point].push((face, scale(face_normals[face], angle)));
+                    }
+                }
+
+                let mut normals = Vec::with_capacity(self.face_vertex_indices.len());
+                for &(face, offset, count) in &faces {
+                    for i in 0..count {
+                        let point = self.point_index(offset + i)?;
+                        let mut normal = [0.0f32; 3];
+                        for &(other, weighted) in &incident[point] {
+                            if other == face || dot(unit[face], unit[other]) >= cos_threshold {
+                                normal = add(normal, weighted);
+                            }
+                        }
+                        if normal == [0.0; 3] {
+                            normal = unit[face];
+                        }
+                        normals.push(normalize(normal));
+                    }
+                }
+                self.normals = Some(normals);
+                self.normals_interpolation = Interpolation::FaceVarying;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the interior angle at corner `i` of the face starting at `offset`.
+    fn corner_angle(&self, offset: usize, count: usize, i: usize) -> Result<f32> {
+        let p = self.points[self.point_index(offset + i)?];
+        let prev = self.points[self.point_index(offset + (i + count - 1) % count)?];
+        let next = self.points[self.point_index(offset + (i + 1) % count)?];
+        let a = normalize(sub(prev, p));
+        let b = normalize(sub(next, p));
+        Ok(dot(a, b).clamp(-1.0, 1.0).acos())
+    }
+
+    /// Computes the triangle corners of every face, validating the topology.
+    fn triangulate_topology(&self) -> Result<TriangleTopology> {
+        let mut topology = TriangleTopology::default();
+        let mut polygon = Vec::new();
+        let holes: HashSet<i32> = self.hole_indices.iter().copied().collect();
+
+        for (face, offset, count) in self.faces()? {
+            if count < 3 || holes.contains(&(face as i32)) {
+                continue;
+            }
+
+            polygon.clear();
+            for corner in offset..offset + count {
+                polygon.push(self.point_index(corner)?);
+            }
+
+            for [a, b, c] in ear_clip(&self.points, &polygon) {
+                topology
+                    .corners
+                    .extend_from_slice(&[offset + a, offset + b, offset + c]);
+                topology.triangle_faces.push(face);
+            }
+        }
+
+        Ok(topology)
+    }
+
+    /// Guesses a primvar's interpolation from its number of values.
+    fn infer_interpolation(&self, len: usize) -> Interpolation {
+        if len == self.points.len() {
+            Interpolation::Vertex
+        } else if len == self.face_vertex_indices.len() {
+            Interpolation::FaceVarying
+        } else if len == self.face_vertex_counts.len() {
+            Interpolation::Uniform
+        } else {
+            Interpolation::Constant
+        }
+    }
+
+    /// Returns `(face, offset, count)` for every face, checking that the
+    /// face vertex counts fit within `face_vertex_indices`.
+    fn faces(&self) -> Result<Vec<(usize, usize, usize)>> {
+        let len = self.face_vertex_indices.len();
+        let mut faces = Vec::with_capacity(self.face_vertex_counts.len());
+        let mut offset = 0;
+
+        for (face, &count) in self.face_vertex_counts.iter().enumerate() {
+            if count < 0 {
+                return Err(Error::InvalidMesh(format!(
+                    "face {} has negative vertex count {}",
+                    face, count
+                )));
+            }
+
+            let count = count as usize;
+            if offset + count > len {
+                return Err(Error::IndexOutOfBounds {
+                    index: offset + count - 1,
+                    len,
+                });
+            }
+
+            faces.push((face, offset, count));
+            offset += count;
+        }
+
+        Ok(faces)
+    }
+
+    /// Returns the point index stored at a face-vertex corner.
+    fn point_index(&self, corner: usize) -> Result<usize> {
+        let index = self.face_vertex_indices[corner];
+        if index < 0 || index as usize >= self.points.len() {
+            return Err(Error::IndexOutOfBounds {
+                index: index.max(0) as usize,
+                len: self.points.len(),
+            });
+        }
+        Ok(index as usize)
+    }
+}
+
+/// Triangles produced by triangulation, expressed in terms of the source mesh.
+#[derive(Default)]
+struct TriangleTopology {
+    /// Source face-vertex corner for each output corner.
+    corners: Vec<usize>,
+    /// Source face for each output triangle.
+    triangle_faces: Vec<usize>,
+}
+
+impl TriangleTopology {
+    /// Remaps primvar values of the source mesh onto the triangulated mesh.
+    fn remap<T: Copy>(&self, values: &[T], interpolation: Interpolation) -> Result<Vec<T>> {
+        let sources = match interpolation {
+            Interpolation::Constant | Interpolation::Vertex | Interpolation::Varying => {
+                return Ok(values.to_vec());
+            }
+            Interpolation::Uniform => &self.triangle_faces,
+            Interpolation::FaceVarying => &self.corners,
+        };
+
+        sources
+            .iter()
+            .map(|&i| {
+                values.get(i).copied().ok_or(Error::IndexOutOfBounds {
+                    index: i,
+                    len: values.len(),
+                })
+            })
+            .collect()
+    }
+}
+
+/// Identifies face-vertices that MikkTSpace treats as the same vertex.
+#[derive(PartialEq, Eq, Hash)]
+struct TangentKey {
+    point: i32,
+    normal: [u32; 3],
+    uv: [u32; 2],
+    orientation: bool,
+}
+
+impl TangentKey {
+    fn new(point: i32, normal: [f32; 3], uv: [f32; 2], orientation: bool) -> Self {
+        TangentKey {
+            point,
+            normal: normal.map(f32::to_bits),
+            uv: uv.map(f32::to_bits),
+            orientation,
+        }
+    }
+}
+
+/// Splits a polygon into triangles by ear clipping.
+///
+/// `polygon` holds point indices in face order. The returned triangles are
+/// local positions within `polygon` and keep the polygon's winding.
+fn ear_clip(points: &[[f32; 3]], polygon: &[usize]) -> Vec<[usize; 3]> {
+    let n = polygon.len();
+    if n == 3 {
+        return vec![[0, 1, 2]];
+    }
+
+    // Project onto the plane most perpendicular to the Newell normal.
+    let mut normal = [0.0f64; 3];
+    for i in 0..n {
+        let p = points[polygon[i]];
+        let q = points[polygon[(i + 1) % n]];
+        normal[0] += (p[1] as f64 - q[1] as f64) * (p[2] as f64 + q[2] as f64);
+        normal[1] += (p[2] as f64 - q[2] as f64) * (p[0] as f64 + q[0] as f64);
+        normal[2] += (p[0] as f64 - q[0] as f64) * (p[1] as f64 + q[1] as f64);
+    }
+    let abs = normal.map(f64::abs);
+    if abs[0] + abs[1] + abs[2] <= f64::EPSILON {
+        return fan(n);
+    }
+    let (u, v, sign) = if abs[0] >= abs[1] && abs[0] >= abs[2] {
+        (1, 2, normal[0].signum())
+    } else if abs[1] >= abs[2] {
+        (2, 0, normal[1].signum())
+    } else {
+        (0, 1, normal[2].signum())
+    };
+    // Flip one axis when the polygon faces away so it is counter-clockwise in 2D.
+    let projected: Vec<[f64; 2]> = polygon
+        .iter()
+        .map(|&i| [points[i][u] as f64, points[i][v] as f64 * sign])
+        .collect();
+
+    let mut remaining: Vec<usize> = (0..n).collect();
+    let mut triangles = Vec::with_capacity(n - 2);
+
+    while remaining.len() > 3 {
+        let m = remaining.len();
+        let ear = (0..m)
+            .find(|&i| {
+                let (a, b, c) = (
+                    remaining[(i + m - 1) % m],
+                    remaining[i],
+                    remaining[(i + 1) % m],
+                );
+                is_ear(&projected, &remaining, a, b, c)
+            })
+            // No clean ear (degenerate or self-intersecting input): clip the
+            // most convex vertex so triangulation always makes progress.
+            .unwrap_or_else(|| {
+                (0..m)
+                    .max_by(|&i, &j| {
+                        let area = |k: usize| {
+                            cross(
+                                projected[remaining[(k + m - 1) % m]],
+                                projected[remaining[k]],
+                                projected[remaining[(k + 1) % m]],
+                            )
+                        };
+                        area(i).total_cmp(&area(j))
+                    })
+                    .unwrap_or(0)
+            });
+
+        triangles.push([
+            remaining[(ear + m - 1) % m],
+            remaining[ear],
+            remaining[(ear + 1) % m],
+        ]);
+        remaining.remove(ear);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+    triangles
+}
+
+/// Returns true if the corner `b` between `a` and `c` can be clipped.
+fn is_ear(projected: &[[f64; 2]], remaining: &[usize], a: usize, b: usize, c: usize) -> bool {
+    let (pa, pb, pc) = (projected[a], projected[b], projected[c]);
+    if cross(pa, pb, pc) <= 0.0 {
+        return false;
+    }
+
+    remaining
+        .iter()
+        .filter(|&&k| k != a && k != b && k != c)
+        .all(|&k| {
+            let p = projected[k];
+            // Points coincident with the ear's corners do not block it.
+            if p == pa || p == pb || p == pc {
+                return true;
+            }
+            !(cross(pa, pb, p) >= 0.0 && cross(pb, pc, p) >= 0.0 && cross(pc, pa, p) >= 0.0)
+        })
+}
+
+/// Twice the signed area of the 2D triangle `abc`.
+fn cross(a: [f64; 2], b: [f64; 2], c: [f64; 2]) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Fan triangulation of an `n`-sided polygon, used for degenerate faces.
+fn fan(n: usize) -> Vec<[usize; 3]> {
+    (1..n - 1).map(|i| [0, i, i + 1]).collect()
+}
+
+/// How [`MeshExtractor`] handles instanced meshes: meshes under point
+/// instancer prototypes and meshes shared by native instances.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Instancing {
+    /// Emit one world-space copy of each prototype mesh per instance.
+    #[default]
+    Expand,
+    /// Leave prototype meshes out of [`MeshExtractor::meshes`]; read them
+    /// with their instance transforms from
+    /// [`MeshExtractor::instanced_meshes`].
+    Prototypes,
+}
+
+/// A prototype mesh and the transforms of the instances that use it.
+#[derive(Debug, Clone)]
+pub struct InstancedMesh {
+    /// The prototype mesh. Its `world_transform` is relative to the
+    /// instance. For point instancers this includes the transform of the
+    /// prototype prim; for native instances the instance prim's transform
+    /// replaces it.
+    pub prototype: Mesh,
+    /// The path of the point instancer. For native instances, the path of
+    /// the prim the prototype's meshes were read from: the referenced prim
+    /// when it is on the stage (see
+    /// [`PrototypeId::source_path`](crate::PrototypeId::source_path)),
+    /// otherwise the first instance.
+    pub instancer: String,
+    /// World transform of each visible instance. The world transform of a
+    /// copy is `prototype.world_transform * transforms[i]`.
+    pub transforms: Vec<[[f64; 4]; 4]>,
+}
+
+/// Returns the index of the face group bound to `material`, adding it if
+/// needed.
+fn material_group(
+    groups: &mut Vec<(Option<String>, Vec<usize>)>,
+    material: Option<String>,
+) -> usize {
+    match groups.iter().position(|(m, _)| *m == material) {
+        Some(group) => group,
+        None => {
+            groups.push((material, Vec::new()));
+            groups.len() - 1
+        }
     }
 }
 
 /// Utility to extract meshes from a USD stage.
 ///
-/// Note: Due to limitations in the current tinyusdz C API, actual geometry
-/// data cannot be extracted. This extractor identifies Mesh prims and creates
-/// placeholder Mesh objects with names and property lists.
+/// Meshes below inactive or invisible prims are skipped, as are meshes whose
+/// computed purpose is not listed in [`purposes`](Self::purposes).
 pub struct MeshExtractor<'a> {
     stage: &'a Stage,
+    instancing: Instancing,
+    purposes: Vec<Purpose>,
 }
 
 impl<'a> MeshExtractor<'a> {
     /// Creates a new mesh extractor for the given stage.
     pub fn new(stage: &'a Stage) -> Self {
-        MeshExtractor { stage }
+        MeshExtractor {
+            stage,
+            instancing: Instancing::default(),
+            purposes: vec![Purpose::Default, Purpose::Render],
+        }
+    }
+
+    /// Sets how instanced meshes are extracted.
+    pub fn instancing(mut self, instancing: Instancing) -> Self {
+        self.instancing = instancing;
+        self
+    }
+
+    /// Sets the purposes of the meshes to extract. Defaults to `default`
+    /// and `render`, leaving out `proxy` and `guide` geometry.
+    pub fn purposes(mut self, purposes: &[Purpose]) -> Self {
+        self.purposes = purposes.to_vec();
+        self
     }
 
     /// Returns an iterator over all meshes in the stage.
     ///
-    /// Note: The returned meshes will have names and property information
-    /// but may not contain actual geometry data due to C API limitations.
+    /// Each mesh carries its local transform and the world transform
+    /// accumulated from its ancestors. Skinned meshes also carry the path of
+    /// the skeleton bound to them or to their nearest bound ancestor.
+    ///
+    /// Meshes under point instancer prototypes and native instances are
+    /// expanded into one copy per visible instance, or skipped, depending on
+    /// [`Instancing`]. Meshes under `class` prims are abstract and skipped.
     pub fn meshes(&self) -> impl Iterator<Item = Mesh> + '_ {
-        self.stage.traverse().filter_map(|prim| {
-            if prim.is_mesh() {
-                Some(Self::extract_mesh(&prim))
-            } else {
-                None
+        let (meshes, instanced) = self.extract();
+        let mut meshes: Vec<Mesh> = meshes.into_iter().map(|(_, mesh)| mesh).collect();
+        if self.instancing == Instancing::Expand {
+            for instanced in instanced {
+                for (i, transform) in instanced.transforms.iter().enumerate() {
+                    let mut mesh = instanced.prototype.clone();
+                    mesh.name = format!("{}_{}", mesh.name, i);
+                    mesh.world_transform = matrix_multiply(mesh.world_transform, *transform);
+                    meshes.push(mesh);
+                }
             }
-        })
+        }
+        meshes.into_iter()
+    }
+
+    /// Returns the meshes used as point instancer prototypes or shared by
+    /// native instances, each once with the world transforms of its visible
+    /// instances.
+    ///
+    /// Point instances are placed at the earliest authored time.
+    pub fn instanced_meshes(&self) -> Vec<InstancedMesh> {
+        self.extract().1
+    }
+
+    /// Extracts plain meshes, paired with their prim paths, and instanced
+    /// prototype meshes.
+    pub(crate) fn extract(&self) -> (Vec<(String, Mesh)>, Vec<InstancedMesh>) {
+        let mut meshes = Vec::new();
+        let mut paths = Vec::new();
+        let mut worlds = HashMap::new();
+        let mut instancers = Vec::new();
+        let mut instances: Vec<(PrototypeId, Vec<String>)> = Vec::new();
+        let mut abstract_prims = Vec::new();
+        let mut hidden_prims = Vec::new();
+        let mut purposes = HashMap::new();
+        let mut skeleton_bindings = HashMap::new();
+        let mut blend_shape_specs = HashMap::new();
+        let mut blend_shape_targets = Vec::new();
+        self.stage.walk_transforms(|visit, local, world| {
+            worlds.insert(visit.path.clone(), *world);
+            if let Some(skeleton) = skel::skeleton_binding(visit.spec) {
+                skeleton_bindings.insert(visit.path.clone(), skeleton);
+            }
+            if visit.spec.type_name == "BlendShape" {
+                blend_shape_specs.insert(visit.path.clone(), visit.spec.clone());
+            }
+            if visit.spec.specifier == "class" {
+                abstract_prims.push(visit.path.clone());
+            }
+            // A non-default purpose is inherited by the whole subtree.
+            let inherited = visit
+                .path
+                .rfind('/')
+                .and_then(|i| purposes.get(&visit.path[..i]))
+                .copied()
+                .unwrap_or_default();
+            let purpose = match inherited {
+                Purpose::Default => prim::purpose(visit.spec),
+                inherited => inherited,
+            };
+            purposes.insert(visit.path.clone(), purpose);
+            if prim::bool_meta(visit.spec, "active") == Some(false)
+                || prim::visibility(visit.spec) == Visibility::Invisible
+            {
+                hidden_prims.push(visit.path.clone());
+            }
+            if let Some(prototype) = prim::prototype(visit.spec) {
+                match instances.iter_mut().find(|(p, _)| *p == prototype) {
+                    Some((_, paths)) => paths.push(visit.path.clone()),
+                    None => instances.push((prototype, vec![visit.path.clone()])),
+                }
+            }
+            if visit.spec.type_name == "PointInstancer" {
+                let mut instancer = PointInstancer::from_spec(visit.spec, &visit.path);
+                instancer.world_transform = *world;
+                instancers.push(instancer);
+            }
+
+            if visit.spec.type_name == "Mesh" {
+                let mut mesh = Self::extract_mesh(visit.spec);
+                mesh.local_transform = *local;
+                mesh.world_transform = *world;
+                if mesh.joint_influences.is_some() {
+                    mesh.skeleton = skel::inherited_binding(&skeleton_bindings, &visit.path);
+                }
+                blend_shape_targets.push(Self::blend_shape_targets(visit.spec));
+                paths.push(visit.path.clone());
+                meshes.push(mesh);
+            }
+        });
+
+        // Blend shape targets may be defined anywhere, so resolve them once
+        // every prim has been visited.
+        for (mesh, targets) in meshes.iter_mut().zip(blend_shape_targets) {
+            mesh.blend_shapes = targets
+                .into_iter()
+                .filter_map(|(name, path)| {
+                    let spec = blend_shape_specs.get(&path)?;
+                    Some(BlendShape::from_spec(spec, &name, &path))
+                })
+                .collect();
+        }
+
+        // Instances nested in another instance are part of its prototype.
+        let all_instances: Vec<String> = instances
+            .iter()
+            .flat_map(|(_, paths)| paths.iter().cloned())
+            .collect();
+        for (_, paths) in &mut instances {
+            paths.retain(|path| {
+                !all_instances
+                    .iter()
+                    .any(|outer| outer != path && is_within(path, outer))
+            });
+        }
+        instances.retain(|(_, paths)| !paths.is_empty());
+        let is_hidden = |path: &str| {
+            abstract_prims
+                .iter()
+                .chain(&hidden_prims)
+                .any(|p| is_within(path, p))
+                || purposes
+                    .get(path)
+                    .is_some_and(|purpose| !self.purposes.contains(purpose))
+        };
+
+        let mut prototype_meshes = HashSet::new();
+        let mut instanced = Vec::new();
+        for instancer in &instancers {
+            let transforms = instancer.instance_transforms(f64::NEG_INFINITY);
+            let hidden = is_hidden(&instancer.path);
+
+            for (index, prototype) in instancer.prototypes.iter().enumerate() {
+                let instances: Vec<_> = (0..instancer.instance_count())
+                    .filter(|&i| {
+                        !hidden
+                            && instancer.proto_indices[i] == index as i32
+                            && instancer.is_visible(i)
+                    })
+                    .map(|i| matrix_multiply(transforms[i], instancer.world_transform))
+                    .collect();
+
+                // Express prototype meshes relative to the prototype's parent,
+                // so the prototype prim's own transform is kept.
+                let parent = prototype
+                    .rfind('/')
+                    .and_then(|i| worlds.get(&prototype[..i]))
+                    .copied()
+                    .unwrap_or_else(xform::identity);
+                let to_parent = xform::invert(&parent).unwrap_or_else(xform::identity);
+
+                for (mesh, path) in meshes.iter().zip(&paths) {
+                    if !is_within(path, prototype) {
+                        continue;
+                    }
+                    prototype_meshes.insert(path.clone());
+
+                    let mut mesh = mesh.clone();
+                    mesh.world_transform = matrix_multiply(mesh.world_transform, to_parent);
+                    instanced.push(InstancedMesh {
+                        prototype: mesh,
+                        instancer: instancer.path.clone(),
+                        transforms: instances.clone(),
+                    });
+                }
+            }
+        }
+
+        // Native instances share the meshes of their prototype. When the
+        // prototype is not on the stage, the meshes composed under the first
+        // instance stand in for it.
+        for (prototype, instance_paths) in &instances {
+            let root = match prototype.source_path() {
+                Some(source) if paths.iter().any(|path| is_within(path, source)) => source,
+                _ => &instance_paths[0],
+            };
+            let transforms: Vec<_> = instance_paths
+                .iter()
+                .filter(|path| !is_hidden(path))
+                .map(|path| worlds[path])
+                .collect();
+            let to_root = worlds
+                .get(root)
+                .and_then(xform::invert)
+                .unwrap_or_else(xform::identity);
+
+            for (mesh, path) in meshes.iter().zip(&paths) {
+                if !is_within(path, root) {
+                    continue;
+                }
+                let mut mesh = mesh.clone();
+                mesh.world_transform = matrix_multiply(mesh.world_transform, to_root);
+                instanced.push(InstancedMesh {
+                    prototype: mesh,
+                    instancer: root.to_string(),
+                    transforms: transforms.clone(),
+                });
+            }
+        }
+
+        let meshes = meshes
+            .into_iter()
+            .zip(paths)
+            .filter(|(_, path)| {
+                !prototype_meshes.contains(path)
+                    && !is_hidden(path)
+                    && !all_instances
+                        .iter()
+                        .any(|instance| is_within(path, instance))
+            })
+            .map(|(mesh, path)| (path, mesh))
+            .collect();
+        (meshes, instanced)
     }
 
-    /// Extracts mesh data from a prim.
-    fn extract_mesh(prim: &Prim<'_>) -> Mesh {
-        let mesh = Mesh::new(prim.name());
+    /// Extracts mesh data from a Mesh prim's spec.
+    fn extract_mesh(spec: &PrimSpec) -> Mesh {
+        let mut mesh = Mesh::new(spec.name.as_str());
+
+        if let Some(Value::Float3Array(points)) = spec.value("points") {
+            mesh.points = points;
+        }
+        if let Some(Value::IntArray(counts)) = spec.value("faceVertexCounts") {
+            mesh.face_vertex_counts = counts;
+        }
+        if let Some(Value::IntArray(indices)) = spec.value("faceVertexIndices") {
+            mesh.face_vertex_indices = indices;
+        }
+        if let Some(Value::IntArray(holes)) = spec.value("holeIndices") {
+            mesh.hole_indices = holes;
+        }
+        if let Some(orientation) = spec
+            .value("orientation")
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(Orientation::from_token)
+        {
+            mesh.orientation = orientation;
+        }
+        if let Some(scheme) = spec
+            .value("subdivisionScheme")
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(SubdivisionScheme::from_token)
+        {
+            mesh.subdivision_scheme = scheme;
+        }
+        if let Some(boundary) = spec
+            .value("interpolateBoundary")
+            .as_ref()
+            .and_then(Value::as_str)
+            .and_then(InterpolateBoundary::from_token)
+        {
+            mesh.interpolate_boundary = boundary;
+        }
+        if let Some(Value::IntArray(indices)) = spec.value("creaseIndices") {
+            mesh.crease_indices = indices;
+        }
+        if let Some(Value::IntArray(lengths)) = spec.value("creaseLengths") {
+            mesh.crease_lengths = lengths;
+        }
+        if let Some(Value::FloatArray(sharpnesses)) = spec.value("creaseSharpnesses") {
+            mesh.crease_sharpnesses = sharpnesses;
+        }
+        if let Some(Value::IntArray(indices)) = spec.value("cornerIndices") {
+            mesh.corner_indices = indices;
+        }
+        if let Some(Value::FloatArray(sharpnesses)) = spec.value("cornerSharpnesses") {
+            mesh.corner_sharpnesses = sharpnesses;
+        }
+
+        mesh.material_binding = Self::material_binding(spec);
+        mesh.joint_influences = JointInfluences::from_spec(spec);
+        mesh.subsets = spec
+            .children
+            .iter()
+            .filter(|child| child.type_name == "GeomSubset")
+            .map(Self::extract_subset)
+            .collect();
 
-        // Note: Due to C API limitations, we can only get property names
-        // but not their values. The property extraction functions
-        // (c_tinyusd_prim_property_get, etc.) are not yet implemented.
-        let _property_names = prim.property_names();
+        // `primvars:normals` takes precedence over the `normals` attribute.
+        let normals =
+            Self::primvar(spec, "primvars:normals").or_else(|| Self::primvar(spec, "normals"));
+        if let Some((Value::Float3Array(normals), interpolation)) = normals {
+            mesh.normals_interpolation = interpolation.unwrap_or(Interpolation::Vertex);
+            mesh.normals = Some(normals);
+        }
 
-        // Future: When C API supports it, extract:
-        // - points (vertex positions)
-        // - faceVertexCounts
-        // - faceVertexIndices
-        // - normals
-        // - primvars:st (UVs)
-        // - xformOp:transform (transform matrix)
+        // Prefer `primvars:st`, then any other texCoord2 primvar.
+        let uv_name = spec
+            .property("primvars:st")
+            .or_else(|| {
+                spec.properties.iter().find(|p| {
+                    p.name.starts_with("primvars:")
+                        && !p.name.ends_with(":indices")
+                        && p.type_name.starts_with("texCoord2")
+                })
+            })
+            .map(|p| p.name.clone());
+        if let Some((Value::Float2Array(uvs), interpolation)) =
+            uv_name.and_then(|name| Self::primvar(spec, &name))
+        {
+            mesh.uvs_interpolation =
+                interpolation.unwrap_or_else(|| mesh.infer_interpolation(uvs.len()));
+            mesh.uvs = Some(uvs);
+        }
 
         mesh
     }
 
+    /// Pairs a mesh's `skel:blendShapes` names with their target paths.
+    fn blend_shape_targets(spec: &PrimSpec) -> Vec<(String, String)> {
+        let Some(Value::TokenArray(names)) = spec.value("skel:blendShapes") else {
+            return Vec::new();
+        };
+        let targets = spec
+            .property("skel:blendShapeTargets")
+            .map(|p| p.targets.clone())
+            .unwrap_or_default();
+        names.into_iter().zip(targets).collect()
+    }
+
+    /// Extracts a `GeomSubset` child prim.
+    fn extract_subset(spec: &PrimSpec) -> GeomSubset {
+        let token = |name: &str| {
+            spec.value(name)
+                .as_ref()
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+
+        GeomSubset {
+            name: spec.name.clone(),
+            element_type: token("elementType")
+                .and_then(|t| ElementType::from_token(&t))
+                .unwrap_or_default(),
+            indices: match spec.value("indices") {
+                Some(Value::IntArray(indices)) => indices,
+                _ => Vec::new(),
+            },
+            family_name: token("familyName"),
+            material_binding: Self::material_binding(spec),
+        }
+    }
+
+    /// Returns the target of a prim's `material:binding` relationship.
+    fn material_binding(spec: &PrimSpec) -> Option<String> {
+        spec.property("material:binding")
+            .filter(|p| p.is_relationship())
+            .and_then(|p| p.targets.first().cloned())
+    }
+
+    /// Reads a primvar's values, flattening `<name>:indices` if present.
+    fn primvar(spec: &PrimSpec, name: &str) -> Option<(Value, Option<Interpolation>)> {
+        let property = spec.property(name)?;
+        let value = spec.value(name)?;
+        let interpolation = spec_interpolation(property);
+
+        let value = match spec.value(&format!("{}:indices", name)) {
+            Some(Value::IntArray(indices)) => match value {
+                Value::Float3Array(v) => Value::Float3Array(flatten_indexed(&v, &indices)),
+                Value::Float2Array(v) => Value::Float2Array(flatten_indexed(&v, &indices)),
+                other => other,
+            },
+            _ => value,
+        };
+
+        Some((value, interpolation))
+    }
+
     /// Extracts all meshes and returns them as a vector.
     pub fn collect(&self) -> Vec<Mesh> {
         self.meshes().collect()
     }
 }
 
-/// Returns a 4x4 identity matrix.
-fn identity_matrix() -> [[f64; 4]; 4] {
-    [
-        [1.0, 0.0, 0.0, 0.0],
-        [0.0, 1.0, 0.0, 0.0],
-        [0.0, 0.0, 1.0, 0.0],
-        [0.0, 0.0, 0.0, 1.0],
-    ]
+/// Expands an indexed primvar, skipping indices that are out of range.
+fn flatten_indexed<T: Copy>(values: &[T], indices: &[i32]) -> Vec<T> {
+    indices
+        .iter()
+        .filter_map(|&i| values.get(usize::try_from(i).ok()?).copied())
+        .collect()
+}
+
+fn add(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f32; 3], s: f32) -> [f32; 3] {
+    [a[0] * s, a[1] * s, a[2] * s]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// Normalizes a vector, leaving zero-length vectors unchanged.
+fn normalize(a: [f32; 3]) -> [f32; 3] {
+    let len = dot(a, a).sqrt();
+    if len > 0.0 {
+        scale(a, 1.0 / len)
+    } else {
+        a
+    }
+}
+
+/// Returns an arbitrary unit vector perpendicular to `n`.
+fn perpendicular(n: [f32; 3]) -> [f32; 3] {
+    let axis = if n[0].abs() < 0.9 {
+        [1.0, 0.0, 0.0]
+    } else {
+        [0.0, 1.0, 0.0]
+    };
+    normalize(sub(axis, scale(n, dot(n, axis))))
+}
+
+/// Picks `values` at `indices`, or returns them unchanged for `None`.
+fn pick<T: Copy>(values: &[T], indices: Option<&[usize]>) -> Result<Vec<T>> {
+    let Some(indices) = indices else {
+        return Ok(values.to_vec());
+    };
+    indices
+        .iter()
+        .map(|&i| {
+            values.get(i).copied().ok_or(Error::IndexOutOfBounds {
+                index: i,
+                len: values.len(),
+            })
+        })
+        .collect()
 }
 
 /// Multiplies two 4x4 matrices.
@@ -219,3 +1697,415 @@ pub fn matrix_to_f32(m: [[f64; 4]; 4]) -> [[f32; 4]; 4] {
         ],
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quad_mesh() -> Mesh {
+        let mut mesh = Mesh::new("quad");
+        mesh.points = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        mesh.face_vertex_counts = vec![4];
+        mesh.face_vertex_indices = vec![0, 1, 2, 3];
+        mesh
+    }
+
+    #[test]
+    fn test_triangulate_concave_polygon() {
+        // An arrow-head shape whose reflex vertex (index 3) breaks a fan from 0.
+        let mut mesh = Mesh::new("concave");
+        mesh.points = vec![
+            [0.0, 0.0, 0.0],
+            [2.0, 0.0, 0.0],
+            [2.0, 2.0, 0.0],
+            [1.0, 0.5, 0.0],
+            [0.0, 2.0, 0.0],
+        ];
+        mesh.face_vertex_counts = vec![5];
+        mesh.face_vertex_indices = vec![0, 1, 2, 3, 4];
+
+        let tri = mesh.triangulate().unwrap();
+        assert_eq!(tri.face_vertex_counts, vec![3, 3, 3]);
+
+        // All triangles keep the polygon's counter-clockwise winding and the
+        // total area matches the polygon area (2*2 - 1.5 = 2.5).
+        let mut area = 0.0;
+        for t in tri.face_vertex_indices.chunks(3) {
+            let [a, b, c] = [0, 1, 2].map(|i| tri.points[t[i] as usize]);
+            let signed = (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0]);
+            assert!(signed > 0.0);
+            area += signed * 0.5;
+        }
+        assert!((area - 2.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_triangulate_carries_face_varying_uvs() {
+        let mut mesh = quad_mesh();
+        mesh.uvs = Some(vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
+        mesh.uvs_interpolation = Interpolation::FaceVarying;
+        mesh.normals = Some(vec![[0.0, 0.0, 1.0]]);
+        mesh.normals_interpolation = Interpolation::Uniform;
+
+        let tri = mesh.triangulate().unwrap();
+        let uvs = tri.uvs.unwrap();
+        assert_eq!(uvs.len(), 6);
+        for (corner, &index) in tri.face_vertex_indices.iter().enumerate() {
+            let p = mesh.points[index as usize];
+            assert_eq!(uvs[corner], [p[0], p[1]]);
+        }
+        assert_eq!(tri.normals.unwrap(), vec![[0.0, 0.0, 1.0]; 2]);
+    }
+
+    #[test]
+    fn test_triangulate_skips_holes_and_degenerate_faces() {
+        let mut mesh = quad_mesh();
+        mesh.face_vertex_counts = vec![3, 2, 3];
+        mesh.face_vertex_indices = vec![0, 1, 2, 0, 1, 0, 2, 3];
+        mesh.hole_indices = vec![0];
+
+        let tri = mesh.triangulate().unwrap();
+        assert_eq!(tri.face_vertex_indices, vec![0, 2, 3]);
+    }
+
+    #[test]
+    fn test_triangulate_rejects_out_of_range_indices() {
+        let mut mesh = quad_mesh();
+        mesh.face_vertex_indices = vec![0, 1, 2, 7];
+        assert!(matches!(
+            mesh.triangulate(),
+            Err(Error::IndexOutOfBounds { index: 7, len: 4 })
+        ));
+
+        let mut mesh = quad_mesh();
+        mesh.face_vertex_counts = vec![4, 3];
+        assert!(matches!(
+            mesh.triangulate(),
+            Err(Error::IndexOutOfBounds { .. })
+        ));
+    }
+
+    /// An open box corner: three unit quads meeting at the origin.
+    fn corner_mesh() -> Mesh {
+        let mut mesh = Mesh::new("corner");
+        mesh.points = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [0.0, 1.0, 0.0],
+            [0.0, 0.0, 1.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 1.0],
+            [1.0, 0.0, 1.0],
+        ];
+        mesh.face_vertex_counts = vec![4, 4, 4];
+        mesh.face_vertex_indices = vec![0, 2, 4, 1, 0, 3, 5, 2, 0, 1, 6, 3];
+        mesh
+    }
+
+    #[test]
+    fn test_split_by_subsets() {
+        let spec = crate::usda::parse_prim(
+            r#"def Mesh "Strip" (
+    prepend apiSchemas = ["MaterialBindingAPI"]
+)
+{
+    int[] faceVertexCounts = [4, 4, 4]
+    int[] faceVertexIndices = [0, 1, 5, 4, 1, 2, 6, 5, 2, 3, 7, 6]
+    point3f[] points = [(0, 0, 0), (1, 0, 0), (2, 0, 0), (3, 0, 0), (0, 1, 0), (1, 1, 0), (2, 1, 0), (3, 1, 0)]
+    texCoord2f[] primvars:st = [(0, 0), (1, 0), (2, 0), (3, 0), (0, 1), (1, 1), (2, 1), (3, 1)] (
+        interpolation = "vertex"
+    )
+    rel material:binding = </Looks/Base>
+    uniform token subsetFamily:materialBind:familyType = "partition"
+
+    def GeomSubset "Red" (
+        prepend apiSchemas = ["MaterialBindingAPI"]
+    )
+    {
+        uniform token elementType = "face"
+        uniform token familyName = "materialBind"
+        int[] indices = [2, 0]
+        rel material:binding = </Looks/Red>
+    }
+}"#,
+            true,
+        )
+        .unwrap();
+
+        let mesh = MeshExtractor::extract_mesh(&spec);
+        assert_eq!(mesh.material_binding.as_deref(), Some("/Looks/Base"));
+        assert_eq!(mesh.subsets.len(), 1);
+        assert!(mesh.subsets[0].is_material_bind());
+        assert_eq!(mesh.subsets[0].indices, vec![2, 0]);
+
+        let parts = mesh.split_by_subsets().unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].material_binding.as_deref(), Some("/Looks/Red"));
+        assert_eq!(parts[0].face_count(), 2);
+        assert_eq!(parts[0].points.len(), 8);
+        assert_eq!(parts[1].material_binding.as_deref(), Some("/Looks/Base"));
+        assert_eq!(parts[1].points.len(), 4);
+        assert_eq!(parts[1].uvs.as_ref().unwrap()[0], [1.0, 0.0]);
+        assert_eq!(parts[1].face_vertex_indices, vec![0, 1, 2, 3]);
+
+        // Triangulation keeps subsets pointing at the right faces.
+        let triangulated = mesh.triangulate().unwrap();
+        assert_eq!(triangulated.subsets[0].indices, vec![0, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_split_unbound_subset() {
+        let mut mesh = corner_mesh();
+        mesh.material_binding = Some("/Looks/Base".into());
+        mesh.subsets = [(None, 0), (Some("/Looks/Red"), 1)]
+            .into_iter()
+            .map(|(material, face)| GeomSubset {
+                name: format!("Subset{}", face),
+                element_type: ElementType::Face,
+                indices: vec![face],
+                family_name: Some(GeomSubset::MATERIAL_BIND_FAMILY.into()),
+                material_binding: material.map(String::from),
+            })
+            .collect();
+
+        // The unbound subset shares the mesh material with the faces left
+        // outside every subset.
+        let parts = mesh.split_by_subsets().unwrap();
+        let groups: Vec<(Option<&str>, usize)> = parts
+            .iter()
+            .map(|p| (p.material_binding.as_deref(), p.face_count()))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![(Some("/Looks/Base"), 2), (Some("/Looks/Red"), 1)]
+        );
+    }
+
+    #[test]
+    fn test_compute_normals_modes() {
+        let mut mesh = corner_mesh();
+
+        mesh.compute_normals(NormalMode::Flat).unwrap();
+        assert_eq!(mesh.normals_interpolation, Interpolation::Uniform);
+        assert_eq!(
+            mesh.normals.as_deref().unwrap(),
+            &[[0.0, 0.0, -1.0], [-1.0, 0.0, 0.0], [0.0, -1.0, 0.0]]
+        );
+
+        mesh.compute_normals(NormalMode::Smooth).unwrap();
+        assert_eq!(mesh.normals_interpolation, Interpolation::Vertex);
+        let n = mesh.normals.as_deref().unwrap()[0];
+        let expected = -1.0 / 3.0f32.sqrt();
+        assert!(n.iter().all(|c| (c - expected).abs() < 1e-6));
+
+        // A 45 degree threshold keeps the right-angled faces apart.
+        mesh.compute_normals(NormalMode::Crease(45f32.to_radians()))
+            .unwrap();
+        assert_eq!(mesh.normals_interpolation, Interpolation::FaceVarying);
+        let normals = mesh.normals.as_deref().unwrap();
+        assert_eq!(normals.len(), 12);
+        assert_eq!(normals[0], [0.0, 0.0, -1.0]);
+        assert_eq!(normals[4], [-1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_compute_normals_left_handed() {
+        let mut mesh = quad_mesh();
+        mesh.orientation = Orientation::LeftHanded;
+        mesh.compute_normals(NormalMode::Flat).unwrap();
+        assert_eq!(mesh.normals.unwrap(), vec![[0.0, 0.0, -1.0]]);
+    }
+
+    #[test]
+    fn test_compute_tangents() {
+        let mut mesh = quad_mesh();
+        mesh.normals = Some(vec![[0.0, 0.0, 1.0]]);
+        mesh.normals_interpolation = Interpolation::Constant;
+        mesh.uvs = Some(vec![[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]);
+        mesh.compute_tangents().unwrap();
+        assert_eq!(
+            mesh.tangents.as_deref().unwrap(),
+            &[[1.0, 0.0, 0.0, 1.0]; 4]
+        );
+
+        // Mirroring U flips both the tangent and its handedness.
+        mesh.uvs = Some(vec![[1.0, 0.0], [0.0, 0.0], [0.0, 1.0], [1.0, 1.0]]);
+        mesh.compute_tangents().unwrap();
+        assert_eq!(
+            mesh.tangents.as_deref().unwrap(),
+            &[[-1.0, 0.0, 0.0, -1.0]; 4]
+        );
+
+        mesh.uvs = None;
+        assert!(matches!(
+            mesh.compute_tangents(),
+            Err(Error::AttributeNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_nested_and_invisible_instances() {
+        // Instances are written with their prototype's prims composed in.
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Xform "Protos"
+{
+    def Xform "Car"
+    {
+        def Mesh "Body"
+        {
+        }
+
+        def Xform "Wheel" (
+            instanceable = true
+            references = </Protos/Wheel>
+        )
+        {
+            def Mesh "Tire"
+            {
+            }
+        }
+    }
+
+    def Xform "Wheel"
+    {
+        def Mesh "Tire"
+        {
+        }
+    }
+}
+
+def Xform "Car_1" (
+    instanceable = true
+    references = </Protos/Car>
+)
+{
+    def Mesh "Body"
+    {
+    }
+
+    def Xform "Wheel" (
+        instanceable = true
+        references = </Protos/Wheel>
+    )
+    {
+        def Mesh "Tire"
+        {
+        }
+    }
+}
+
+def Xform "Car_2" (
+    instanceable = true
+    references = </Protos/Car>
+)
+{
+    token visibility = "invisible"
+}
+"#,
+        )
+        .unwrap();
+        let extractor = MeshExtractor::new(&stage).instancing(Instancing::Prototypes);
+
+        // The wheel nested in Car_1 is part of the car prototype, and the
+        // invisible Car_2 has no copies.
+        let instanced: Vec<(String, String, usize)> = extractor
+            .instanced_meshes()
+            .into_iter()
+            .map(|i| (i.instancer, i.prototype.name, i.transforms.len()))
+            .collect();
+        assert_eq!(
+            instanced,
+            vec![
+                ("/Protos/Wheel".to_string(), "Tire".to_string(), 1),
+                ("/Protos/Car".to_string(), "Body".to_string(), 1),
+                ("/Protos/Car".to_string(), "Tire".to_string(), 1),
+            ]
+        );
+        assert_eq!(extractor.meshes().count(), 2);
+        assert_eq!(stage.prototypes()[0].source_path(), Some("/Protos/Wheel"));
+        assert!(stage
+            .prim_at_path("/Protos/Car/Wheel")
+            .unwrap()
+            .is_instance());
+        assert!(stage.prim_at_path("/Protos/Car/Door").is_none());
+    }
+
+    #[test]
+    fn test_skip_hidden_meshes() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Xform "World"
+{
+    def Mesh "Shown"
+    {
+    }
+
+    def Xform "Hidden"
+    {
+        token visibility = "invisible"
+
+        def Mesh "Inner"
+        {
+        }
+    }
+
+    def Mesh "Off" (
+        active = false
+    )
+    {
+    }
+
+    def Xform "Rig"
+    {
+        uniform token purpose = "guide"
+
+        def Mesh "Handle"
+        {
+        }
+    }
+
+    def Mesh "Proxy"
+    {
+        uniform token purpose = "proxy"
+    }
+
+    def Mesh "Render"
+    {
+        uniform token purpose = "render"
+    }
+}
+"#,
+        )
+        .unwrap();
+        let names = |extractor: MeshExtractor<'_>| {
+            extractor.meshes().map(|mesh| mesh.name).collect::<Vec<_>>()
+        };
+
+        // Invisible and inactive subtrees, guides and proxies are skipped.
+        assert_eq!(names(MeshExtractor::new(&stage)), ["Shown", "Render"]);
+
+        // Guides are inherited from their parent.
+        let all = [
+            Purpose::Default,
+            Purpose::Render,
+            Purpose::Proxy,
+            Purpose::Guide,
+        ];
+        assert_eq!(
+            names(MeshExtractor::new(&stage).purposes(&all)),
+            ["Shown", "Handle", "Proxy", "Render"]
+        );
+        assert_eq!(
+            names(MeshExtractor::new(&stage).purposes(&[Purpose::Guide])),
+            ["Handle"]
+        );
+    }
+}
@@ -0,0 +1,385 @@
+//! Unit and up-axis normalization of extracted data.
+//!
+//! A [`Normalization`] converts meshes, cameras and lights from the
+//! convention a stage was authored in to a target [`Convention`], so assets
+//! authored Z-up in centimeters and Y-up in meters end up in the same space.
+
+use crate::camera::Camera;
+use crate::error::Result;
+use crate::light::Light;
+use crate::mesh::{matrix_multiply, InstancedMesh, Mesh, Orientation};
+use crate::stage::{Axis, Stage};
+use crate::xform;
+
+/// Handedness of a coordinate system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Handedness {
+    /// Right-handed coordinates (USD, glTF).
+    #[default]
+    Right,
+    /// Left-handed coordinates.
+    Left,
+}
+
+/// A coordinate system convention.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Convention {
+    /// The up axis.
+    pub up_axis: Axis,
+    /// Length of a unit in meters.
+    pub meters_per_unit: f64,
+    /// Handedness of the coordinate system.
+    pub handedness: Handedness,
+}
+
+impl Default for Convention {
+    /// Y-up, right-handed meters, as used by glTF.
+    fn default() -> Self {
+        Convention {
+            up_axis: Axis::Y,
+            meters_per_unit: 1.0,
+            handedness: Handedness::Right,
+        }
+    }
+}
+
+impl Convention {
+    /// Returns the convention a stage was authored in, from its `upAxis` and
+    /// `metersPerUnit` metadata. USD is always right-handed.
+    pub fn of_stage(stage: &Stage) -> Result<Self> {
+        Ok(Convention {
+            up_axis: stage.up_axis()?,
+            meters_per_unit: stage.meters_per_unit()?,
+            handedness: Handedness::Right,
+        })
+    }
+}
+
+/// A correction from one [`Convention`] to another.
+///
+/// Points, normals and transforms are re-expressed in the target convention:
+/// transforms are conjugated by the correction, so a mesh keeps its place in
+/// the hierarchy and its points end up in target units. When the correction
+/// mirrors the scene, mesh orientation is flipped to keep faces front-facing.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::{normalize, Convention, MeshExtractor, Stage};
+///
+/// let stage = Stage::open("model.usdz").unwrap();
+/// let normalization = normalize(&stage, &Convention::default()).unwrap();
+/// for mut mesh in MeshExtractor::new(&stage).meshes() {
+///     normalization.apply_to_mesh(&mut mesh);
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    /// Rotation and mirroring, without scale.
+    rotation: [[f64; 4]; 4],
+    /// Uniform scale from source to target units.
+    scale: f64,
+}
+
+/// Returns the correction from `stage`'s convention to `target`.
+pub fn normalize(stage: &Stage, target: &Convention) -> Result<Normalization> {
+    Ok(Normalization::new(&Convention::of_stage(stage)?, target))
+}
+
+impl Normalization {
+    /// Creates the correction from `source` to `target`.
+    pub fn new(source: &Convention, target: &Convention) -> Self {
+        let mut rotation = to_y_up(source.up_axis);
+        if source.handedness != target.handedness {
+            // Mirror the horizontal Z axis, leaving up untouched.
+            rotation = matrix_multiply(rotation, xform::scale([1.0, 1.0, -1.0]));
+        }
+        rotation = matrix_multiply(rotation, transpose(&to_y_up(target.up_axis)));
+
+        let scale = if target.meters_per_unit > 0.0 {
+            source.meters_per_unit / target.meters_per_unit
+        } else {
+            1.0
+        };
+        Normalization { rotation, scale }
+    }
+
+    /// Returns the correction matrix applied to world-space points.
+    pub fn matrix(&self) -> [[f64; 4]; 4] {
+        matrix_multiply(self.rotation, xform::scale([self.scale; 3]))
+    }
+
+    /// Returns true if the correction mirrors the scene.
+    pub fn mirrors(&self) -> bool {
+        determinant3(&self.rotation) < 0.0
+    }
+
+    /// Re-expresses a matrix in the target convention.
+    fn conjugate(&self, m: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+        let mut m = matrix_multiply(
+            matrix_multiply(transpose(&self.rotation), *m),
+            self.rotation,
+        );
+        for c in &mut m[3][..3] {
+            *c *= self.scale;
+        }
+        m
+    }
+
+    fn point(&self, p: [f32; 3]) -> [f32; 3] {
+        self.vector(p).map(|c| c * self.scale as f32)
+    }
+
+    fn vector(&self, v: [f32; 3]) -> [f32; 3] {
+        xform::transform_vector(&self.rotation, v.map(f64::from)).map(|c| c as f32)
+    }
+
+    /// Converts a mesh's points, normals, tangents, blend shapes and
+    /// transforms to the target convention.
+    pub fn apply_to_mesh(&self, mesh: &mut Mesh) {
+        for p in &mut mesh.points {
+            *p = self.point(*p);
+        }
+        if let Some(normals) = &mut mesh.normals {
+            for n in normals {
+                *n = self.vector(*n);
+            }
+        }
+        let sign = if self.mirrors() { -1.0 } else { 1.0 };
+        if let Some(tangents) = &mut mesh.tangents {
+            for t in tangents {
+                let [x, y, z] = self.vector([t[0], t[1], t[2]]);
+                *t = [x, y, z, t[3] * sign];
+            }
+        }
+        for shape in &mut mesh.blend_shapes {
+            for p in &mut shape.offsets {
+                *p = self.point(*p);
+            }
+            for n in shape.normal_offsets.iter_mut().flatten() {
+                *n = self.vector(*n);
+            }
+            for inbetween in &mut shape.inbetweens {
+                for p in &mut inbetween.offsets {
+                    *p = self.point(*p);
+                }
+                for n in inbetween.normal_offsets.iter_mut().flatten() {
+                    *n = self.vector(*n);
+                }
+            }
+        }
+        if let Some(influences) = &mut mesh.joint_influences {
+            influences.geom_bind_transform = self.conjugate(&influences.geom_bind_transform);
+        }
+
+        if self.mirrors() {
+            mesh.orientation = match mesh.orientation {
+                Orientation::RightHanded => Orientation::LeftHanded,
+                Orientation::LeftHanded => Orientation::RightHanded,
+            };
+        }
+        mesh.local_transform = self.conjugate(&mesh.local_transform);
+        mesh.world_transform = self.conjugate(&mesh.world_transform);
+    }
+
+    /// Converts an instanced mesh and its instance transforms to the target
+    /// convention.
+    pub fn apply_to_instanced_mesh(&self, instanced: &mut InstancedMesh) {
+        self.apply_to_mesh(&mut instanced.prototype);
+        for transform in &mut instanced.transforms {
+            *transform = self.conjugate(transform);
+        }
+    }
+
+    /// Converts a camera to the target convention.
+    ///
+    /// The camera keeps looking down its local -Z axis; its world transform
+    /// is corrected and its lens, aperture and distances are scaled.
+    pub fn apply_to_camera(&self, camera: &mut Camera) {
+        camera.world_transform = self.correct(&camera.world_transform);
+        let scale = self.scale as f32;
+        camera.focal_length *= scale;
+        camera.horizontal_aperture *= scale;
+        camera.vertical_aperture *= scale;
+        camera.horizontal_aperture_offset *= scale;
+        camera.vertical_aperture_offset *= scale;
+        camera.clipping_range = camera.clipping_range.map(|c| c * scale);
+        camera.focus_distance *= scale;
+    }
+
+    /// Converts a light to the target convention.
+    ///
+    /// The light keeps emitting along its local -Z axis; its world transform
+    /// is corrected and its shape is scaled.
+    pub fn apply_to_light(&self, light: &mut Light) {
+        let world = self.correct(light.world_transform());
+        light.params_mut().world_transform = world;
+
+        let scale = self.scale as f32;
+        match light {
+            Light::Sphere { radius, .. } | Light::Disk { radius, .. } => *radius *= scale,
+            Light::Rect { width, height, .. } | Light::Portal { width, height, .. } => {
+                *width *= scale;
+                *height *= scale;
+            }
+            Light::Cylinder { length, radius, .. } => {
+                *length *= scale;
+                *radius *= scale;
+            }
+            Light::Distant { .. } | Light::Dome { .. } => {}
+        }
+    }
+
+    /// Corrects the world transform of an object whose local axes follow a
+    /// schema convention, such as a camera or light.
+    fn correct(&self, m: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+        let mut m = matrix_multiply(*m, self.rotation);
+        for c in &mut m[3][..3] {
+            *c *= self.scale;
+        }
+        m
+    }
+}
+
+/// Returns the rotation taking `axis` up to Y up.
+fn to_y_up(axis: Axis) -> [[f64; 4]; 4] {
+    match axis {
+        // (x, y, z) -> (-y, x, z)
+        Axis::X => [
+            [0.0, 1.0, 0.0, 0.0],
+            [-1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+        Axis::Y => xform::identity(),
+        // (x, y, z) -> (x, z, -y)
+        Axis::Z => [
+            [1.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, -1.0, 0.0],
+            [0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 1.0],
+        ],
+    }
+}
+
+fn transpose(m: &[[f64; 4]; 4]) -> [[f64; 4]; 4] {
+    let mut t = [[0.0; 4]; 4];
+    for (i, row) in m.iter().enumerate() {
+        for (j, value) in row.iter().enumerate() {
+            t[j][i] = *value;
+        }
+    }
+    t
+}
+
+fn determinant3(m: &[[f64; 4]; 4]) -> f64 {
+    m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+        - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+        + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::light::LightParams;
+
+    #[test]
+    fn test_z_up_centimeters_to_y_up_meters() {
+        let source = Convention {
+            up_axis: Axis::Z,
+            meters_per_unit: 0.01,
+            handedness: Handedness::Right,
+        };
+        let normalization = Normalization::new(&source, &Convention::default());
+        assert!(!normalization.mirrors());
+
+        let mut mesh = Mesh::new("Pole");
+        mesh.points = vec![[0.0, 0.0, 0.0], [0.0, 0.0, 200.0]];
+        mesh.normals = Some(vec![[0.0, 0.0, 1.0]; 2]);
+        mesh.world_transform = xform::translation([100.0, 0.0, 0.0]);
+        normalization.apply_to_mesh(&mut mesh);
+
+        assert_eq!(mesh.points[1], [0.0, 2.0, 0.0]);
+        assert_eq!(mesh.normals.as_ref().unwrap()[0], [0.0, 1.0, 0.0]);
+
+        // The world-space top of the pole moves from (100, 0, 200) cm to
+        // (1, 2, 0) m.
+        let top = xform::transform_point(&mesh.world_transform, [0.0, 2.0, 0.0]);
+        assert_eq!(top, [1.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn test_handedness_flips_orientation() {
+        let target = Convention {
+            handedness: Handedness::Left,
+            ..Convention::default()
+        };
+        let normalization = Normalization::new(&Convention::default(), &target);
+        assert!(normalization.mirrors());
+
+        let mut mesh = Mesh::new("Quad");
+        mesh.points = vec![[1.0, 2.0, 3.0]];
+        normalization.apply_to_mesh(&mut mesh);
+        assert_eq!(mesh.points[0], [1.0, 2.0, -3.0]);
+        assert_eq!(mesh.orientation, Orientation::LeftHanded);
+    }
+
+    fn assert_near(a: f32, b: f32) {
+        assert!((a - b).abs() < 1e-5, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_z_up_centimeters_camera_light_and_instances() {
+        let source = Convention {
+            up_axis: Axis::Z,
+            meters_per_unit: 0.01,
+            handedness: Handedness::Right,
+        };
+        let normalization = Normalization::new(&source, &Convention::default());
+
+        // A camera 2 m up looking straight down its local -Z, which is down
+        // in Z-up.
+        let mut camera = Camera::new("Top");
+        camera.world_transform = xform::translation([100.0, 0.0, 200.0]);
+        normalization.apply_to_camera(&mut camera);
+        assert_eq!(
+            xform::transform_point(&camera.world_transform, [0.0; 3]),
+            [1.0, 2.0, 0.0]
+        );
+        let forward = xform::transform_vector(&camera.world_transform, [0.0, 0.0, -1.0]);
+        assert_eq!(forward, [0.0, -1.0, 0.0]);
+        // Lens and aperture stay the same physical size: 50 mm is 0.5 tenths
+        // of a meter.
+        assert_near(camera.focal_length, 0.5);
+        assert_near(camera.horizontal_aperture, 0.20955);
+        assert_near(camera.clipping_range[0], 0.01);
+        assert_eq!(camera.aspect_ratio(), Camera::new("Top").aspect_ratio());
+
+        let mut light = Light::Sphere {
+            params: LightParams::new("Bulb"),
+            radius: 50.0,
+            treat_as_point: false,
+        };
+        light.params_mut().world_transform = xform::translation([0.0, 300.0, 0.0]);
+        normalization.apply_to_light(&mut light);
+        assert_eq!(
+            xform::transform_point(light.world_transform(), [0.0; 3]),
+            [0.0, 0.0, -3.0]
+        );
+        let Light::Sphere { radius, .. } = light else {
+            unreachable!();
+        };
+        assert_near(radius, 0.5);
+
+        // Instance transforms are conjugated like mesh transforms.
+        let mut instanced = InstancedMesh {
+            prototype: Mesh::new("Tree"),
+            instancer: "/Forest".into(),
+            transforms: vec![xform::translation([0.0, 0.0, 500.0])],
+        };
+        normalization.apply_to_instanced_mesh(&mut instanced);
+        assert_eq!(
+            xform::transform_point(&instanced.transforms[0], [0.0; 3]),
+            [0.0, 5.0, 0.0]
+        );
+    }
+}
@@ -1,15 +1,430 @@
 //! USD Prim (primitive) handling.
 
-use std::ffi::CStr;
-use std::marker::PhantomData;
+use std::ffi::{CStr, CString};
+
+use crate::attribute::Attribute;
+use crate::collection::{self, Collection};
+use crate::stage::Stage;
+use crate::usda::{find_meta, list_op_items, Node, PrimSpec};
+use crate::value::Value;
+
+/// Purpose of an imageable prim, used to include or exclude it from
+/// rendering and bounds computation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Purpose {
+    /// Always included.
+    #[default]
+    Default,
+    /// Final-quality render geometry.
+    Render,
+    /// Lightweight stand-in for render geometry.
+    Proxy,
+    /// Helper geometry not meant to be rendered.
+    Guide,
+}
+
+impl Purpose {
+    /// Parses a USD purpose token (e.g. "guide").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "default" => Some(Purpose::Default),
+            "render" => Some(Purpose::Render),
+            "proxy" => Some(Purpose::Proxy),
+            "guide" => Some(Purpose::Guide),
+            _ => None,
+        }
+    }
+}
+
+/// Model kind of a prim, from its `kind` metadata.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// Abstract base of all model kinds.
+    Model,
+    /// A model that groups other models.
+    Group,
+    /// An important group, such as a set or a published asset of assets.
+    Assembly,
+    /// A leaf model; models may not be nested below it.
+    Component,
+    /// A meaningful part of a component that is not itself a model.
+    Subcomponent,
+}
+
+impl Kind {
+    /// Parses a USD kind token (e.g. "component").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "model" => Some(Kind::Model),
+            "group" => Some(Kind::Group),
+            "assembly" => Some(Kind::Assembly),
+            "component" => Some(Kind::Component),
+            "subcomponent" => Some(Kind::Subcomponent),
+            _ => None,
+        }
+    }
+
+    /// Returns true for kinds in the model hierarchy (all but
+    /// `subcomponent`).
+    pub fn is_model(self) -> bool {
+        self != Kind::Subcomponent
+    }
+
+    /// Returns true for group kinds (`group` and `assembly`).
+    pub fn is_group(self) -> bool {
+        matches!(self, Kind::Group | Kind::Assembly)
+    }
+}
+
+/// Visibility of an imageable prim.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Visibility {
+    /// Visible unless an ancestor is invisible.
+    #[default]
+    Inherited,
+    /// Invisible, along with all descendants.
+    Invisible,
+}
+
+impl Visibility {
+    /// Parses a USD visibility token (e.g. "invisible").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "inherited" => Some(Visibility::Inherited),
+            "invisible" => Some(Visibility::Invisible),
+            _ => None,
+        }
+    }
+}
+
+/// How a prim is specified in a layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Specifier {
+    /// A concrete prim (`def`).
+    #[default]
+    Def,
+    /// An override of a prim defined elsewhere (`over`).
+    Over,
+    /// An abstract prim to inherit from (`class`).
+    Class,
+}
+
+impl Specifier {
+    /// Parses a USD specifier keyword (e.g. "over").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "def" => Some(Specifier::Def),
+            "over" => Some(Specifier::Over),
+            "class" => Some(Specifier::Class),
+            _ => None,
+        }
+    }
+}
+
+/// Schema type of a prim, from its type name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrimType {
+    /// `Xform`
+    Xform,
+    /// `Scope`
+    Scope,
+    /// `Mesh`
+    Mesh,
+    /// `GeomSubset`
+    GeomSubset,
+    /// `Points`
+    Points,
+    /// `BasisCurves`
+    BasisCurves,
+    /// `NurbsCurves`
+    NurbsCurves,
+    /// `Cube`
+    Cube,
+    /// `Sphere`
+    Sphere,
+    /// `Cylinder`
+    Cylinder,
+    /// `Cone`
+    Cone,
+    /// `Capsule`
+    Capsule,
+    /// `PointInstancer`
+    PointInstancer,
+    /// `Camera`
+    Camera,
+    /// `Material`
+    Material,
+    /// `Shader`
+    Shader,
+    /// `NodeGraph`
+    NodeGraph,
+    /// `DistantLight`
+    DistantLight,
+    /// `SphereLight`
+    SphereLight,
+    /// `RectLight`
+    RectLight,
+    /// `DiskLight`
+    DiskLight,
+    /// `CylinderLight`
+    CylinderLight,
+    /// `DomeLight`
+    DomeLight,
+    /// `PortalLight`
+    PortalLight,
+    /// `SkelRoot`
+    SkelRoot,
+    /// `Skeleton`
+    Skeleton,
+    /// `SkelAnimation`
+    SkelAnimation,
+    /// `BlendShape`
+    BlendShape,
+}
+
+impl PrimType {
+    /// Parses a USD schema type name (e.g. "SphereLight").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "Xform" => Some(PrimType::Xform),
+            "Scope" => Some(PrimType::Scope),
+            "Mesh" => Some(PrimType::Mesh),
+            "GeomSubset" => Some(PrimType::GeomSubset),
+            "Points" => Some(PrimType::Points),
+            "BasisCurves" => Some(PrimType::BasisCurves),
+            "NurbsCurves" => Some(PrimType::NurbsCurves),
+            "Cube" => Some(PrimType::Cube),
+            "Sphere" => Some(PrimType::Sphere),
+            "Cylinder" => Some(PrimType::Cylinder),
+            "Cone" => Some(PrimType::Cone),
+            "Capsule" => Some(PrimType::Capsule),
+            "PointInstancer" => Some(PrimType::PointInstancer),
+            "Camera" => Some(PrimType::Camera),
+            "Material" => Some(PrimType::Material),
+            "Shader" => Some(PrimType::Shader),
+            "NodeGraph" => Some(PrimType::NodeGraph),
+            "DistantLight" => Some(PrimType::DistantLight),
+            "SphereLight" => Some(PrimType::SphereLight),
+            "RectLight" => Some(PrimType::RectLight),
+            "DiskLight" => Some(PrimType::DiskLight),
+            "CylinderLight" => Some(PrimType::CylinderLight),
+            "DomeLight" => Some(PrimType::DomeLight),
+            "PortalLight" => Some(PrimType::PortalLight),
+            "SkelRoot" => Some(PrimType::SkelRoot),
+            "Skeleton" => Some(PrimType::Skeleton),
+            "SkelAnimation" => Some(PrimType::SkelAnimation),
+            "BlendShape" => Some(PrimType::BlendShape),
+            _ => None,
+        }
+    }
+
+    /// Converts a prim type from the tinyusdz C API.
+    ///
+    /// Returns `None` for `C_TINYUSD_PRIM_MODEL` (a typeless prim) and for
+    /// `C_TINYUSD_PRIM_UNKNOWN`, which the C API also reports for the types
+    /// its enum does not cover; use [`from_token`](Self::from_token) for
+    /// those.
+    pub fn from_sys(prim_type: tinyusdz_sys::CTinyUSDPrimType) -> Option<Self> {
+        use tinyusdz_sys::CTinyUSDPrimType as T;
+        match prim_type {
+            T::C_TINYUSD_PRIM_XFORM => Some(PrimType::Xform),
+            T::C_TINYUSD_PRIM_MESH => Some(PrimType::Mesh),
+            T::C_TINYUSD_PRIM_GEOMSUBSET => Some(PrimType::GeomSubset),
+            T::C_TINYUSD_PRIM_MATERIAL => Some(PrimType::Material),
+            T::C_TINYUSD_PRIM_SHADER => Some(PrimType::Shader),
+            T::C_TINYUSD_PRIM_CAMERA => Some(PrimType::Camera),
+            T::C_TINYUSD_PRIM_SPHERE_LIGHT => Some(PrimType::SphereLight),
+            T::C_TINYUSD_PRIM_DISTANT_LIGHT => Some(PrimType::DistantLight),
+            T::C_TINYUSD_PRIM_RECT_LIGHT => Some(PrimType::RectLight),
+            _ => None,
+        }
+    }
+
+    /// Returns the USD schema type name (e.g. "SphereLight").
+    pub fn type_name(self) -> &'static str {
+        match self {
+            PrimType::Xform => "Xform",
+            PrimType::Scope => "Scope",
+            PrimType::Mesh => "Mesh",
+            PrimType::GeomSubset => "GeomSubset",
+            PrimType::Points => "Points",
+            PrimType::BasisCurves => "BasisCurves",
+            PrimType::NurbsCurves => "NurbsCurves",
+            PrimType::Cube => "Cube",
+            PrimType::Sphere => "Sphere",
+            PrimType::Cylinder => "Cylinder",
+            PrimType::Cone => "Cone",
+            PrimType::Capsule => "Capsule",
+            PrimType::PointInstancer => "PointInstancer",
+            PrimType::Camera => "Camera",
+            PrimType::Material => "Material",
+            PrimType::Shader => "Shader",
+            PrimType::NodeGraph => "NodeGraph",
+            PrimType::DistantLight => "DistantLight",
+            PrimType::SphereLight => "SphereLight",
+            PrimType::RectLight => "RectLight",
+            PrimType::DiskLight => "DiskLight",
+            PrimType::CylinderLight => "CylinderLight",
+            PrimType::DomeLight => "DomeLight",
+            PrimType::PortalLight => "PortalLight",
+            PrimType::SkelRoot => "SkelRoot",
+            PrimType::Skeleton => "Skeleton",
+            PrimType::SkelAnimation => "SkelAnimation",
+            PrimType::BlendShape => "BlendShape",
+        }
+    }
+
+    /// Returns true for UsdLux light types.
+    pub fn is_light(self) -> bool {
+        matches!(
+            self,
+            PrimType::DistantLight
+                | PrimType::SphereLight
+                | PrimType::RectLight
+                | PrimType::DiskLight
+                | PrimType::CylinderLight
+                | PrimType::DomeLight
+                | PrimType::PortalLight
+        )
+    }
+
+    /// Returns true for renderable geometry types (gprims).
+    pub fn is_gprim(self) -> bool {
+        matches!(
+            self,
+            PrimType::Mesh
+                | PrimType::Points
+                | PrimType::BasisCurves
+                | PrimType::NurbsCurves
+                | PrimType::Cube
+                | PrimType::Sphere
+                | PrimType::Cylinder
+                | PrimType::Cone
+                | PrimType::Capsule
+        )
+    }
+}
+
+/// A variant set authored on a prim.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariantSet {
+    /// The name of the variant set (e.g. "trim").
+    pub name: String,
+    /// The names of its variants, in authored order.
+    pub variants: Vec<String>,
+    /// The selected variant, if any: the load option override or the
+    /// authored selection.
+    pub selection: Option<String>,
+}
+
+/// A reference or payload arc authored on a prim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    /// Asset path of the targeted layer, or `None` for an arc within the
+    /// same layer.
+    pub asset_path: Option<String>,
+    /// Path of the targeted prim, or `None` for the layer's default prim.
+    pub prim_path: Option<String>,
+    /// Time offset of the layer offset.
+    pub offset: f64,
+    /// Time scale of the layer offset.
+    pub scale: f64,
+}
+
+impl Reference {
+    /// Reads a reference from a list-op item.
+    pub(crate) fn from_node(node: &Node) -> Option<Self> {
+        let (asset_path, prim_path, offset, scale) = match node {
+            Node::Asset(asset) => (Some(asset.clone()), None, 0.0, 1.0),
+            Node::Path(path) => (None, Some(path.clone()), 0.0, 1.0),
+            Node::Reference {
+                asset,
+                path,
+                offset,
+                scale,
+            } => (asset.clone(), path.clone(), *offset, *scale),
+            _ => return None,
+        };
+        Some(Reference {
+            asset_path,
+            prim_path,
+            offset,
+            scale,
+        })
+    }
+}
+
+impl std::fmt::Display for Reference {
+    /// Formats the arc as in USDA, e.g. `@model.usd@</Model>`, without the
+    /// layer offset.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(asset) = &self.asset_path {
+            write!(f, "@{}@", asset)?;
+        }
+        if let Some(path) = &self.prim_path {
+            write!(f, "<{}>", path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Identifies the prototype shared by native instances.
+///
+/// USD composes prototypes into in-memory `/__Prototype_N` prims that the C
+/// API does not expose. Instanceable prims with the same references and
+/// payloads share a prototype, so those arcs identify it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrototypeId {
+    arcs: Vec<Reference>,
+}
+
+impl PrototypeId {
+    /// Returns the references and payloads the prototype is composed from.
+    pub fn arcs(&self) -> &[Reference] {
+        &self.arcs
+    }
+
+    /// Returns the path of the prim the prototype is composed from, when
+    /// the instances reference a single prim in the same layer.
+    ///
+    /// That prim exists on the stage and can be read with
+    /// [`Stage::prim_at_path`].
+    pub fn source_path(&self) -> Option<&str> {
+        match self.arcs.as_slice() {
+            [Reference {
+                asset_path: None,
+                prim_path: Some(path),
+                ..
+            }] => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for PrototypeId {
+    /// Formats the arcs as in USDA, separated by commas.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (i, arc) in self.arcs.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", arc)?;
+        }
+        Ok(())
+    }
+}
 
 /// A USD Prim represents a node in the scene hierarchy.
 ///
 /// Prims can have children, attributes, and metadata.
 #[derive(Clone)]
 pub struct Prim<'a> {
+    /// The C prim, or null for prims the C API does not report.
     pub(crate) inner: *const tinyusdz_sys::CTinyUSDPrim,
-    pub(crate) _marker: PhantomData<&'a ()>,
+    stage: &'a Stage,
+    /// The prim's spec in the stage's parsed layer, if it could be parsed.
+    spec: Option<&'a PrimSpec>,
+    path: String,
 }
 
 // Safety: Prim borrows from Stage which manages lifetime
@@ -17,19 +432,31 @@ unsafe impl<'a> Send for Prim<'a> {}
 unsafe impl<'a> Sync for Prim<'a> {}
 
 impl<'a> Prim<'a> {
-    /// Creates a Prim from a raw pointer.
+    /// Creates a prim of `stage` from its parsed spec and/or C prim.
     ///
-    /// # Safety
-    /// The pointer must be valid for the lifetime 'a.
-    pub(crate) unsafe fn from_ptr(ptr: *const tinyusdz_sys::CTinyUSDPrim) -> Self {
+    /// `inner` must be null or a prim owned by `stage`.
+    pub(crate) fn new(
+        stage: &'a Stage,
+        spec: Option<&'a PrimSpec>,
+        path: String,
+        inner: *const tinyusdz_sys::CTinyUSDPrim,
+    ) -> Self {
         Prim {
-            inner: ptr,
-            _marker: PhantomData,
+            inner,
+            stage,
+            spec,
+            path,
         }
     }
 
     /// Returns the type name of this prim (e.g., "Mesh", "Xform", "Material").
     pub fn type_name(&self) -> &str {
+        if let Some(spec) = self.spec {
+            return &spec.type_name;
+        }
+        if self.inner.is_null() {
+            return "";
+        }
         unsafe {
             let ptr = tinyusdz_sys::c_tinyusd_prim_type(self.inner);
             if ptr.is_null() {
@@ -41,6 +468,12 @@ impl<'a> Prim<'a> {
 
     /// Returns the element name (local name) of this prim.
     pub fn name(&self) -> &str {
+        if let Some(spec) = self.spec {
+            return &spec.name;
+        }
+        if self.inner.is_null() {
+            return "";
+        }
         unsafe {
             let ptr = tinyusdz_sys::c_tinyusd_prim_element_name(self.inner);
             if ptr.is_null() {
@@ -52,18 +485,27 @@ impl<'a> Prim<'a> {
 
     /// Returns the full path of this prim in the scene hierarchy.
     pub fn path(&self) -> String {
-        // The path is typically constructed from the element names
-        // For now, we'll return the element name as a simple path
-        format!("/{}", self.name())
+        self.path.clone()
     }
 
     /// Returns the number of child prims.
     pub fn num_children(&self) -> usize {
+        if let Some(spec) = self.spec {
+            return spec.children.len();
+        }
+        if self.inner.is_null() {
+            return 0;
+        }
         unsafe { tinyusdz_sys::c_tinyusd_prim_num_children(self.inner) as usize }
     }
 
     /// Returns a child prim by index.
     pub fn child(&self, index: usize) -> Option<Prim<'a>> {
+        if let Some(spec) = self.spec {
+            let child = spec.children.get(index)?;
+            let path = format!("{}/{}", self.path, child.name);
+            return Some(self.stage.prim(child, path));
+        }
         let num = self.num_children();
         if index >= num {
             return None;
@@ -75,7 +517,9 @@ impl<'a> Prim<'a> {
                 tinyusdz_sys::c_tinyusd_prim_get_child(self.inner, index as u64, &mut child_ptr);
 
             if result != 0 && !child_ptr.is_null() {
-                Some(Prim::from_ptr(child_ptr))
+                let child = Prim::new(self.stage, None, String::new(), child_ptr);
+                let path = format!("{}/{}", self.path, child.name());
+                Some(Prim::new(self.stage, None, path, child_ptr))
             } else {
                 None
             }
@@ -93,6 +537,12 @@ impl<'a> Prim<'a> {
 
     /// Returns the names of all properties on this prim.
     pub fn property_names(&self) -> Vec<String> {
+        if let Some(spec) = self.spec {
+            return spec.properties.iter().map(|p| p.name.clone()).collect();
+        }
+        if self.inner.is_null() {
+            return Vec::new();
+        }
         unsafe {
             let tokens = tinyusdz_sys::c_tinyusd_token_vector_new_empty();
             if tokens.is_null() {
@@ -122,39 +572,237 @@ impl<'a> Prim<'a> {
         }
     }
 
+    /// Returns the attributes authored on this prim.
+    pub fn attributes(&self) -> Vec<Attribute> {
+        self.spec()
+            .map(|spec| {
+                spec.properties
+                    .iter()
+                    .filter(|p| !p.is_relationship())
+                    .map(Attribute::from_spec)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns an attribute by name (e.g. "points").
+    pub fn attribute(&self, name: &str) -> Option<Attribute> {
+        let spec = self.spec()?;
+        spec.property(name)
+            .filter(|p| !p.is_relationship())
+            .map(Attribute::from_spec)
+    }
+
+    /// Returns the local transformation matrix from this prim's xformOps.
+    pub fn local_transform(&self) -> [[f64; 4]; 4] {
+        self.spec()
+            .map(|spec| crate::xform::local_transform(spec).0)
+            .unwrap_or_else(crate::xform::identity)
+    }
+
+    /// Returns true if this prim ignores its parent transforms
+    /// (`!resetXformStack!`).
+    pub fn resets_xform_stack(&self) -> bool {
+        self.spec()
+            .map(|spec| crate::xform::local_transform(spec).1)
+            .unwrap_or(false)
+    }
+
+    /// Returns the model kind (`kind` metadata), if it is a known kind.
+    pub fn kind(&self) -> Option<Kind> {
+        kind(self.spec()?)
+    }
+
+    /// Returns false if this prim is deactivated (`active = false`).
+    pub fn is_active(&self) -> bool {
+        self.spec()
+            .and_then(|spec| bool_meta(spec, "active"))
+            .unwrap_or(true)
+    }
+
+    /// Returns true if this prim is hidden from UIs (`hidden = true`).
+    pub fn is_hidden(&self) -> bool {
+        self.spec()
+            .and_then(|spec| bool_meta(spec, "hidden"))
+            .unwrap_or(false)
+    }
+
+    /// Returns how this prim is specified (`def`, `over` or `class`).
+    pub fn specifier(&self) -> Specifier {
+        self.spec()
+            .and_then(|spec| Specifier::from_token(&spec.specifier))
+            .unwrap_or_default()
+    }
+
+    /// Returns true if this prim is defined (`def`) rather than an `over` or
+    /// `class`.
+    pub fn is_defined(&self) -> bool {
+        self.specifier() == Specifier::Def
+    }
+
+    /// Returns the authored purpose, or [`Purpose::Default`].
+    ///
+    /// A non-default purpose applies to the whole subtree; see
+    /// [`Stage::compute_purpose`](crate::Stage::compute_purpose).
+    pub fn purpose(&self) -> Purpose {
+        self.spec().map(purpose).unwrap_or_default()
+    }
+
+    /// Returns the authored visibility, or [`Visibility::Inherited`].
+    ///
+    /// Invisibility applies to the whole subtree; see
+    /// [`Stage::compute_visibility`](crate::Stage::compute_visibility).
+    pub fn visibility(&self) -> Visibility {
+        self.spec().map(visibility).unwrap_or_default()
+    }
+
+    /// Returns the documentation string (`doc` metadata), if authored.
+    pub fn documentation(&self) -> Option<String> {
+        let spec = self.spec()?;
+        find_meta(&spec.metadata, "doc")
+            .and_then(Node::as_str)
+            .map(String::from)
+    }
+
+    /// Returns the entries of the `customData` dictionary.
+    pub fn custom_data(&self) -> Vec<(String, Value)> {
+        self.spec()
+            .map(|spec| dictionary(spec, "customData"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the entries of the `assetInfo` dictionary (e.g. "identifier",
+    /// "version").
+    pub fn asset_info(&self) -> Vec<(String, Value)> {
+        self.spec()
+            .map(|spec| dictionary(spec, "assetInfo"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the variant sets authored on this prim.
+    ///
+    /// Selections include overrides passed with
+    /// [`LoadOptions`](crate::LoadOptions).
+    pub fn variant_sets(&self) -> Vec<VariantSet> {
+        self.spec().map(variant_sets).unwrap_or_default()
+    }
+
+    /// Returns the references authored on this prim.
+    pub fn references(&self) -> Vec<Reference> {
+        self.spec()
+            .map(|spec| references(spec, "references"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the payloads authored on this prim.
+    pub fn payloads(&self) -> Vec<Reference> {
+        self.spec()
+            .map(|spec| references(spec, "payload"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the paths of the prims this prim inherits from.
+    pub fn inherits(&self) -> Vec<String> {
+        self.spec()
+            .map(|spec| target_paths(spec, "inherits"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the paths of the prims this prim specializes.
+    pub fn specializes(&self) -> Vec<String> {
+        self.spec()
+            .map(|spec| target_paths(spec, "specializes"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the collections authored on this prim with the
+    /// CollectionAPI schema.
+    pub fn collections(&self) -> Vec<Collection> {
+        self.spec().map(collection::collections).unwrap_or_default()
+    }
+
+    /// Returns true if this prim is a native instance (`instanceable = true`
+    /// with a reference or payload to share).
+    pub fn is_instance(&self) -> bool {
+        self.prototype().is_some()
+    }
+
+    /// Returns the prototype shared by this instance, if it is one.
+    ///
+    /// Instances with the same references and payloads share a prototype;
+    /// see [`PrototypeId`].
+    pub fn prototype(&self) -> Option<PrototypeId> {
+        prototype(self.spec()?)
+    }
+
+    /// Returns this prim's spec from the stage's parsed layer.
+    pub(crate) fn spec(&self) -> Option<&'a PrimSpec> {
+        self.spec
+    }
+
+    /// Returns the schema type of this prim, or `None` for typeless prims
+    /// and types this crate does not know.
+    ///
+    /// Types are resolved through the C API once per stage and type name,
+    /// so this is a map lookup.
+    pub fn prim_type(&self) -> Option<PrimType> {
+        self.stage.prim_type(self.type_name())
+    }
+
+    /// Returns the API schemas applied to this prim (e.g.
+    /// "MaterialBindingAPI", "CollectionAPI:lights"), in authored order.
+    pub fn applied_schemas(&self) -> Vec<String> {
+        self.spec().map(applied_schemas).unwrap_or_default()
+    }
+
+    /// Returns true if the API schema `name` is applied to this prim.
+    ///
+    /// For multiple-apply schemas, `name` matches every instance
+    /// ("CollectionAPI" matches "CollectionAPI:lights") unless an instance
+    /// is given.
+    pub fn has_api(&self, name: &str) -> bool {
+        self.spec().is_some_and(|spec| {
+            list_op_items(&spec.metadata, "apiSchemas")
+                .filter_map(Node::as_str)
+                .any(|schema| schema_matches(schema, name))
+        })
+    }
+
     /// Returns true if this prim is a Mesh.
     pub fn is_mesh(&self) -> bool {
-        self.type_name() == "Mesh"
+        self.prim_type() == Some(PrimType::Mesh)
     }
 
     /// Returns true if this prim is an Xform (transform).
     pub fn is_xform(&self) -> bool {
-        self.type_name() == "Xform"
+        self.prim_type() == Some(PrimType::Xform)
     }
 
     /// Returns true if this prim is a Material.
     pub fn is_material(&self) -> bool {
-        self.type_name() == "Material"
+        self.prim_type() == Some(PrimType::Material)
     }
 
     /// Returns true if this prim is a Shader.
     pub fn is_shader(&self) -> bool {
-        self.type_name() == "Shader"
+        self.prim_type() == Some(PrimType::Shader)
     }
 
     /// Returns true if this prim is a Camera.
     pub fn is_camera(&self) -> bool {
-        self.type_name() == "Camera"
+        self.prim_type() == Some(PrimType::Camera)
     }
 
-    /// Returns true if this prim is a light (any light type).
+    /// Returns true if this prim is a light (any UsdLux light type).
     pub fn is_light(&self) -> bool {
-        let type_name = self.type_name();
-        type_name.ends_with("Light")
+        self.prim_type().is_some_and(PrimType::is_light)
     }
 
     /// Converts this prim to a debug string representation.
     pub fn to_debug_string(&self) -> String {
+        if self.inner.is_null() {
+            return String::new();
+        }
         unsafe {
             let s = tinyusdz_sys::c_tinyusd_string_new_empty();
             if s.is_null() {
@@ -190,6 +838,116 @@ impl<'a> std::fmt::Debug for Prim<'a> {
     }
 }
 
+/// Resolves a schema type name through the C API. Types its enum lacks
+/// (such as `Scope`, the skeleton types and most lights) are parsed from the
+/// type name.
+pub(crate) fn resolve_prim_type(type_name: &str) -> Option<PrimType> {
+    CString::new(type_name)
+        .ok()
+        .map(|name| unsafe { tinyusdz_sys::c_tinyusd_prim_type_from_string(name.as_ptr()) })
+        .and_then(PrimType::from_sys)
+        .or_else(|| PrimType::from_token(type_name))
+}
+
+/// Returns the API schemas applied to a prim spec.
+pub(crate) fn applied_schemas(spec: &PrimSpec) -> Vec<String> {
+    list_op_items(&spec.metadata, "apiSchemas")
+        .filter_map(Node::as_str)
+        .map(String::from)
+        .collect()
+}
+
+/// Returns true if an applied schema is `name`, or an instance of the
+/// multiple-apply schema `name`.
+fn schema_matches(schema: &str, name: &str) -> bool {
+    schema == name
+        || schema
+            .strip_prefix(name)
+            .is_some_and(|rest| rest.starts_with(':'))
+}
+
+/// Returns the prototype of an instanceable prim spec.
+pub(crate) fn prototype(spec: &PrimSpec) -> Option<PrototypeId> {
+    if bool_meta(spec, "instanceable") != Some(true) {
+        return None;
+    }
+
+    let arcs: Vec<Reference> = ["references", "payload"]
+        .into_iter()
+        .flat_map(|key| references(spec, key))
+        .collect();
+    (!arcs.is_empty()).then_some(PrototypeId { arcs })
+}
+
+/// Reads the model kind of a prim spec.
+pub(crate) fn kind(spec: &PrimSpec) -> Option<Kind> {
+    find_meta(&spec.metadata, "kind")
+        .and_then(Node::as_str)
+        .and_then(Kind::from_token)
+}
+
+/// Reads a boolean metadata entry.
+pub(crate) fn bool_meta(spec: &PrimSpec, key: &str) -> Option<bool> {
+    match find_meta(&spec.metadata, key)?.to_untyped_value() {
+        Value::Bool(b) => Some(b),
+        Value::Int(i) => Some(i != 0),
+        _ => None,
+    }
+}
+
+/// Reads the authored purpose of a prim spec.
+pub(crate) fn purpose(spec: &PrimSpec) -> Purpose {
+    spec.value("purpose")
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(Purpose::from_token)
+        .unwrap_or_default()
+}
+
+/// Reads the authored visibility of a prim spec.
+pub(crate) fn visibility(spec: &PrimSpec) -> Visibility {
+    spec.value("visibility")
+        .as_ref()
+        .and_then(Value::as_str)
+        .and_then(Visibility::from_token)
+        .unwrap_or_default()
+}
+
+/// Reads the entries of a dictionary metadata entry.
+fn dictionary(spec: &PrimSpec, key: &str) -> Vec<(String, Value)> {
+    match find_meta(&spec.metadata, key).map(Node::to_untyped_value) {
+        Some(Value::Dictionary(entries)) => entries,
+        _ => Vec::new(),
+    }
+}
+
+/// Reads the variant sets of a prim spec.
+pub(crate) fn variant_sets(spec: &PrimSpec) -> Vec<VariantSet> {
+    spec.variant_sets
+        .iter()
+        .map(|set| VariantSet {
+            name: set.name.clone(),
+            variants: set.variants.iter().map(|v| v.name.clone()).collect(),
+            selection: spec.variant_selection(&set.name),
+        })
+        .collect()
+}
+
+/// Reads the reference or payload arcs of a prim spec.
+pub(crate) fn references(spec: &PrimSpec, key: &str) -> Vec<Reference> {
+    list_op_items(&spec.metadata, key)
+        .filter_map(Reference::from_node)
+        .collect()
+}
+
+/// Reads the target paths of an inherits or specializes arc.
+pub(crate) fn target_paths(spec: &PrimSpec, key: &str) -> Vec<String> {
+    list_op_items(&spec.metadata, key)
+        .filter_map(Node::as_path)
+        .map(String::from)
+        .collect()
+}
+
 /// Iterator over the children of a prim.
 pub struct ChildIterator<'a> {
     prim: Prim<'a>,
@@ -217,3 +975,219 @@ impl<'a> Iterator for ChildIterator<'a> {
 }
 
 impl<'a> ExactSizeIterator for ChildIterator<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda;
+
+    #[test]
+    fn test_prototype() {
+        let spec = usda::parse_prim(
+            r#"def Xform "Tree_1" (
+    instanceable = true
+    prepend references = </Protos/Tree>
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+        let tree = prototype(&spec).unwrap();
+        assert_eq!(tree.source_path(), Some("/Protos/Tree"));
+        assert_eq!(tree.to_string(), "</Protos/Tree>");
+
+        let spec = usda::parse_prim(
+            r#"def Xform "Car_1" (
+    instanceable = true
+    references = @car.usd@</Car>
+    payload = @car_payload.usd@
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+        let car = prototype(&spec).unwrap();
+        assert_eq!(car.source_path(), None);
+        assert_eq!(car.arcs().len(), 2);
+        assert_eq!(car.to_string(), "@car.usd@</Car>, @car_payload.usd@");
+
+        // Numeric booleans are accepted too.
+        let spec = usda::parse_prim(
+            r#"def Xform "Tree_2" (
+    instanceable = 1
+    references = </Protos/Tree>
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+        assert!(prototype(&spec).is_some());
+
+        let spec = usda::parse_prim(
+            r#"def Xform "Rock" (
+    instanceable = false
+    references = @rock.usd@</Rock>
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+        assert_eq!(prototype(&spec), None);
+    }
+
+    #[test]
+    fn test_prim_metadata() {
+        let spec = usda::parse_prim(
+            r#"over "Chair" (
+    active = false
+    assetInfo = {
+        string identifier = "chair.usd"
+        string version = "2"
+    }
+    customData = {
+        int lod = 1
+    }
+    "A chair."
+    kind = "component"
+)
+{
+    token visibility = "invisible"
+}"#,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(bool_meta(&spec, "active"), Some(false));
+        assert_eq!(bool_meta(&spec, "hidden"), None);
+        assert_eq!(visibility(&spec), Visibility::Invisible);
+        assert_eq!(
+            dictionary(&spec, "customData"),
+            vec![("lod".to_string(), Value::Int(1))]
+        );
+        assert_eq!(
+            dictionary(&spec, "assetInfo")[1],
+            ("version".to_string(), Value::String("2".into()))
+        );
+        assert_eq!(
+            find_meta(&spec.metadata, "doc").and_then(Node::as_str),
+            Some("A chair.")
+        );
+        let kind = kind(&spec);
+        assert_eq!(kind, Some(Kind::Component));
+        assert!(kind.unwrap().is_model() && !kind.unwrap().is_group());
+    }
+
+    #[test]
+    fn test_applied_schemas() {
+        let spec = usda::parse_prim(
+            r#"def Mesh "Body" (
+    prepend apiSchemas = ["MaterialBindingAPI", "CollectionAPI:lights"]
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let schemas = applied_schemas(&spec);
+        assert_eq!(schemas, vec!["MaterialBindingAPI", "CollectionAPI:lights"]);
+        assert!(schemas.iter().any(|s| schema_matches(s, "CollectionAPI")));
+        assert!(schemas
+            .iter()
+            .any(|s| schema_matches(s, "CollectionAPI:lights")));
+        assert!(!schemas.iter().any(|s| schema_matches(s, "Collection")));
+        assert_eq!(PrimType::from_token(&spec.type_name), Some(PrimType::Mesh));
+        assert!(PrimType::RectLight.is_light() && !PrimType::Mesh.is_light());
+    }
+
+    #[test]
+    fn test_composition_arcs() {
+        let spec = usda::parse_prim(
+            r#"def Xform "Car" (
+    prepend inherits = </_class_Vehicle>
+    prepend payload = @./car_geo.usd@</Geo> (offset = 10; scale = 2)
+    prepend references = [@./wheels.usd@, </Shared/Body>]
+    delete references = @./old.usd@
+)
+{
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let refs = references(&spec, "references");
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].asset_path.as_deref(), Some("./wheels.usd"));
+        assert_eq!(refs[0].prim_path, None);
+        assert_eq!(refs[1].to_string(), "</Shared/Body>");
+
+        let payloads = references(&spec, "payload");
+        assert_eq!(payloads[0].to_string(), "@./car_geo.usd@</Geo>");
+        assert_eq!((payloads[0].offset, payloads[0].scale), (10.0, 2.0));
+
+        assert_eq!(target_paths(&spec, "inherits"), vec!["/_class_Vehicle"]);
+        assert!(target_paths(&spec, "specializes").is_empty());
+    }
+
+    #[test]
+    fn test_prim_type_and_api_schemas() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Scope "Root" (
+    prepend apiSchemas = ["CollectionAPI:lights", "GeomModelAPI"]
+)
+{
+    def Mesh "Mesh"
+    {
+    }
+
+    def SphereLight "Key"
+    {
+    }
+
+    def DomeLight "Sky"
+    {
+    }
+
+    def Skeleton "Skel"
+    {
+    }
+
+    def "Typeless"
+    {
+    }
+
+    def CustomThing "Custom"
+    {
+    }
+}
+"#,
+        )
+        .unwrap();
+        let types: Vec<Option<PrimType>> = stage.traverse().map(|p| p.prim_type()).collect();
+        assert_eq!(
+            types,
+            vec![
+                Some(PrimType::Scope),
+                Some(PrimType::Mesh),
+                Some(PrimType::SphereLight),
+                Some(PrimType::DomeLight),
+                Some(PrimType::Skeleton),
+                None,
+                None,
+            ]
+        );
+
+        let root = &stage.root_prims()[0];
+        assert!(root.has_api("CollectionAPI"));
+        assert!(root.has_api("CollectionAPI:lights"));
+        assert!(!root.has_api("CollectionAPI:other"));
+        assert!(root.has_api("GeomModelAPI"));
+        assert!(!root.child(0).unwrap().has_api("GeomModelAPI"));
+    }
+}
@@ -0,0 +1,384 @@
+//! Prim queries by path pattern, type and properties.
+
+use crate::error::{Error, Result};
+use crate::prim::{Prim, PrimType};
+use crate::stage::Stage;
+use crate::traversal::{Traversal, TraversalOptions};
+
+/// An SdfPath-style glob over absolute prim paths.
+///
+/// Each path element is matched separately: `*` matches any run of
+/// characters within an element and `?` matches a single character. An
+/// element of `**` matches any number of elements, including none.
+///
+/// # Example
+/// ```
+/// use tinyusdz_rs::PathPattern;
+///
+/// let pattern = PathPattern::new("/World/**/Wheel*").unwrap();
+/// assert!(pattern.matches("/World/Wheel_FL"));
+/// assert!(pattern.matches("/World/Car/Axle/Wheel_FL"));
+/// assert!(!pattern.matches("/World/Car/Body"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PathPattern {
+    elements: Vec<Element>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Element {
+    /// `**`
+    AnyDepth,
+    Glob(String),
+}
+
+impl PathPattern {
+    /// Parses a pattern; it must be an absolute path.
+    pub fn new(pattern: &str) -> Result<Self> {
+        let rest = pattern
+            .strip_prefix('/')
+            .ok_or_else(|| Error::InvalidPath(format!("Pattern is not absolute: {}", pattern)))?;
+        let mut elements = Vec::new();
+        for element in rest.split('/') {
+            match element {
+                "" => {
+                    return Err(Error::InvalidPath(format!(
+                        "Pattern has an empty element: {}",
+                        pattern
+                    )))
+                }
+                "**" => {
+                    // Consecutive `**` elements are equivalent to one.
+                    if elements.last() != Some(&Element::AnyDepth) {
+                        elements.push(Element::AnyDepth);
+                    }
+                }
+                _ => elements.push(Element::Glob(element.to_string())),
+            }
+        }
+        Ok(PathPattern { elements })
+    }
+
+    /// Returns true if an absolute prim path matches the pattern.
+    pub fn matches(&self, path: &str) -> bool {
+        let Some(rest) = path.strip_prefix('/') else {
+            return false;
+        };
+        let states = rest
+            .split('/')
+            .fold(self.start(), |states, name| self.step(&states, name));
+        self.is_match(&states)
+    }
+
+    /// Returns the states before any element is matched.
+    fn start(&self) -> Vec<usize> {
+        self.closure(vec![0])
+    }
+
+    /// Advances the states past the path element `name`. An empty result
+    /// means no descendant can match either.
+    fn step(&self, states: &[usize], name: &str) -> Vec<usize> {
+        let mut next = Vec::new();
+        for &i in states {
+            match self.elements.get(i) {
+                Some(Element::AnyDepth) => next.push(i),
+                Some(Element::Glob(glob)) if glob_match(glob, name) => next.push(i + 1),
+                _ => {}
+            }
+        }
+        self.closure(next)
+    }
+
+    /// Adds the states reached by letting a `**` match no elements.
+    fn closure(&self, mut states: Vec<usize>) -> Vec<usize> {
+        let mut i = 0;
+        while i < states.len() {
+            let state = states[i];
+            if self.elements.get(state) == Some(&Element::AnyDepth) {
+                states.push(state + 1);
+            }
+            i += 1;
+        }
+        states.sort_unstable();
+        states.dedup();
+        states
+    }
+
+    fn is_match(&self, states: &[usize]) -> bool {
+        states.contains(&self.elements.len())
+    }
+}
+
+/// Matches a single path element against a glob with `*` and `?`.
+fn glob_match(glob: &str, name: &str) -> bool {
+    let glob: Vec<char> = glob.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    let (mut g, mut n) = (0, 0);
+    // Position after the last `*` and the name position it was tried at.
+    let mut backtrack = None;
+    while n < name.len() {
+        match glob.get(g) {
+            Some('*') => {
+                g += 1;
+                backtrack = Some((g, n));
+            }
+            Some('?') => {
+                g += 1;
+                n += 1;
+            }
+            Some(&c) if c == name[n] => {
+                g += 1;
+                n += 1;
+            }
+            _ => match backtrack {
+                Some((bg, bn)) => {
+                    g = bg;
+                    n = bn + 1;
+                    backtrack = Some((bg, bn + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    glob[g..].iter().all(|&c| c == '*')
+}
+
+/// A lazy query over the prims of a stage.
+///
+/// Returned by [`Stage::find`]. Subtrees that cannot match the pattern are
+/// not visited.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::{PrimType, Stage};
+///
+/// let stage = Stage::open("scene.usda").unwrap();
+/// let lods = stage
+///     .find("/World/Props/**/*_LOD0")
+///     .unwrap()
+///     .of_type(PrimType::Mesh)
+///     .with_property("points");
+/// for prim in lods {
+///     println!("{}", prim.name());
+/// }
+/// ```
+pub struct PrimQuery<'a> {
+    pattern: PathPattern,
+    prim_type: Option<PrimType>,
+    properties: Vec<String>,
+    traversal: Traversal<'a>,
+    /// Pattern states for each depth of the current path.
+    states: Vec<Vec<usize>>,
+}
+
+impl<'a> PrimQuery<'a> {
+    pub(crate) fn new(stage: &'a Stage, pattern: PathPattern) -> Self {
+        PrimQuery {
+            pattern,
+            prim_type: None,
+            properties: Vec::new(),
+            traversal: stage.traverse_with(TraversalOptions::new()),
+            states: Vec::new(),
+        }
+    }
+
+    /// Only returns prims of the given type.
+    pub fn of_type(mut self, prim_type: PrimType) -> Self {
+        self.prim_type = Some(prim_type);
+        self
+    }
+
+    /// Only returns prims with a property of the given name (e.g.
+    /// "points"). May be called several times to require each property.
+    pub fn with_property(mut self, name: impl Into<String>) -> Self {
+        self.properties.push(name.into());
+        self
+    }
+
+    /// Returns the full path of the last returned prim.
+    pub fn path(&self) -> &str {
+        self.traversal.path()
+    }
+
+    fn accept(&self, prim: &Prim<'_>) -> bool {
+        if self.prim_type.is_some() && prim.prim_type() != self.prim_type {
+            return false;
+        }
+        if self.properties.is_empty() {
+            return true;
+        }
+        let names = prim.property_names();
+        self.properties.iter().all(|p| names.contains(p))
+    }
+}
+
+impl<'a> Iterator for PrimQuery<'a> {
+    type Item = Prim<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(prim) = self.traversal.next() {
+            let depth = self.traversal.depth();
+            self.states.truncate(depth);
+            let parent = match self.states.last() {
+                Some(states) => states.clone(),
+                None => self.pattern.start(),
+            };
+            let states = self.pattern.step(&parent, prim.name());
+            if states.is_empty() {
+                self.traversal.skip_children();
+                continue;
+            }
+
+            let is_match = self.pattern.is_match(&states);
+            self.states.push(states);
+            if is_match && self.accept(&prim) {
+                return Some(prim);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROPS: &str = r#"#usda 1.0
+
+def Xform "World"
+{
+    def Xform "Props"
+    {
+        def Mesh "Chair_LOD0"
+        {
+            point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+        }
+
+        def Mesh "Chair_LOD1"
+        {
+            point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+        }
+
+        def Xform "Set"
+        {
+            def Xform "Table"
+            {
+                def Mesh "Table_LOD0"
+                {
+                    point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+                }
+
+                def Mesh "Empty_LOD0"
+                {
+                }
+
+                def Xform "Group_LOD0"
+                {
+                }
+            }
+        }
+    }
+
+    def Xform "Lights"
+    {
+        def Mesh "Lamp_LOD0"
+        {
+            point3f[] points = [(0, 0, 0), (1, 0, 0), (0, 1, 0)]
+        }
+    }
+}
+"#;
+
+    #[test]
+    fn test_path_pattern() {
+        let pattern = PathPattern::new("/World/Props/**/*_LOD0").unwrap();
+        assert!(pattern.matches("/World/Props/Chair_LOD0"));
+        assert!(pattern.matches("/World/Props/Set/Table/Table_LOD0"));
+        assert!(!pattern.matches("/World/Props/Chair_LOD1"));
+        assert!(!pattern.matches("/World/Chair_LOD0"));
+
+        // Elements below a non-matching prefix are pruned.
+        let states = pattern.step(&pattern.start(), "Lights");
+        assert!(states.is_empty());
+
+        let pattern = PathPattern::new("/**").unwrap();
+        assert!(pattern.matches("/World"));
+        assert!(pattern.matches("/World/A/B"));
+
+        assert!(PathPattern::new("/Geo/Mesh_??")
+            .unwrap()
+            .matches("/Geo/Mesh_01"));
+        assert!(!PathPattern::new("/Geo/Mesh_??")
+            .unwrap()
+            .matches("/Geo/Mesh_1"));
+        assert!(PathPattern::new("/*a*b*").unwrap().matches("/xaYbZ"));
+        assert!(PathPattern::new("World").is_err());
+        assert!(PathPattern::new("/World//Mesh").is_err());
+    }
+
+    #[test]
+    fn test_stage_find() {
+        let stage = Stage::from_usda(PROPS.as_bytes()).unwrap();
+        let paths = |query: PrimQuery<'_>| query.map(|p| p.path()).collect::<Vec<_>>();
+
+        // Everything matching the pattern, whatever its type.
+        assert_eq!(
+            paths(stage.find("/World/Props/**/*_LOD0").unwrap()),
+            [
+                "/World/Props/Chair_LOD0",
+                "/World/Props/Set/Table/Table_LOD0",
+                "/World/Props/Set/Table/Empty_LOD0",
+                "/World/Props/Set/Table/Group_LOD0",
+            ]
+        );
+
+        // Type and property filters.
+        assert_eq!(
+            paths(
+                stage
+                    .find("/World/Props/**/*_LOD0")
+                    .unwrap()
+                    .of_type(PrimType::Mesh)
+            ),
+            [
+                "/World/Props/Chair_LOD0",
+                "/World/Props/Set/Table/Table_LOD0",
+                "/World/Props/Set/Table/Empty_LOD0",
+            ]
+        );
+        assert_eq!(
+            paths(
+                stage
+                    .find("/World/Props/**/*_LOD0")
+                    .unwrap()
+                    .of_type(PrimType::Mesh)
+                    .with_property("points")
+            ),
+            [
+                "/World/Props/Chair_LOD0",
+                "/World/Props/Set/Table/Table_LOD0"
+            ]
+        );
+
+        // The query reports the path of the last returned prim.
+        let mut query = stage.find("/World/*/Chair_*").unwrap();
+        assert!(query.next().is_some());
+        assert_eq!(query.path(), "/World/Props/Chair_LOD0");
+        assert!(query.next().is_some());
+        assert_eq!(query.path(), "/World/Props/Chair_LOD1");
+        assert!(query.next().is_none());
+
+        // Descendants of a full match are pruned once no element is left.
+        assert_eq!(
+            paths(stage.find("/World/Props/*").unwrap()),
+            [
+                "/World/Props/Chair_LOD0",
+                "/World/Props/Chair_LOD1",
+                "/World/Props/Set",
+            ]
+        );
+        assert_eq!(paths(stage.find("/World/Lights/*_LOD1").unwrap()).len(), 0);
+
+        assert!(stage.find("World").is_err());
+    }
+}
@@ -0,0 +1,1035 @@
+//! UsdSkel skeletons, animation and skinning.
+
+use std::collections::HashMap;
+
+use crate::attribute::spec_interpolation;
+use crate::error::{Error, Result};
+use crate::mesh::{matrix_multiply, Interpolation, Mesh};
+use crate::stage::Stage;
+use crate::usda::{find_meta, PrimSpec};
+use crate::value::Value;
+use crate::xform;
+
+/// A skeleton extracted from a USD Skeleton prim.
+#[derive(Debug, Clone)]
+pub struct Skeleton {
+    /// The name of the skeleton.
+    pub name: String,
+    /// The prim path of the skeleton.
+    pub path: String,
+    /// Joint paths (e.g. "Hips/Spine"), ordered parents before children.
+    pub joints: Vec<String>,
+    /// Optional display names of the joints.
+    pub joint_names: Vec<String>,
+    /// Skeleton-space transform of each joint at bind time.
+    pub bind_transforms: Vec<[[f64; 4]; 4]>,
+    /// Parent-relative transform of each joint in the rest pose.
+    pub rest_transforms: Vec<[[f64; 4]; 4]>,
+    /// Parent of each joint, derived from the joint paths.
+    pub parent_indices: Vec<Option<usize>>,
+    /// Path of the bound animation (`skel:animationSource`, possibly
+    /// inherited).
+    pub animation_source: Option<String>,
+    /// World transformation matrix (4x4).
+    pub world_transform: [[f64; 4]; 4],
+}
+
+impl Skeleton {
+    /// Creates an empty skeleton.
+    pub fn new(name: impl Into<String>) -> Self {
+        Skeleton {
+            name: name.into(),
+            path: String::new(),
+            joints: Vec::new(),
+            joint_names: Vec::new(),
+            bind_transforms: Vec::new(),
+            rest_transforms: Vec::new(),
+            parent_indices: Vec::new(),
+            animation_source: None,
+            world_transform: xform::identity(),
+        }
+    }
+
+    /// Returns the number of joints.
+    pub fn joint_count(&self) -> usize {
+        self.joints.len()
+    }
+
+    /// Returns the index of the joint with the given path.
+    pub fn joint_index(&self, joint: &str) -> Option<usize> {
+        self.joints.iter().position(|j| j == joint)
+    }
+
+    /// Composes parent-relative joint transforms into skeleton space.
+    ///
+    /// Parents must precede their children, as UsdSkel requires.
+    pub fn skeleton_transforms(&self, local: &[[[f64; 4]; 4]]) -> Vec<[[f64; 4]; 4]> {
+        let mut result: Vec<[[f64; 4]; 4]> = Vec::with_capacity(local.len());
+        for (joint, m) in local.iter().enumerate() {
+            let parent = self
+                .parent_indices
+                .get(joint)
+                .copied()
+                .flatten()
+                .filter(|&p| p < result.len());
+            result.push(match parent {
+                Some(parent) => matrix_multiply(*m, result[parent]),
+                None => *m,
+            });
+        }
+        result
+    }
+
+    /// Returns the skeleton-space transforms of the rest pose.
+    pub fn rest_skeleton_transforms(&self) -> Vec<[[f64; 4]; 4]> {
+        self.skeleton_transforms(&self.rest_transforms)
+    }
+}
+
+/// Joint and blend shape animation from a USD SkelAnimation prim.
+///
+/// Each track holds time samples of joint-ordered arrays. An attribute with
+/// only a default value is stored as a single sample at time 0.
+#[derive(Debug, Clone, Default)]
+pub struct SkelAnimation {
+    /// The name of the animation.
+    pub name: String,
+    /// The prim path of the animation.
+    pub path: String,
+    /// Joint paths the tracks are ordered by.
+    pub joints: Vec<String>,
+    /// Parent-relative joint translations.
+    pub translations: Vec<(f64, Vec<[f32; 3]>)>,
+    /// Parent-relative joint rotations as quaternions `(x, y, z, w)`.
+    pub rotations: Vec<(f64, Vec<[f32; 4]>)>,
+    /// Parent-relative joint scales.
+    pub scales: Vec<(f64, Vec<[f32; 3]>)>,
+    /// Names of the animated blend shapes.
+    pub blend_shapes: Vec<String>,
+    /// Blend shape weights, ordered by `blend_shapes`.
+    pub blend_shape_weights: Vec<(f64, Vec<f32>)>,
+}
+
+impl SkelAnimation {
+    /// Returns the parent-relative joint transforms at `time`, ordered by
+    /// `joints`.
+    ///
+    /// Translations and scales are interpolated linearly and rotations
+    /// spherically between the surrounding samples.
+    pub fn sample(&self, time: f64) -> Vec<[[f64; 4]; 4]> {
+        let translations = xform::sample_track(&self.translations, time, xform::lerp3);
+        let rotations = xform::sample_track(&self.rotations, time, xform::slerp);
+        let scales = xform::sample_track(&self.scales, time, xform::lerp3);
+
+        (0..self.joints.len())
+            .map(|joint| {
+                let s = scales
+                    .get(joint)
+                    .map_or_else(xform::identity, |s| xform::scale(s.map(|c| c as f64)));
+                let r = rotations.get(joint).map_or_else(xform::identity, |q| {
+                    xform::quat_to_matrix(q.map(|c| c as f64))
+                });
+                let t = translations
+                    .get(joint)
+                    .map_or_else(xform::identity, |t| xform::translation(t.map(|c| c as f64)));
+                matrix_multiply(matrix_multiply(s, r), t)
+            })
+            .collect()
+    }
+
+    /// Returns the parent-relative transforms of every joint of `skeleton`
+    /// at `time`.
+    ///
+    /// Joints the animation does not drive keep their rest transform.
+    pub fn sample_skeleton(&self, skeleton: &Skeleton, time: f64) -> Vec<[[f64; 4]; 4]> {
+        let mut pose: Vec<_> = (0..skeleton.joint_count())
+            .map(|joint| {
+                skeleton
+                    .rest_transforms
+                    .get(joint)
+                    .copied()
+                    .unwrap_or_else(xform::identity)
+            })
+            .collect();
+        for (joint, m) in self.joints.iter().zip(self.sample(time)) {
+            if let Some(index) = skeleton.joint_index(joint) {
+                pose[index] = m;
+            }
+        }
+        pose
+    }
+
+    /// Returns the blend shape weights at `time`, ordered by `blend_shapes`.
+    pub fn blend_shape_weights_at(&self, time: f64) -> Vec<f32> {
+        xform::sample_track(&self.blend_shape_weights, time, |a, b, t| a + (b - a) * t)
+    }
+
+    /// Returns the blend shape weights at `time`, ordered by the blend shapes
+    /// of `mesh`. Shapes the animation does not drive get weight 0.
+    pub fn blend_shape_weights_for(&self, mesh: &Mesh, time: f64) -> Vec<f32> {
+        let weights = self.blend_shape_weights_at(time);
+        mesh.blend_shapes
+            .iter()
+            .map(|shape| {
+                self.blend_shapes
+                    .iter()
+                    .position(|name| *name == shape.name)
+                    .and_then(|i| weights.get(i).copied())
+                    .unwrap_or(0.0)
+            })
+            .collect()
+    }
+
+    /// Returns the earliest and latest sample times of all tracks.
+    pub fn time_range(&self) -> Option<(f64, f64)> {
+        let times = self
+            .translations
+            .iter()
+            .map(|(t, _)| *t)
+            .chain(self.rotations.iter().map(|(t, _)| *t))
+            .chain(self.scales.iter().map(|(t, _)| *t))
+            .chain(self.blend_shape_weights.iter().map(|(t, _)| *t));
+        times.fold(None, |range, t| match range {
+            None => Some((t, t)),
+            Some((start, end)) => Some((start.min(t), end.max(t))),
+        })
+    }
+}
+
+/// Deforms a mesh by linear blend skinning.
+///
+/// `pose` holds the parent-relative transform of every joint of `skeleton`,
+/// as returned by [`SkelAnimation::sample_skeleton`]. Points are moved by
+/// `geom_bind_transform`, then by each influencing joint's transform
+/// relative to its bind transform, blended by weight. The result is in
+/// skeleton space, so its `world_transform` is the skeleton's. Normals are
+/// transformed without inverse-transpose correction, which is exact for
+/// rotations and uniform scales.
+///
+/// Returns an error if the mesh has no joint influences or an influence
+/// refers to a joint outside the skeleton.
+pub fn skin_mesh(mesh: &Mesh, skeleton: &Skeleton, pose: &[[[f64; 4]; 4]]) -> Result<Mesh> {
+    let influences = mesh
+        .joint_influences
+        .as_ref()
+        .ok_or_else(|| Error::AttributeNotFound("primvars:skel:jointIndices".into()))?;
+
+    let joint_count = skeleton.joint_count();
+    if pose.len() != joint_count {
+        return Err(Error::IndexOutOfBounds {
+            index: pose.len(),
+            len: joint_count,
+        });
+    }
+
+    // Joints without bind transforms are bound at their rest pose.
+    let binds = if skeleton.bind_transforms.len() == joint_count {
+        skeleton.bind_transforms.clone()
+    } else {
+        skeleton.rest_skeleton_transforms()
+    };
+    let posed = skeleton.skeleton_transforms(pose);
+    let skinning: Vec<[[f64; 4]; 4]> = binds
+        .iter()
+        .zip(&posed)
+        .map(|(bind, posed)| {
+            let inverse = xform::invert(bind).unwrap_or_else(xform::identity);
+            matrix_multiply(inverse, *posed)
+        })
+        .collect();
+
+    let mapping = influences.joint_mapping(skeleton);
+    let joint_matrix = |index: i32| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|i| mapping.get(i).copied().flatten())
+            .map(|joint| &skinning[joint])
+            .ok_or(Error::IndexOutOfBounds {
+                index: index.max(0) as usize,
+                len: mapping.len(),
+            })
+    };
+
+    let blend = |point: usize, v: [f64; 3], vector: bool| -> Result<[f64; 3]> {
+        let mut result = [0.0; 3];
+        for (joint, weight) in influences.point_influences(point) {
+            if weight == 0.0 {
+                continue;
+            }
+            let m = joint_matrix(joint)?;
+            let moved = if vector {
+                xform::transform_vector(m, v)
+            } else {
+                xform::transform_point(m, v)
+            };
+            for (r, c) in result.iter_mut().zip(moved) {
+                *r += c * weight as f64;
+            }
+        }
+        Ok(result)
+    };
+
+    let geom_bind = &influences.geom_bind_transform;
+    let mut result = mesh.clone();
+    for (i, p) in result.points.iter_mut().enumerate() {
+        let bound = xform::transform_point(geom_bind, p.map(|c| c as f64));
+        *p = blend(i, bound, false)?.map(|c| c as f32);
+    }
+
+    if let Some(normals) = &mut result.normals {
+        if matches!(
+            mesh.normals_interpolation,
+            Interpolation::Vertex | Interpolation::Varying
+        ) {
+            for (i, n) in normals.iter_mut().enumerate() {
+                let bound = xform::transform_vector(geom_bind, n.map(|c| c as f64));
+                let skinned = blend(i, bound, true)?;
+                let len = skinned.iter().map(|c| c * c).sum::<f64>().sqrt();
+                if len > 0.0 {
+                    *n = skinned.map(|c| (c / len) as f32);
+                }
+            }
+        } else {
+            // Per-face normals cannot follow per-point weights.
+            result.compute_normals(crate::mesh::NormalMode::Smooth)?;
+        }
+    }
+
+    result.tangents = None;
+    result.local_transform = xform::identity();
+    result.world_transform = skeleton.world_transform;
+    Ok(result)
+}
+
+/// A blend shape (morph target) from a USD BlendShape prim.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendShape {
+    /// The name the mesh uses for this shape (`skel:blendShapes`).
+    pub name: String,
+    /// The prim path of the BlendShape prim.
+    pub path: String,
+    /// Point offsets at weight 1.
+    pub offsets: Vec<[f32; 3]>,
+    /// Normal offsets at weight 1 (optional).
+    pub normal_offsets: Option<Vec<[f32; 3]>>,
+    /// Points the offsets apply to; all points in order when `None`.
+    pub point_indices: Option<Vec<i32>>,
+    /// Intermediate shapes, ordered by weight.
+    pub inbetweens: Vec<Inbetween>,
+}
+
+/// An intermediate shape of a [`BlendShape`] at a given weight.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inbetween {
+    /// The name of the inbetween (`inbetweens:<name>`).
+    pub name: String,
+    /// The weight at which the inbetween is reached exactly.
+    pub weight: f32,
+    /// Point offsets, matching the shape's `offsets`.
+    pub offsets: Vec<[f32; 3]>,
+    /// Normal offsets (optional).
+    pub normal_offsets: Option<Vec<[f32; 3]>>,
+}
+
+impl BlendShape {
+    /// Returns the point and normal offsets for `weight`.
+    ///
+    /// The shape is piecewise linear through the rest shape at weight 0, the
+    /// inbetweens, and `offsets` at weight 1. Weights outside that range
+    /// extrapolate the nearest segment.
+    ///
+    /// The shape is expected to be [valid](Self::validate); offsets past the
+    /// shortest array are ignored otherwise.
+    pub fn offsets_at(&self, weight: f32) -> (Vec<[f32; 3]>, Vec<[f32; 3]>) {
+        let zero = vec![[0.0; 3]; self.offsets.len()];
+        let normals = |n: &Option<Vec<[f32; 3]>>| n.clone().unwrap_or_else(|| zero.clone());
+
+        let mut keys = vec![(0.0f32, zero.clone(), zero.clone())];
+        keys.extend(
+            self.inbetweens
+                .iter()
+                .filter(|i| i.offsets.len() == self.offsets.len())
+                .map(|i| (i.weight, i.offsets.clone(), normals(&i.normal_offsets))),
+        );
+        keys.push((1.0, self.offsets.clone(), normals(&self.normal_offsets)));
+        keys.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let upper = keys
+            .iter()
+            .position(|(w, _, _)| *w >= weight)
+            .unwrap_or(keys.len() - 1)
+            .max(1);
+        let (w0, p0, n0) = &keys[upper - 1];
+        let (w1, p1, n1) = &keys[upper];
+        let t = if w1 > w0 {
+            (weight - w0) / (w1 - w0)
+        } else {
+            1.0
+        };
+
+        let mix = |a: &[[f32; 3]], b: &[[f32; 3]]| -> Vec<[f32; 3]> {
+            a.iter()
+                .zip(b)
+                .map(|(a, b)| [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t))
+                .collect()
+        };
+        (mix(p0, p1), mix(n0, n1))
+    }
+
+    /// Checks that `normal_offsets`, `point_indices` and the offsets of every
+    /// inbetween have one entry per offset.
+    ///
+    /// Returns [`Error::InvalidMesh`] naming the first array whose length
+    /// does not match.
+    pub fn validate(&self) -> Result<()> {
+        let expected = self.offsets.len();
+        let mut lengths: Vec<(String, usize)> = Vec::new();
+        if let Some(normals) = &self.normal_offsets {
+            lengths.push(("normalOffsets".into(), normals.len()));
+        }
+        if let Some(indices) = &self.point_indices {
+            lengths.push(("pointIndices".into(), indices.len()));
+        }
+        for inbetween in &self.inbetweens {
+            let name = format!("inbetweens:{}", inbetween.name);
+            if let Some(normals) = &inbetween.normal_offsets {
+                lengths.push((format!("{}:normalOffsets", name), normals.len()));
+            }
+            lengths.push((name, inbetween.offsets.len()));
+        }
+
+        match lengths.into_iter().find(|(_, len)| *len != expected) {
+            Some((array, len)) => Err(Error::InvalidMesh(format!(
+                "blend shape {}: {} has {} entries but offsets has {}",
+                self.path, array, len, expected
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the shape with dense offsets for the given source points, in
+    /// order.
+    pub(crate) fn remap_points(&self, points: &[usize]) -> BlendShape {
+        let dense_index: HashMap<usize, usize> = match &self.point_indices {
+            Some(indices) => indices
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &p)| usize::try_from(p).ok().map(|p| (p, i)))
+                .collect(),
+            None => (0..self.offsets.len()).map(|i| (i, i)).collect(),
+        };
+        let remap = |offsets: &[[f32; 3]]| -> Vec<[f32; 3]> {
+            points
+                .iter()
+                .map(|p| {
+                    dense_index
+                        .get(p)
+                        .and_then(|&i| offsets.get(i))
+                        .copied()
+                        .unwrap_or([0.0; 3])
+                })
+                .collect()
+        };
+
+        BlendShape {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            offsets: remap(&self.offsets),
+            normal_offsets: self.normal_offsets.as_deref().map(remap),
+            point_indices: None,
+            inbetweens: self
+                .inbetweens
+                .iter()
+                .map(|inbetween| Inbetween {
+                    name: inbetween.name.clone(),
+                    weight: inbetween.weight,
+                    offsets: remap(&inbetween.offsets),
+                    normal_offsets: inbetween.normal_offsets.as_deref().map(remap),
+                })
+                .collect(),
+        }
+    }
+
+    /// Reads a blend shape from a BlendShape prim's spec.
+    ///
+    /// Arrays are kept as authored; mismatched lengths are reported by
+    /// [`validate`](Self::validate).
+    pub(crate) fn from_spec(spec: &PrimSpec, name: &str, path: &str) -> BlendShape {
+        let offsets = match spec.value("offsets") {
+            Some(Value::Float3Array(v)) => v,
+            _ => Vec::new(),
+        };
+        let vectors = |name: &str| match spec.value(name) {
+            Some(Value::Float3Array(v)) => Some(v),
+            _ => None,
+        };
+
+        let mut inbetweens: Vec<Inbetween> = spec
+            .properties
+            .iter()
+            .filter_map(|p| {
+                let inbetween = p.name.strip_prefix("inbetweens:")?;
+                if inbetween.contains(':') {
+                    return None;
+                }
+                Some(Inbetween {
+                    name: inbetween.to_string(),
+                    weight: find_meta(&p.metadata, "weight")
+                        .and_then(|node| node.as_f64())
+                        .unwrap_or(0.0) as f32,
+                    offsets: vectors(&p.name)?,
+                    normal_offsets: vectors(&format!("{}:normalOffsets", p.name)),
+                })
+            })
+            .collect();
+        inbetweens.sort_by(|a, b| a.weight.total_cmp(&b.weight));
+
+        let point_indices = match spec.value("pointIndices") {
+            Some(Value::IntArray(indices)) => Some(indices),
+            _ => None,
+        };
+        BlendShape {
+            name: name.to_string(),
+            path: path.to_string(),
+            normal_offsets: vectors("normalOffsets"),
+            offsets,
+            point_indices,
+            inbetweens,
+        }
+    }
+}
+
+/// A `SkelRoot` prim and the skeletons beneath it.
+#[derive(Debug, Clone)]
+pub struct SkelRoot {
+    /// The name of the skel root.
+    pub name: String,
+    /// The prim path of the skel root.
+    pub path: String,
+    /// Skeletons defined beneath the skel root.
+    pub skeletons: Vec<Skeleton>,
+    /// World transformation matrix (4x4).
+    pub world_transform: [[f64; 4]; 4],
+}
+
+/// Per-point joint influences of a skinned mesh.
+///
+/// Each point has `element_size` joint indices and weights, unless the
+/// influences are `rigid`, in which case a single set applies to the whole
+/// mesh.
+#[derive(Debug, Clone, PartialEq)]
+pub struct JointInfluences {
+    /// Joint indices (`primvars:skel:jointIndices`).
+    pub indices: Vec<i32>,
+    /// Joint weights (`primvars:skel:jointWeights`).
+    pub weights: Vec<f32>,
+    /// Number of influences per point.
+    pub element_size: usize,
+    /// Whether one set of influences applies to every point (`constant`).
+    pub rigid: bool,
+    /// Transform of the mesh at bind time (`primvars:skel:geomBindTransform`).
+    pub geom_bind_transform: [[f64; 4]; 4],
+    /// Joint order used by `indices` (`skel:joints`), if it differs from
+    /// the skeleton's.
+    pub joints: Option<Vec<String>>,
+}
+
+impl JointInfluences {
+    /// Returns the `(joint index, weight)` pairs influencing a point.
+    pub fn point_influences(&self, point: usize) -> impl Iterator<Item = (i32, f32)> + '_ {
+        let start = if self.rigid {
+            0
+        } else {
+            point * self.element_size
+        };
+        let end = (start + self.element_size).min(self.indices.len().min(self.weights.len()));
+        let range = start.min(end)..end;
+        self.indices[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.weights[range].iter().copied())
+    }
+
+    /// Scales each point's weights to sum to one.
+    pub fn normalize_weights(&mut self) {
+        let size = self.element_size.max(1);
+        for weights in self.weights.chunks_mut(size) {
+            let sum: f32 = weights.iter().sum();
+            if sum > 0.0 {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+        }
+    }
+
+    /// Maps the joint indices used by `indices` to joint indices of
+    /// `skeleton`, honouring `skel:joints` when authored.
+    pub fn joint_mapping(&self, skeleton: &Skeleton) -> Vec<Option<usize>> {
+        match &self.joints {
+            Some(joints) => joints.iter().map(|j| skeleton.joint_index(j)).collect(),
+            None => (0..skeleton.joint_count()).map(Some).collect(),
+        }
+    }
+
+    /// Returns the influences of the given source points, in order.
+    pub(crate) fn remap_points(&self, points: &[usize]) -> JointInfluences {
+        let mut result = self.clone();
+        if self.rigid {
+            return result;
+        }
+
+        let size = self.element_size;
+        result.indices.clear();
+        result.weights.clear();
+        for &point in points {
+            let range = point * size..(point + 1) * size;
+            match (self.indices.get(range.clone()), self.weights.get(range)) {
+                (Some(indices), Some(weights)) => {
+                    result.indices.extend_from_slice(indices);
+                    result.weights.extend_from_slice(weights);
+                }
+                _ => {
+                    result.indices.resize(result.indices.len() + size, 0);
+                    result.weights.resize(result.weights.len() + size, 0.0);
+                }
+            }
+        }
+        result
+    }
+
+    /// Reads joint influences from a skinned prim's spec.
+    pub(crate) fn from_spec(spec: &PrimSpec) -> Option<Self> {
+        let indices_name = "primvars:skel:jointIndices";
+        let property = spec.property(indices_name)?;
+        let Some(Value::IntArray(indices)) = spec.value(indices_name) else {
+            return None;
+        };
+        let weights = match spec.value("primvars:skel:jointWeights") {
+            Some(Value::FloatArray(weights)) => weights,
+            _ => vec![1.0; indices.len()],
+        };
+
+        let element_size = find_meta(&property.metadata, "elementSize")
+            .and_then(|node| node.as_f64())
+            .map_or(1, |size| size.max(1.0) as usize);
+
+        Some(JointInfluences {
+            indices,
+            weights,
+            element_size,
+            rigid: spec_interpolation(property) == Some(Interpolation::Constant),
+            geom_bind_transform: spec
+                .value("primvars:skel:geomBindTransform")
+                .as_ref()
+                .and_then(Value::as_matrix4d)
+                .unwrap_or_else(xform::identity),
+            joints: match spec.value("skel:joints") {
+                Some(Value::TokenArray(joints)) => Some(joints),
+                _ => None,
+            },
+        })
+    }
+}
+
+/// Returns the target of a prim's `skel:skeleton` binding relationship.
+pub(crate) fn skeleton_binding(spec: &PrimSpec) -> Option<String> {
+    spec.property("skel:skeleton")
+        .filter(|p| p.is_relationship())
+        .and_then(|p| p.targets.first().cloned())
+}
+
+/// Returns the target of a prim's `skel:animationSource` relationship.
+pub(crate) fn animation_binding(spec: &PrimSpec) -> Option<String> {
+    spec.property("skel:animationSource")
+        .filter(|p| p.is_relationship())
+        .and_then(|p| p.targets.first().cloned())
+}
+
+/// Finds the binding that applies to `path`: its own, or the nearest
+/// ancestor's, since UsdSkel bindings are inherited.
+pub(crate) fn inherited_binding(bindings: &HashMap<String, String>, path: &str) -> Option<String> {
+    let mut current = path;
+    loop {
+        if let Some(target) = bindings.get(current) {
+            return Some(target.clone());
+        }
+        match current.rfind('/') {
+            Some(0) | None => return None,
+            Some(i) => current = &current[..i],
+        }
+    }
+}
+
+/// Derives each joint's parent from the joint paths.
+///
+/// A joint's parent is the nearest ancestor path that is also a joint.
+pub fn parent_indices(joints: &[String]) -> Vec<Option<usize>> {
+    let index: HashMap<&str, usize> = joints
+        .iter()
+        .enumerate()
+        .map(|(i, j)| (j.as_str(), i))
+        .collect();
+
+    joints
+        .iter()
+        .map(|joint| {
+            let mut path = joint.as_str();
+            while let Some(i) = path.rfind('/') {
+                path = &path[..i];
+                if let Some(&parent) = index.get(path) {
+                    return Some(parent);
+                }
+            }
+            None
+        })
+        .collect()
+}
+
+/// Utility to extract skeletons from a USD stage.
+pub struct SkelExtractor<'a> {
+    stage: &'a Stage,
+}
+
+impl<'a> SkelExtractor<'a> {
+    /// Creates a new skeleton extractor for the given stage.
+    pub fn new(stage: &'a Stage) -> Self {
+        SkelExtractor { stage }
+    }
+
+    /// Returns all skeletons in the stage.
+    pub fn skeletons(&self) -> Vec<Skeleton> {
+        let mut skeletons = Vec::new();
+        let mut animation_bindings = HashMap::new();
+        self.stage.walk_transforms(|visit, _, world| {
+            if let Some(animation) = animation_binding(visit.spec) {
+                animation_bindings.insert(visit.path.clone(), animation);
+            }
+
+            if visit.spec.type_name == "Skeleton" {
+                let mut skeleton = Self::extract_skeleton(visit.spec);
+                skeleton.path = visit.path.clone();
+                skeleton.animation_source = inherited_binding(&animation_bindings, &visit.path);
+                skeleton.world_transform = *world;
+                skeletons.push(skeleton);
+            }
+        });
+        skeletons
+    }
+
+    /// Returns all skeletal animations in the stage.
+    pub fn animations(&self) -> Vec<SkelAnimation> {
+        let mut animations = Vec::new();
+        self.stage.walk((), |visit, _| {
+            if visit.spec.type_name == "SkelAnimation" {
+                let mut animation = Self::extract_animation(visit.spec);
+                animation.path = visit.path.clone();
+                animations.push(animation);
+            }
+            Some(())
+        });
+        animations
+    }
+
+    /// Returns the animation bound to `skeleton`, if any.
+    pub fn animation_for(&self, skeleton: &Skeleton) -> Option<SkelAnimation> {
+        let source = skeleton.animation_source.as_ref()?;
+        self.animations().into_iter().find(|a| &a.path == source)
+    }
+
+    /// Extracts animation data from a SkelAnimation prim's spec.
+    fn extract_animation(spec: &PrimSpec) -> SkelAnimation {
+        let tokens = |name: &str| match spec.value(name) {
+            Some(Value::TokenArray(tokens)) => tokens,
+            _ => Vec::new(),
+        };
+
+        SkelAnimation {
+            name: spec.name.clone(),
+            path: String::new(),
+            joints: tokens("joints"),
+            translations: spec.track("translations", |v| match v {
+                Value::Float3Array(v) => Some(v),
+                _ => None,
+            }),
+            rotations: spec.track("rotations", |v| match v {
+                Value::Float4Array(v) => Some(v),
+                _ => None,
+            }),
+            scales: spec.track("scales", |v| match v {
+                Value::Float3Array(v) => Some(v),
+                _ => None,
+            }),
+            blend_shapes: tokens("blendShapes"),
+            blend_shape_weights: spec.track("blendShapeWeights", |v| match v {
+                Value::FloatArray(v) => Some(v),
+                _ => None,
+            }),
+        }
+    }
+
+    /// Returns all skel roots in the stage with the skeletons beneath them.
+    pub fn skel_roots(&self) -> Vec<SkelRoot> {
+        let mut roots = Vec::new();
+        self.stage.walk_transforms(|visit, _, world| {
+            if visit.spec.type_name == "SkelRoot" {
+                roots.push(SkelRoot {
+                    name: visit.spec.name.clone(),
+                    path: visit.path.clone(),
+                    skeletons: Vec::new(),
+                    world_transform: *world,
+                });
+            }
+        });
+
+        for skeleton in self.skeletons() {
+            // Skel roots may nest; the innermost one owns the skeleton.
+            let owner = roots
+                .iter_mut()
+                .filter(|root| skeleton.path.starts_with(&format!("{}/", root.path)))
+                .max_by_key(|root| root.path.len());
+            if let Some(root) = owner {
+                root.skeletons.push(skeleton);
+            }
+        }
+        roots
+    }
+
+    /// Extracts skeleton data from a Skeleton prim's spec.
+    fn extract_skeleton(spec: &PrimSpec) -> Skeleton {
+        let mut skeleton = Skeleton::new(&spec.name);
+        let tokens = |name: &str| match spec.value(name) {
+            Some(Value::TokenArray(tokens)) => tokens,
+            _ => Vec::new(),
+        };
+        let matrices = |name: &str| match spec.value(name) {
+            Some(Value::Matrix4dArray(matrices)) => matrices,
+            _ => Vec::new(),
+        };
+
+        skeleton.joints = tokens("joints");
+        skeleton.joint_names = tokens("jointNames");
+        skeleton.bind_transforms = matrices("bindTransforms");
+        skeleton.rest_transforms = matrices("restTransforms");
+        skeleton.parent_indices = parent_indices(&skeleton.joints);
+
+        // Without rest transforms, derive them from the bind pose.
+        if skeleton.rest_transforms.len() != skeleton.joints.len()
+            && skeleton.bind_transforms.len() == skeleton.joints.len()
+        {
+            skeleton.rest_transforms = skeleton
+                .bind_transforms
+                .iter()
+                .enumerate()
+                .map(|(joint, bind)| match skeleton.parent_indices[joint] {
+                    Some(parent) => xform::invert(&skeleton.bind_transforms[parent])
+                        .map_or(*bind, |inverse| matrix_multiply(*bind, inverse)),
+                    None => *bind,
+                })
+                .collect();
+        }
+
+        skeleton
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::parse_prim;
+
+    #[test]
+    fn test_extract_skeleton_and_influences() {
+        let root = parse_prim(
+            r#"def SkelRoot "Character" (
+    prepend apiSchemas = ["SkelBindingAPI"]
+)
+{
+    rel skel:skeleton = </Character/Rig>
+
+    def Skeleton "Rig"
+    {
+        uniform matrix4d[] bindTransforms = [( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 0, 0, 1) ), ( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 2, 0, 1) ), ( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 3, 0, 1) )]
+        uniform token[] joints = ["Hips", "Hips/Spine", "Hips/Spine/Neck"]
+    }
+
+    def Mesh "Body"
+    {
+        int[] faceVertexCounts = [3]
+        int[] faceVertexIndices = [0, 1, 2]
+        point3f[] points = [(0, 0, 0), (1, 2, 0), (0, 3, 0)]
+        int[] primvars:skel:jointIndices = [0, 1, 1, 2, 2, 0] (
+            elementSize = 2
+            interpolation = "vertex"
+        )
+        float[] primvars:skel:jointWeights = [1, 0, 0.5, 0.5, 1, 0] (
+            elementSize = 2
+            interpolation = "vertex"
+        )
+        matrix4d primvars:skel:geomBindTransform = ( (1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 0, 1, 1) )
+    }
+}"#,
+            true,
+        )
+        .unwrap();
+
+        let skeleton = SkelExtractor::extract_skeleton(&root.children[0]);
+        assert_eq!(skeleton.parent_indices, vec![None, Some(0), Some(1)]);
+        // Rest transforms are derived from the bind pose.
+        assert_eq!(skeleton.rest_transforms[2][3], [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(
+            skeleton.rest_skeleton_transforms()[2][3],
+            [0.0, 3.0, 0.0, 1.0]
+        );
+
+        let influences = JointInfluences::from_spec(&root.children[1]).unwrap();
+        assert_eq!(influences.element_size, 2);
+        assert_eq!(influences.geom_bind_transform[3][2], 1.0);
+        let point: Vec<_> = influences.point_influences(1).collect();
+        assert_eq!(point, vec![(1, 0.5), (2, 0.5)]);
+
+        let mut bindings = HashMap::new();
+        bindings.insert("/Character".to_string(), skeleton_binding(&root).unwrap());
+        assert_eq!(
+            inherited_binding(&bindings, "/Character/Body").as_deref(),
+            Some("/Character/Rig")
+        );
+        assert_eq!(inherited_binding(&bindings, "/Other/Body"), None);
+    }
+
+    #[test]
+    fn test_sample_animation_and_skin_mesh() {
+        let spec = parse_prim(
+            r#"def SkelAnimation "Wave"
+{
+    uniform token[] joints = ["Root/Arm"]
+    quatf[] rotations.timeSamples = {
+        0: [(1, 0, 0, 0)],
+        10: [(0.70710677, 0, 0, 0.70710677)],
+    }
+    half3[] scales = [(1, 1, 1)]
+    float3[] translations = [(1, 0, 0)]
+}"#,
+            false,
+        )
+        .unwrap();
+        let animation = SkelExtractor::extract_animation(&spec);
+        assert_eq!(animation.rotations.len(), 2);
+        assert_eq!(animation.time_range(), Some((0.0, 10.0)));
+
+        let mut skeleton = Skeleton::new("Rig");
+        skeleton.joints = vec!["Root".into(), "Root/Arm".into()];
+        skeleton.parent_indices = parent_indices(&skeleton.joints);
+        skeleton.rest_transforms = vec![xform::identity(), xform::translation([1.0, 0.0, 0.0])];
+        skeleton.bind_transforms = skeleton.rest_skeleton_transforms();
+
+        // Halfway through, the arm is rotated 45 degrees about Z.
+        let pose = animation.sample_skeleton(&skeleton, 5.0);
+        let tip = xform::transform_point(&pose[1], [1.0, 0.0, 0.0]);
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        assert!((tip[0] - (1.0 + h)).abs() < 1e-6 && (tip[1] - h).abs() < 1e-6);
+
+        let mut mesh = Mesh::new("Arm");
+        mesh.points = vec![[1.0, 0.0, 0.0], [2.0, 0.0, 0.0]];
+        mesh.joint_influences = Some(JointInfluences {
+            indices: vec![0, 1],
+            weights: vec![1.0, 1.0],
+            element_size: 1,
+            rigid: false,
+            geom_bind_transform: xform::identity(),
+            joints: None,
+        });
+
+        let pose = animation.sample_skeleton(&skeleton, 10.0);
+        let skinned = skin_mesh(&mesh, &skeleton, &pose).unwrap();
+        assert_eq!(skinned.points[0], [1.0, 0.0, 0.0]);
+        assert!((skinned.points[1][0] - 1.0).abs() < 1e-6);
+        assert!((skinned.points[1][1] - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_blend_shape_inbetweens() {
+        let spec = parse_prim(
+            r#"def BlendShape "Smile"
+{
+    uniform vector3f[] inbetweens:Half = [(0, 2, 0)] (
+        weight = 0.5
+    )
+    uniform vector3f[] offsets = [(0, 1, 0)]
+    uniform int[] pointIndices = [1]
+}"#,
+            false,
+        )
+        .unwrap();
+        let shape = BlendShape::from_spec(&spec, "smile", "/Face/Smile");
+        assert_eq!(shape.inbetweens.len(), 1);
+        assert_eq!(shape.inbetweens[0].weight, 0.5);
+
+        let mut mesh = Mesh::new("Face");
+        mesh.points = vec![[0.0; 3], [1.0, 0.0, 0.0]];
+        mesh.blend_shapes = vec![shape];
+
+        // Rises to the inbetween at 0.5, then falls back to the full shape.
+        let at = |weight: f32| mesh.apply_blend_shapes(&[weight]).unwrap().points[1][1];
+        assert_eq!(at(0.25), 1.0);
+        assert_eq!(at(0.5), 2.0);
+        assert_eq!(at(0.75), 1.5);
+        assert_eq!(at(1.0), 1.0);
+        assert_eq!(at(1.5), 0.0);
+        assert_eq!(mesh.apply_blend_shapes(&[1.0]).unwrap().points[0], [0.0; 3]);
+    }
+
+    #[test]
+    fn test_blend_shape_lengths() {
+        let spec = parse_prim(
+            r#"def BlendShape "Frown"
+{
+    uniform vector3f[] inbetweens:Short = [(0, 2, 0)]
+    uniform vector3f[] offsets = [(0, 1, 0), (0, -1, 0)]
+}"#,
+            false,
+        )
+        .unwrap();
+        // Mismatched arrays are kept for validate to report.
+        let shape = BlendShape::from_spec(&spec, "frown", "/Face/Frown");
+        assert_eq!(shape.inbetweens[0].offsets.len(), 1);
+        let message = |shape: &BlendShape| match shape.validate() {
+            Err(Error::InvalidMesh(message)) => message,
+            other => panic!("unexpected result {:?}", other),
+        };
+        assert_eq!(
+            message(&shape),
+            "blend shape /Face/Frown: inbetweens:Short has 1 entries but offsets has 2"
+        );
+
+        let spec = parse_prim(
+            r#"def BlendShape "Frown"
+{
+    uniform vector3f[] offsets = [(0, 1, 0)]
+    uniform int[] pointIndices = [0, 1]
+}"#,
+            false,
+        )
+        .unwrap();
+        let shape = BlendShape::from_spec(&spec, "frown", "/Face/Frown");
+        assert_eq!(
+            message(&shape),
+            "blend shape /Face/Frown: pointIndices has 2 entries but offsets has 1"
+        );
+
+        let mut mesh = Mesh::new("Face");
+        mesh.points = vec![[0.0; 3], [1.0, 0.0, 0.0]];
+        mesh.normals = Some(vec![[0.0, 0.0, 1.0]; 2]);
+        mesh.normals_interpolation = Interpolation::Vertex;
+        mesh.blend_shapes = vec![BlendShape {
+            name: "frown".into(),
+            path: "/Face/Frown".into(),
+            offsets: vec![[0.0, 1.0, 0.0]; 2],
+            normal_offsets: Some(vec![[1.0, 0.0, 0.0]]),
+            point_indices: None,
+            inbetweens: Vec::new(),
+        }];
+        assert!(matches!(
+            mesh.apply_blend_shapes(&[1.0]),
+            Err(Error::InvalidMesh(_))
+        ));
+    }
+}
@@ -1,25 +1,196 @@
 //! USD Stage operations.
 
+use std::collections::{HashMap, HashSet};
 use std::ffi::{CStr, CString};
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
 
+use crate::bounds::BoundingBox;
 use crate::error::{Error, Result};
-use crate::prim::Prim;
+use crate::instancer::PointInstancer;
+use crate::mesh::matrix_multiply;
+use crate::prim::{self, Prim, PrimType, PrototypeId, Purpose, Specifier, Visibility};
+use crate::query::{PathPattern, PrimQuery};
+use crate::traversal::{Traversal, TraversalOptions};
+use crate::usda::{self, find_meta, LayerSpec, Node, PrimSpec};
+use crate::value::Value;
+use crate::xform;
 
 // Wrapper for raw pointer that implements Send (we ensure safety through mutex)
 #[derive(Clone, Copy)]
 struct PrimPtr(*const tinyusdz_sys::CTinyUSDPrim);
 unsafe impl Send for PrimPtr {}
+// Safety: prims are owned by their stage and never mutated through the pointer
+unsafe impl Sync for PrimPtr {}
 
 // Global storage for collected prims during traversal (needed because C callback has no userdata)
 static TRAVERSAL_PRIMS: Mutex<Vec<PrimPtr>> = Mutex::new(Vec::new());
+static TRAVERSAL_LOCK: Mutex<()> = Mutex::new(());
+
+/// Options for loading a stage.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::{LoadOptions, Stage};
+///
+/// let options = LoadOptions::new().variant_selection("/Car", "trim", "sport");
+/// let stage = Stage::open_with_options("car.usda", options).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct LoadOptions {
+    variant_selections: Vec<(String, String, String)>,
+}
+
+impl LoadOptions {
+    /// Creates the default load options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Selects `variant` in the variant set `set` of the prim at `path`,
+    /// overriding the selection authored in the file.
+    pub fn variant_selection(
+        mut self,
+        path: impl Into<String>,
+        set: impl Into<String>,
+        variant: impl Into<String>,
+    ) -> Self {
+        let (path, set) = (path.into(), set.into());
+        self.variant_selections
+            .retain(|(p, s, _)| *p != path || *s != set);
+        self.variant_selections.push((path, set, variant.into()));
+        self
+    }
+
+    /// Returns the selection override for a variant set of the prim at
+    /// `path`.
+    pub fn selected_variant(&self, path: &str, set: &str) -> Option<&str> {
+        self.variant_selections
+            .iter()
+            .find(|(p, s, _)| p == path && s == set)
+            .map(|(_, _, v)| v.as_str())
+    }
+}
+
+/// A coordinate axis, used for the stage up axis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Axis {
+    /// The X axis.
+    X,
+    /// The Y axis (USD default).
+    #[default]
+    Y,
+    /// The Z axis.
+    Z,
+}
+
+impl Axis {
+    /// Parses a USD axis token (e.g. "Z").
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token {
+            "X" => Some(Axis::X),
+            "Y" => Some(Axis::Y),
+            "Z" => Some(Axis::Z),
+            _ => None,
+        }
+    }
+
+    /// Converts an axis from the tinyusdz C API.
+    pub fn from_sys(axis: tinyusdz_sys::CTinyUSDAxis) -> Option<Self> {
+        match axis {
+            tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_X => Some(Axis::X),
+            tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Y => Some(Axis::Y),
+            tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Z => Some(Axis::Z),
+            _ => None,
+        }
+    }
+}
+
+impl From<Axis> for tinyusdz_sys::CTinyUSDAxis {
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::X => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_X,
+            Axis::Y => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Y,
+            Axis::Z => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Z,
+        }
+    }
+}
 
 /// A USD Stage represents the root of a USD scene graph.
 ///
 /// The stage is the main entry point for loading and traversing USD files.
 pub struct Stage {
     pub(crate) inner: *mut tinyusdz_sys::CTinyUSDStage,
+    options: LoadOptions,
+    cache: OnceLock<StageCache>,
+}
+
+/// The stage's USDA representation, parsed on first use.
+///
+/// Prims read their specs from here instead of printing and parsing
+/// themselves on every access.
+struct StageCache {
+    /// The root layer with selected variants composed in, or the parse
+    /// error.
+    layer: std::result::Result<LayerSpec, String>,
+    /// The C prim at each prim path.
+    prims: HashMap<String, PrimPtr>,
+    /// The resolved schema type of each type name in the layer.
+    prim_types: HashMap<String, Option<PrimType>>,
+}
+
+impl StageCache {
+    fn new(stage: &Stage) -> Self {
+        let layer = stage
+            .to_string()
+            .map_err(|e| e.to_string())
+            .and_then(|text| usda::parse_layer(&text))
+            .map(|mut layer| {
+                layer.prims = std::mem::take(&mut layer.prims)
+                    .into_iter()
+                    .map(|spec| {
+                        let path = format!("/{}", spec.name);
+                        compose_variants(spec, &path, &stage.options)
+                    })
+                    .collect();
+                layer
+            });
+
+        let mut prims = HashMap::new();
+        let mut stack: Vec<(String, PrimPtr)> = c_root_prims(stage)
+            .into_iter()
+            .map(|ptr| {
+                let prim = Prim::new(stage, None, String::new(), ptr.0);
+                (format!("/{}", prim.name()), ptr)
+            })
+            .collect();
+        while let Some((path, ptr)) = stack.pop() {
+            let prim = Prim::new(stage, None, path.clone(), ptr.0);
+            for child in prim.children() {
+                stack.push((format!("{}/{}", path, child.name()), PrimPtr(child.inner)));
+            }
+            prims.insert(path, ptr);
+        }
+
+        let mut prim_types = HashMap::new();
+        if let Ok(layer) = &layer {
+            let mut specs: Vec<&PrimSpec> = layer.prims.iter().collect();
+            while let Some(spec) = specs.pop() {
+                if !prim_types.contains_key(&spec.type_name) {
+                    let prim_type = prim::resolve_prim_type(&spec.type_name);
+                    prim_types.insert(spec.type_name.clone(), prim_type);
+                }
+                specs.extend(&spec.children);
+            }
+        }
+
+        StageCache {
+            layer,
+            prims,
+            prim_types,
+        }
+    }
 }
 
 // Safety: Stage owns its inner pointer and manages its lifetime
@@ -33,7 +204,11 @@ impl Stage {
         if inner.is_null() {
             return Err(Error::NullPointer);
         }
-        Ok(Stage { inner })
+        Ok(Stage {
+            inner,
+            options: LoadOptions::default(),
+            cache: OnceLock::new(),
+        })
     }
 
     /// Opens a USD file from the filesystem.
@@ -53,13 +228,22 @@ impl Stage {
     /// }
     /// ```
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::open_with_options(path, LoadOptions::default())
+    }
+
+    /// Opens a USD file from the filesystem with the given load options.
+    ///
+    /// Variant selections in `options` are applied when prims are read, so
+    /// prims, traversals and extractors all see the selected variants.
+    pub fn open_with_options<P: AsRef<Path>>(path: P, options: LoadOptions) -> Result<Self> {
         let path_str = path
             .as_ref()
             .to_str()
             .ok_or_else(|| Error::InvalidPath("Path is not valid UTF-8".into()))?;
         let c_path = CString::new(path_str)?;
 
-        let stage = Self::new()?;
+        let mut stage = Self::new()?;
+        stage.options = options;
 
         let warn = unsafe { tinyusdz_sys::c_tinyusd_string_new_empty() };
         let err = unsafe { tinyusdz_sys::c_tinyusd_string_new_empty() };
@@ -104,8 +288,14 @@ impl Stage {
     /// Note: This writes to a temporary file and loads from there,
     /// as the C API memory loading functions don't populate a stage directly.
     pub fn from_usda(data: &[u8]) -> Result<Self> {
+        Self::from_usda_with_options(data, LoadOptions::default())
+    }
+
+    /// Loads a USD stage from USDA (ASCII) data in memory with the given load
+    /// options.
+    pub fn from_usda_with_options(data: &[u8], options: LoadOptions) -> Result<Self> {
         let temp_file = tempfile_for_data(data, ".usda")?;
-        Self::open(temp_file.path())
+        Self::open_with_options(temp_file.path(), options)
     }
 
     /// Loads a USD stage from USDC (binary Crate) data in memory.
@@ -113,8 +303,14 @@ impl Stage {
     /// Note: This writes to a temporary file and loads from there,
     /// as the C API memory loading functions don't populate a stage directly.
     pub fn from_usdc(data: &[u8]) -> Result<Self> {
+        Self::from_usdc_with_options(data, LoadOptions::default())
+    }
+
+    /// Loads a USD stage from USDC (binary Crate) data in memory with the given load
+    /// options.
+    pub fn from_usdc_with_options(data: &[u8], options: LoadOptions) -> Result<Self> {
         let temp_file = tempfile_for_data(data, ".usdc")?;
-        Self::open(temp_file.path())
+        Self::open_with_options(temp_file.path(), options)
     }
 
     /// Loads a USD stage from USDZ (ZIP archive) data in memory.
@@ -122,8 +318,14 @@ impl Stage {
     /// Note: This writes to a temporary file and loads from there,
     /// as the C API memory loading functions don't populate a stage directly.
     pub fn from_usdz(data: &[u8]) -> Result<Self> {
+        Self::from_usdz_with_options(data, LoadOptions::default())
+    }
+
+    /// Loads a USD stage from USDZ (ZIP archive) data in memory with the given load
+    /// options.
+    pub fn from_usdz_with_options(data: &[u8], options: LoadOptions) -> Result<Self> {
         let temp_file = tempfile_for_data(data, ".usdz")?;
-        Self::open(temp_file.path())
+        Self::open_with_options(temp_file.path(), options)
     }
 
     /// Returns an iterator over all prims in the stage using depth-first traversal.
@@ -131,6 +333,393 @@ impl Stage {
         StageTraversal::new(self)
     }
 
+    /// Returns a filtered depth-first cursor over the prims of the stage.
+    ///
+    /// Unlike [`traverse`](Self::traverse), the cursor reports the depth and
+    /// path of each prim and can skip the children of the current prim.
+    pub fn traverse_with(&self, options: TraversalOptions) -> Traversal<'_> {
+        Traversal::new(self, options)
+    }
+
+    /// Returns the prims whose paths match an SdfPath-style glob (e.g.
+    /// "/World/**/Wheel*"), in depth-first order.
+    ///
+    /// See [`PathPattern`] for the syntax. The result can be narrowed by
+    /// type and by authored properties.
+    pub fn find(&self, pattern: &str) -> Result<PrimQuery<'_>> {
+        Ok(PrimQuery::new(self, PathPattern::new(pattern)?))
+    }
+
+    /// Returns the root prims of the stage (the direct children of the
+    /// pseudo-root), in stage order.
+    pub fn root_prims(&self) -> Vec<Prim<'_>> {
+        match &self.cache().layer {
+            Ok(layer) => layer
+                .prims
+                .iter()
+                .map(|spec| self.prim(spec, format!("/{}", spec.name)))
+                .collect(),
+            // Without a parsed layer, prims are read from the C API alone.
+            Err(_) => c_root_prims(self)
+                .into_iter()
+                .map(|ptr| {
+                    let prim = Prim::new(self, None, String::new(), ptr.0);
+                    let path = format!("/{}", prim.name());
+                    Prim::new(self, None, path, ptr.0)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the prim at an absolute prim path such as `/World/Mesh`, or
+    /// `None` if there is no prim at `path`.
+    pub fn prim_at_path(&self, path: &str) -> Option<Prim<'_>> {
+        let mut names = path.strip_prefix('/')?.split('/');
+        let root = names.next()?;
+        let mut prim = self.root_prims().into_iter().find(|p| p.name() == root)?;
+        for name in names {
+            prim = prim.children().find(|c| c.name() == name)?;
+        }
+        Some(prim)
+    }
+
+    /// Creates the prim for a spec of the parsed layer.
+    pub(crate) fn prim<'a>(&'a self, spec: &'a PrimSpec, path: String) -> Prim<'a> {
+        let inner = self
+            .cache()
+            .prims
+            .get(&path)
+            .map_or(std::ptr::null(), |ptr| ptr.0);
+        Prim::new(self, Some(spec), path, inner)
+    }
+
+    /// Returns the schema type for a type name, resolved once per stage.
+    pub(crate) fn prim_type(&self, type_name: &str) -> Option<PrimType> {
+        match self.cache().prim_types.get(type_name) {
+            Some(prim_type) => *prim_type,
+            None => prim::resolve_prim_type(type_name),
+        }
+    }
+
+    fn cache(&self) -> &StageCache {
+        self.cache.get_or_init(|| StageCache::new(self))
+    }
+
+    /// Returns the options the stage was loaded with.
+    pub fn load_options(&self) -> &LoadOptions {
+        &self.options
+    }
+
+    /// Returns the asset paths of the root layer's sublayers, strongest
+    /// first.
+    pub fn sublayers(&self) -> Result<Vec<String>> {
+        Ok(self.layer_spec()?.sublayers())
+    }
+
+    /// Returns the up axis (`upAxis`), or Y when not authored.
+    pub fn up_axis(&self) -> Result<Axis> {
+        Ok(self
+            .layer_metadata("upAxis")?
+            .as_ref()
+            .and_then(Node::as_str)
+            .map(sys_axis)
+            .and_then(Axis::from_sys)
+            .unwrap_or_default())
+    }
+
+    /// Returns the length of a scene unit in meters (`metersPerUnit`), or
+    /// 0.01 when not authored.
+    pub fn meters_per_unit(&self) -> Result<f64> {
+        Ok(self.layer_number("metersPerUnit")?.unwrap_or(0.01))
+    }
+
+    /// Returns the name of the default prim (`defaultPrim`), if authored.
+    pub fn default_prim(&self) -> Result<Option<String>> {
+        Ok(self
+            .layer_metadata("defaultPrim")?
+            .as_ref()
+            .and_then(Node::as_str)
+            .map(String::from))
+    }
+
+    /// Returns the layer documentation (`doc`), if authored.
+    pub fn documentation(&self) -> Result<Option<String>> {
+        Ok(self
+            .layer_metadata("doc")?
+            .as_ref()
+            .and_then(Node::as_str)
+            .map(String::from))
+    }
+
+    /// Returns the entries of `customLayerData`, in authored order.
+    pub fn custom_layer_data(&self) -> Result<Vec<(String, Value)>> {
+        Ok(match self.layer_metadata("customLayerData")? {
+            Some(node) => match node.to_untyped_value() {
+                Value::Dictionary(entries) => entries,
+                _ => Vec::new(),
+            },
+            None => Vec::new(),
+        })
+    }
+
+    /// Returns the authored `startTimeCode` and `endTimeCode`, if both are
+    /// authored.
+    pub fn time_code_range(&self) -> Result<Option<(f64, f64)>> {
+        let layer = self.layer_spec()?;
+        let number = |key| find_meta(&layer.metadata, key).and_then(Node::as_f64);
+        Ok(number("startTimeCode").zip(number("endTimeCode")))
+    }
+
+    /// Returns the number of time codes per second (`timeCodesPerSecond`),
+    /// falling back to `framesPerSecond` and then 24.
+    pub fn time_codes_per_second(&self) -> Result<f64> {
+        let layer = self.layer_spec()?;
+        let number = |key| find_meta(&layer.metadata, key).and_then(Node::as_f64);
+        Ok(number("timeCodesPerSecond")
+            .or_else(|| number("framesPerSecond"))
+            .unwrap_or(24.0))
+    }
+
+    /// Returns the playback rate (`framesPerSecond`), or 24 when not
+    /// authored.
+    pub fn frames_per_second(&self) -> Result<f64> {
+        Ok(self.layer_number("framesPerSecond")?.unwrap_or(24.0))
+    }
+
+    /// Returns a root layer metadata entry.
+    fn layer_metadata(&self, key: &str) -> Result<Option<Node>> {
+        Ok(find_meta(&self.layer_spec()?.metadata, key).cloned())
+    }
+
+    /// Returns a numeric root layer metadata entry.
+    fn layer_number(&self, key: &str) -> Result<Option<f64>> {
+        Ok(self.layer_metadata(key)?.as_ref().and_then(Node::as_f64))
+    }
+
+    /// Returns the stage's parsed USDA representation.
+    fn layer_spec(&self) -> Result<&LayerSpec> {
+        self.cache()
+            .layer
+            .as_ref()
+            .map_err(|e| Error::ParseError(e.clone()))
+    }
+
+    /// Returns the prototypes shared by native instances on the stage, in
+    /// stage order.
+    ///
+    /// See [`PrototypeId`] for how prototypes are identified.
+    pub fn prototypes(&self) -> Vec<PrototypeId> {
+        let mut prototypes = Vec::new();
+        self.walk((), |visit, _| {
+            if let Some(prototype) = crate::prim::prototype(visit.spec) {
+                if !prototypes.contains(&prototype) {
+                    prototypes.push(prototype);
+                }
+            }
+            Some(())
+        });
+        prototypes
+    }
+
+    /// Computes the visibility of the prim at `path`, which is invisible if
+    /// the prim or any of its ancestors is.
+    ///
+    /// Returns `None` if there is no prim at `path`.
+    pub fn compute_visibility(&self, path: &str) -> Option<Visibility> {
+        let mut result = None;
+        self.walk(Visibility::Inherited, |visit, parent| {
+            if !is_within(path, &visit.path) {
+                return None;
+            }
+            let visibility = match parent {
+                Visibility::Invisible => Visibility::Invisible,
+                Visibility::Inherited => prim::visibility(visit.spec),
+            };
+            if visit.path == path {
+                result = Some(visibility);
+                return None;
+            }
+            Some(visibility)
+        });
+        result
+    }
+
+    /// Computes the purpose of the prim at `path`, inheriting the nearest
+    /// non-default purpose of its ancestors.
+    ///
+    /// Returns `None` if there is no prim at `path`.
+    pub fn compute_purpose(&self, path: &str) -> Option<Purpose> {
+        let mut result = None;
+        self.walk(Purpose::Default, |visit, parent| {
+            if !is_within(path, &visit.path) {
+                return None;
+            }
+            let purpose = match parent {
+                Purpose::Default => prim::purpose(visit.spec),
+                inherited => *inherited,
+            };
+            if visit.path == path {
+                result = Some(purpose);
+                return None;
+            }
+            Some(purpose)
+        });
+        result
+    }
+
+    /// Computes the world-space bounding box of all geometry on the stage.
+    ///
+    /// Only prims whose computed purpose is listed in `purposes` contribute.
+    /// Inactive and invisible prims are skipped along with their
+    /// descendants, as are undefined (`over` and abstract `class`) prims.
+    /// Authored `extent` attributes are used when present, otherwise bounds
+    /// are computed from `points`.
+    ///
+    /// Point instancer prototypes contribute once per visible instance,
+    /// placed at the earliest authored time, instead of at their own
+    /// location.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use tinyusdz_rs::{Purpose, Stage};
+    ///
+    /// let stage = Stage::open("model.usdz").unwrap();
+    /// let bounds = stage.compute_world_bounds(&[Purpose::Default, Purpose::Render]);
+    /// println!("size: {:?}", bounds.size());
+    /// ```
+    pub fn compute_world_bounds(&self, purposes: &[Purpose]) -> BoundingBox {
+        // Local bounds and world transform of each contributing prim, and
+        // whether the prim is defined. Undefined prims may still be used as
+        // instancer prototypes.
+        let mut prims: Vec<(String, BoundingBox, [[f64; 4]; 4], bool)> = Vec::new();
+        let mut worlds = HashMap::new();
+        let mut instancers = Vec::new();
+
+        self.walk(
+            (xform::identity(), Purpose::Default, true),
+            |visit, (parent_world, parent_purpose, parent_defined)| {
+                let spec = visit.spec;
+                if prim::bool_meta(spec, "active") == Some(false)
+                    || prim::visibility(spec) == Visibility::Invisible
+                {
+                    return None;
+                }
+                let defined = *parent_defined
+                    && Specifier::from_token(&spec.specifier).unwrap_or_default() == Specifier::Def;
+
+                // A non-default purpose is inherited by the whole subtree.
+                let purpose = if *parent_purpose != Purpose::Default {
+                    *parent_purpose
+                } else {
+                    prim::purpose(spec)
+                };
+
+                let (local, resets) = xform::local_transform(spec);
+                let world = if resets {
+                    local
+                } else {
+                    matrix_multiply(local, *parent_world)
+                };
+                worlds.insert(visit.path.clone(), world);
+
+                if defined && spec.type_name == "PointInstancer" {
+                    let mut instancer = PointInstancer::from_spec(spec, &visit.path);
+                    instancer.world_transform = world;
+                    instancers.push(instancer);
+                }
+                if purposes.contains(&purpose) {
+                    if let Some(local_bounds) = local_bounds(spec) {
+                        prims.push((visit.path.clone(), local_bounds, world, defined));
+                    }
+                }
+
+                Some((world, purpose, defined))
+            },
+        );
+
+        let mut bounds = BoundingBox::empty();
+        let is_prototype = |path: &str| {
+            instancers
+                .iter()
+                .flat_map(|instancer| &instancer.prototypes)
+                .any(|prototype| is_within(path, prototype))
+        };
+        for (path, local_bounds, world, defined) in &prims {
+            if *defined && !is_prototype(path) {
+                bounds.union(&local_bounds.transformed(world));
+            }
+        }
+
+        for instancer in &instancers {
+            let transforms = instancer.instance_transforms(f64::NEG_INFINITY);
+            for (i, transform) in transforms.iter().enumerate() {
+                let Some(prototype) = instancer.prototype_of(i) else {
+                    continue;
+                };
+                if !instancer.is_visible(i) {
+                    continue;
+                }
+
+                // Prototypes are placed relative to their parent, so the
+                // prototype prim's own transform is kept.
+                let parent = prototype
+                    .rfind('/')
+                    .and_then(|p| worlds.get(&prototype[..p]))
+                    .copied()
+                    .unwrap_or_else(xform::identity);
+                let to_instance = matrix_multiply(
+                    xform::invert(&parent).unwrap_or_else(xform::identity),
+                    matrix_multiply(*transform, instancer.world_transform),
+                );
+                for (path, local_bounds, world, _) in &prims {
+                    if is_within(path, prototype) {
+                        let m = matrix_multiply(*world, to_instance);
+                        bounds.union(&local_bounds.transformed(&m));
+                    }
+                }
+            }
+        }
+
+        bounds
+    }
+
+    /// Visits every prim depth-first, starting from the root prims.
+    ///
+    /// Selected variants are composed into each spec before it is visited.
+    /// `visit` receives the prim's parsed spec and path along with the state
+    /// returned for its parent, and returns the state to pass to the prim's
+    /// children, or `None` to skip them.
+    pub(crate) fn walk<S>(
+        &self,
+        root_state: S,
+        mut visit: impl for<'s> FnMut(&Visit<'s>, &S) -> Option<S>,
+    ) {
+        let Ok(layer) = &self.cache().layer else {
+            return;
+        };
+        for spec in &layer.prims {
+            let path = format!("/{}", spec.name);
+            walk_prim(spec, path, &root_state, &mut visit);
+        }
+    }
+
+    /// Visits every prim with its local and world transforms.
+    pub(crate) fn walk_transforms(
+        &self,
+        mut visit: impl for<'s> FnMut(&Visit<'s>, &[[f64; 4]; 4], &[[f64; 4]; 4]),
+    ) {
+        self.walk(xform::identity(), |item, parent_world| {
+            let (local, resets) = xform::local_transform(item.spec);
+            let world = if resets {
+                local
+            } else {
+                matrix_multiply(local, *parent_world)
+            };
+            visit(item, &local, &world);
+            Some(world)
+        });
+    }
+
     /// Returns the stage as a USDA string.
     pub fn to_string(&self) -> Result<String> {
         unsafe {
@@ -174,38 +763,102 @@ impl Drop for Stage {
     }
 }
 
-/// Iterator over prims in a stage.
-pub struct StageTraversal<'a> {
-    #[allow(dead_code)]
-    stage: &'a Stage,
-    prims: Vec<Prim<'a>>,
-    index: usize,
+/// Returns the local bounds of a prim: its authored `extent`, or the
+/// bounds of its `points`.
+fn local_bounds(spec: &PrimSpec) -> Option<BoundingBox> {
+    match spec.value("extent") {
+        Some(Value::Float3Array(extent)) if extent.len() == 2 => {
+            Some(BoundingBox::from_points(&extent))
+        }
+        _ => match spec.value("points") {
+            Some(Value::Float3Array(points)) => Some(BoundingBox::from_points(&points)),
+            _ => None,
+        },
+    }
 }
 
-impl<'a> StageTraversal<'a> {
-    fn new(stage: &'a Stage) -> Self {
-        // Clear previous traversal data
-        {
-            let mut guard = TRAVERSAL_PRIMS.lock().unwrap();
-            guard.clear();
-        }
+/// Returns true if `path` is `root` or one of its descendants.
+pub(crate) fn is_within(path: &str, root: &str) -> bool {
+    path.strip_prefix(root)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
 
-        // Callback that collects prims into global storage
-        unsafe extern "C" fn collect_prim(
-            prim: *const tinyusdz_sys::CTinyUSDPrim,
-            _path: *const tinyusdz_sys::CTinyUSDPath,
-        ) -> i32 {
-            if prim.is_null() {
-                return 1; // continue
-            }
+/// A prim visited by [`Stage::walk`].
+pub(crate) struct Visit<'s> {
+    pub spec: &'s PrimSpec,
+    pub path: String,
+}
 
-            if let Ok(mut guard) = TRAVERSAL_PRIMS.lock() {
-                guard.push(PrimPtr(prim));
-            }
-            1 // continue traversal
+/// Visits a prim and its subtree.
+fn walk_prim<S>(
+    spec: &PrimSpec,
+    path: String,
+    state: &S,
+    visit: &mut impl for<'s> FnMut(&Visit<'s>, &S) -> Option<S>,
+) {
+    let item = Visit { spec, path };
+    let Some(child_state) = visit(&item, state) else {
+        return;
+    };
+    for child_spec in &spec.children {
+        let child_path = format!("{}/{}", item.path, child_spec.name);
+        walk_prim(child_spec, child_path, &child_state, visit);
+    }
+}
+
+/// Composes the selected variants of a prim and its descendants, applying
+/// the selections of `options`.
+fn compose_variants(spec: PrimSpec, path: &str, options: &LoadOptions) -> PrimSpec {
+    let mut spec = if spec.variant_sets.is_empty() {
+        spec
+    } else {
+        spec.with_variants(|set| options.selected_variant(path, set).map(String::from))
+    };
+    spec.children = std::mem::take(&mut spec.children)
+        .into_iter()
+        .map(|child| {
+            let child_path = format!("{}/{}", path, child.name);
+            compose_variants(child, &child_path, options)
+        })
+        .collect();
+    spec
+}
+
+/// Converts an `upAxis` token to the C API's axis.
+///
+/// The C API has no getter for stage metadata, so the token is read from the
+/// stage's USDA.
+fn sys_axis(token: &str) -> tinyusdz_sys::CTinyUSDAxis {
+    match token {
+        "X" => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_X,
+        "Y" => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Y,
+        "Z" => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_Z,
+        _ => tinyusdz_sys::CTinyUSDAxis::C_TINYUSD_AXIS_UNKNOWN,
+    }
+}
+
+/// Returns the root prims of the stage as reported by the C API.
+fn c_root_prims(stage: &Stage) -> Vec<PrimPtr> {
+    // Callback that collects prims into global storage
+    unsafe extern "C" fn collect_prim(
+        prim: *const tinyusdz_sys::CTinyUSDPrim,
+        _path: *const tinyusdz_sys::CTinyUSDPath,
+    ) -> i32 {
+        if prim.is_null() {
+            return 1; // continue
         }
 
-        // Perform traversal
+        if let Ok(mut guard) = TRAVERSAL_PRIMS.lock() {
+            guard.push(PrimPtr(prim));
+        }
+        1 // continue traversal
+    }
+
+    // Traversals share the global storage, so they run one at a time
+    let prims = {
+        let _guard = TRAVERSAL_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        TRAVERSAL_PRIMS.lock().unwrap().clear();
+
         unsafe {
             let err = tinyusdz_sys::c_tinyusd_string_new_empty();
 
@@ -216,20 +869,30 @@ impl<'a> StageTraversal<'a> {
             }
         }
 
-        // Collect results
-        let prims: Vec<Prim<'a>> = {
-            let guard = TRAVERSAL_PRIMS.lock().unwrap();
-            guard
-                .iter()
-                .map(|ptr| unsafe { Prim::from_ptr(ptr.0) })
-                .collect()
-        };
+        std::mem::take(&mut *TRAVERSAL_PRIMS.lock().unwrap())
+    };
 
-        StageTraversal {
-            stage,
-            prims,
-            index: 0,
-        }
+    let children: HashSet<*const tinyusdz_sys::CTinyUSDPrim> = prims
+        .iter()
+        .flat_map(|ptr| Prim::new(stage, None, String::new(), ptr.0).children())
+        .map(|child| child.inner)
+        .collect();
+    prims
+        .into_iter()
+        .filter(|ptr| !children.contains(&ptr.0))
+        .collect()
+}
+
+/// Iterator over prims in a stage.
+pub struct StageTraversal<'a> {
+    stack: Vec<Prim<'a>>,
+}
+
+impl<'a> StageTraversal<'a> {
+    fn new(stage: &'a Stage) -> Self {
+        let mut stack = stage.root_prims();
+        stack.reverse();
+        StageTraversal { stack }
     }
 }
 
@@ -237,13 +900,11 @@ impl<'a> Iterator for StageTraversal<'a> {
     type Item = Prim<'a>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.index < self.prims.len() {
-            let prim = self.prims[self.index].clone();
-            self.index += 1;
-            Some(prim)
-        } else {
-            None
-        }
+        let prim = self.stack.pop()?;
+        let first_child = self.stack.len();
+        self.stack.extend(prim.children());
+        self.stack[first_child..].reverse();
+        Some(prim)
     }
 }
 
@@ -258,11 +919,24 @@ impl TempFile {
     }
 }
 
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
 fn tempfile_for_data(data: &[u8], extension: &str) -> Result<TempFile> {
     use std::io::Write;
 
+    // Stages may be loaded from several threads at once
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
     let temp_dir = std::env::temp_dir();
-    let filename = format!("tinyusdz_rs_{}{}", std::process::id(), extension);
+    let filename = format!(
+        "tinyusdz_rs_{}_{}{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+        extension
+    );
     let path = temp_dir.join(filename);
 
     let mut file = std::fs::File::create(&path)?;
@@ -271,3 +945,291 @@ fn tempfile_for_data(data: &[u8], extension: &str) -> Result<TempFile> {
 
     Ok(TempFile { path })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"#usda 1.0
+(
+    defaultPrim = "World"
+)
+
+def Xform "World"
+{
+    def Mesh "Body"
+    {
+        float radius = 2
+    }
+
+    def Scope "Looks"
+    {
+    }
+}
+
+def Xform "Other"
+{
+    def Mesh "Body"
+    {
+        float radius = 3
+    }
+}
+"#;
+
+    #[test]
+    fn test_prims_read_parsed_layer() {
+        let stage = Stage::from_usda(SCENE.as_bytes()).unwrap();
+        let paths: Vec<String> = stage.traverse().map(|p| p.path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/World",
+                "/World/Body",
+                "/World/Looks",
+                "/Other",
+                "/Other/Body"
+            ]
+        );
+
+        // Prims with the same name keep their own specs.
+        let radii: Vec<Value> = stage
+            .traverse()
+            .filter(|p| p.name() == "Body")
+            .filter_map(|p| p.attribute("radius"))
+            .map(|a| a.value())
+            .collect();
+        assert_eq!(radii, vec![Value::Float(2.0), Value::Float(3.0)]);
+
+        let world = &stage.root_prims()[0];
+        assert_eq!(world.num_children(), 2);
+        assert_eq!(world.child(1).unwrap().path(), "/World/Looks");
+        assert!(world.child(1).unwrap().spec().is_some());
+    }
+
+    #[test]
+    fn test_variant_selection_options() {
+        let car = br#"#usda 1.0
+
+def Xform "Car" (
+    variants = {
+        string trim = "base"
+    }
+    prepend variantSets = "trim"
+)
+{
+    variantSet "trim" = {
+        "base" {
+            float spoiler = 0
+        }
+        "sport" {
+            float spoiler = 1
+
+            def Mesh "Spoiler"
+            {
+            }
+        }
+    }
+}
+"#;
+        let stage = Stage::from_usda(car).unwrap();
+        let spoiler = |stage: &Stage| {
+            stage
+                .prim_at_path("/Car")
+                .and_then(|car| car.attribute("spoiler"))
+                .map(|a| a.value())
+        };
+        assert_eq!(spoiler(&stage), Some(Value::Float(0.0)));
+        assert_eq!(stage.traverse().count(), 1);
+
+        let options = LoadOptions::new().variant_selection("/Car", "trim", "sport");
+        let stage = Stage::from_usda_with_options(car, options).unwrap();
+        assert_eq!(spoiler(&stage), Some(Value::Float(1.0)));
+        let paths: Vec<String> = stage.traverse().map(|p| p.path()).collect();
+        assert_eq!(paths, vec!["/Car", "/Car/Spoiler"]);
+        assert_eq!(
+            stage.root_prims()[0].variant_sets()[0].selection.as_deref(),
+            Some("sport")
+        );
+    }
+
+    #[test]
+    fn test_layer_metadata_defaults() {
+        let stage = Stage::from_usda(b"#usda 1.0\n").unwrap();
+        assert_eq!(stage.up_axis().unwrap(), Axis::Y);
+        assert_eq!(stage.meters_per_unit().unwrap(), 0.01);
+        assert_eq!(stage.time_codes_per_second().unwrap(), 24.0);
+        assert_eq!(stage.frames_per_second().unwrap(), 24.0);
+        assert_eq!(stage.time_code_range().unwrap(), None);
+        assert_eq!(stage.default_prim().unwrap(), None);
+
+        // timeCodesPerSecond falls back to framesPerSecond.
+        let stage = Stage::from_usda(b"#usda 1.0\n(\n    framesPerSecond = 30\n)\n").unwrap();
+        assert_eq!(stage.time_codes_per_second().unwrap(), 30.0);
+    }
+
+    #[test]
+    fn test_layer_metadata_authored() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+(
+    defaultPrim = "World"
+    doc = "A test scene"
+    endTimeCode = 48
+    framesPerSecond = 30
+    metersPerUnit = 1
+    startTimeCode = 1
+    timeCodesPerSecond = 60
+    upAxis = "Z"
+    customLayerData = {
+        string author = "tests"
+    }
+)
+
+def Xform "World"
+{
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(stage.up_axis().unwrap(), Axis::Z);
+        assert_eq!(stage.meters_per_unit().unwrap(), 1.0);
+        assert_eq!(stage.time_codes_per_second().unwrap(), 60.0);
+        assert_eq!(stage.frames_per_second().unwrap(), 30.0);
+        assert_eq!(stage.time_code_range().unwrap(), Some((1.0, 48.0)));
+        assert_eq!(stage.default_prim().unwrap().as_deref(), Some("World"));
+        assert_eq!(
+            stage.documentation().unwrap().as_deref(),
+            Some("A test scene")
+        );
+        assert_eq!(
+            stage.custom_layer_data().unwrap(),
+            vec![("author".to_string(), Value::String("tests".into()))]
+        );
+    }
+
+    #[test]
+    fn test_compute_world_bounds() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def Xform "World"
+{
+    def Mesh "Extent"
+    {
+        float3[] extent = [(-1, -1, -1), (1, 1, 1)]
+        point3f[] points = [(-5, 0, 0), (5, 0, 0)]
+        double3 xformOp:translate = (10, 0, 0)
+        uniform token[] xformOpOrder = ["xformOp:translate"]
+    }
+
+    def Mesh "Points"
+    {
+        point3f[] points = [(0, 0, 0), (1, 2, 3)]
+    }
+
+    def Xform "Hidden"
+    {
+        token visibility = "invisible"
+
+        def Mesh "Big"
+        {
+            point3f[] points = [(-100, -100, -100), (100, 100, 100)]
+        }
+    }
+
+    def Xform "Guides"
+    {
+        uniform token purpose = "guide"
+
+        def Mesh "Helper"
+        {
+            point3f[] points = [(50, 0, 0)]
+        }
+    }
+
+    def Xform "Offset"
+    {
+        double3 xformOp:translate = (1000, 0, 0)
+        uniform token[] xformOpOrder = ["xformOp:translate"]
+
+        def Mesh "Reset"
+        {
+            point3f[] points = [(0, 0, 0)]
+            double3 xformOp:translate = (0, -5, 0)
+            uniform token[] xformOpOrder = ["!resetXformStack!", "xformOp:translate"]
+        }
+    }
+
+    def Mesh "Inactive" (
+        active = false
+    )
+    {
+        point3f[] points = [(-100, -100, -100), (100, 100, 100)]
+    }
+}
+
+class "Template"
+{
+    def Mesh "Big"
+    {
+        point3f[] points = [(-100, -100, -100), (100, 100, 100)]
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        // The extent wins over points, the reset mesh ignores its parent,
+        // and hidden, inactive and abstract prims are skipped.
+        let bounds = stage.compute_world_bounds(&[Purpose::Default]);
+        assert_eq!(
+            bounds,
+            BoundingBox::new([0.0, -5.0, -1.0], [11.0, 2.0, 3.0])
+        );
+
+        // The helper inherits its parent's guide purpose.
+        let bounds = stage.compute_world_bounds(&[Purpose::Default, Purpose::Guide]);
+        assert_eq!(bounds.max, [50.0, 2.0, 3.0]);
+        let bounds = stage.compute_world_bounds(&[Purpose::Guide]);
+        assert_eq!(bounds, BoundingBox::new([50.0, 0.0, 0.0], [50.0, 0.0, 0.0]));
+    }
+
+    #[test]
+    fn test_compute_world_bounds_instancer() {
+        let stage = Stage::from_usda(
+            br#"#usda 1.0
+
+def PointInstancer "Forest"
+{
+    int64[] invisibleIds = [2]
+    point3f[] positions = [(0, 0, 0), (10, 0, 0), (20, 0, 0)]
+    int[] protoIndices = [0, 0, 0]
+    rel prototypes = </Forest/Protos/Tree>
+    double3 xformOp:translate = (0, 0, 100)
+    uniform token[] xformOpOrder = ["xformOp:translate"]
+
+    def Scope "Protos"
+    {
+        def Mesh "Tree"
+        {
+            int[] faceVertexCounts = [3]
+            int[] faceVertexIndices = [0, 1, 2]
+            point3f[] points = [(0, 0, 0), (1, 0, 0), (1, 1, 1)]
+            double3 xformOp:translate = (0, 1, 0)
+            uniform token[] xformOpOrder = ["xformOp:translate"]
+        }
+    }
+}
+"#,
+        )
+        .unwrap();
+
+        // The prototype counts once per visible instance, not where it is
+        // authored.
+        let bounds = stage.compute_world_bounds(&[Purpose::Default]);
+        assert_eq!(
+            bounds,
+            BoundingBox::new([0.0, 1.0, 100.0], [11.0, 2.0, 101.0])
+        );
+    }
+}
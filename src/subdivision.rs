@@ -0,0 +1,731 @@
+//! Subdivision surface evaluation (Catmull-Clark and Loop).
+//!
+//! Each level is expressed as stencils: every refined point is a weighted
+//! sum of points of the previous level. The same stencils refine positions
+//! and `vertex`/`varying` primvars, so they stay consistent.
+//!
+//! Semi-sharp creases and corners follow DeRose et al., "Subdivision Surfaces
+//! in Character Animation" (1998): sharpness decreases by one per level and
+//! fractional sharpness blends between the smooth and sharp rules.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::mesh::{InterpolateBoundary, Interpolation, Mesh, NormalMode, SubdivisionScheme};
+
+/// USD treats sharpness values at or above this as infinitely sharp.
+const INFINITELY_SHARP: f32 = 10.0;
+
+/// A weighted combination of source points.
+type Stencil = Vec<(usize, f32)>;
+
+/// Stencils of the refined points, the refined faces and the
+/// `(parent face, child index)` of each refined face.
+type Refinement = (Vec<Stencil>, Vec<Vec<usize>>, Vec<(usize, usize)>);
+
+/// Subdivides `mesh` `levels` times according to its subdivision scheme.
+pub(crate) fn subdivide(mesh: &Mesh, levels: u32) -> Result<Mesh> {
+    let mut level = Level::from_mesh(mesh)?;
+
+    if mesh.subdivision_scheme == SubdivisionScheme::Loop
+        && level.faces.iter().any(|f| f.len() != 3)
+    {
+        return Err(Error::InvalidMesh(
+            "Loop subdivision requires a triangle mesh".into(),
+        ));
+    }
+
+    if mesh.subdivision_scheme != SubdivisionScheme::None {
+        for _ in 0..levels {
+            level = level.refine(mesh.subdivision_scheme, mesh.interpolate_boundary);
+        }
+    }
+
+    let mut result = level.into_mesh(mesh);
+    if mesh.has_normals() {
+        result.compute_normals(NormalMode::Smooth)?;
+    }
+    Ok(result)
+}
+
+/// A mesh being refined, with its primvars.
+struct Level {
+    points: Vec<[f32; 3]>,
+    faces: Vec<Vec<usize>>,
+    holes: Vec<bool>,
+    /// Face of the source mesh each face was refined from.
+    origins: Vec<usize>,
+    /// Sharpness of creased edges, keyed by sorted point pair.
+    creases: HashMap<(usize, usize), f32>,
+    corners: HashMap<usize, f32>,
+    uvs: Option<(Vec<[f32; 2]>, Interpolation)>,
+}
+
+/// Edge adjacency of a level.
+struct Topology {
+    edges: Vec<(usize, usize)>,
+    edge_faces: Vec<Vec<usize>>,
+    /// Edge from corner `i` to corner `i + 1` of each face.
+    face_edges: Vec<Vec<usize>>,
+    point_edges: Vec<Vec<usize>>,
+    point_faces: Vec<Vec<usize>>,
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+impl Topology {
+    fn new(points: usize, faces: &[Vec<usize>]) -> Self {
+        let mut map = HashMap::new();
+        let mut topology = Topology {
+            edges: Vec::new(),
+            edge_faces: Vec::new(),
+            face_edges: Vec::with_capacity(faces.len()),
+            point_edges: vec![Vec::new(); points],
+            point_faces: vec![Vec::new(); points],
+        };
+
+        for (f, face) in faces.iter().enumerate() {
+            let mut edges = Vec::with_capacity(face.len());
+            for (i, &a) in face.iter().enumerate() {
+                let b = face[(i + 1) % face.len()];
+                let key = edge_key(a, b);
+                let e = *map.entry(key).or_insert_with(|| {
+                    topology.edges.push(key);
+                    topology.edge_faces.push(Vec::new());
+                    topology.point_edges[key.0].push(topology.edges.len() - 1);
+                    topology.point_edges[key.1].push(topology.edges.len() - 1);
+                    topology.edges.len() - 1
+                });
+                topology.edge_faces[e].push(f);
+                topology.point_faces[a].push(f);
+                edges.push(e);
+            }
+            topology.face_edges.push(edges);
+        }
+
+        topology
+    }
+
+    fn is_boundary(&self, e: usize) -> bool {
+        self.edge_faces[e].len() != 2
+    }
+
+    fn other(&self, e: usize, p: usize) -> usize {
+        let (a, b) = self.edges[e];
+        if a == p {
+            b
+        } else {
+            a
+        }
+    }
+}
+
+impl Level {
+    fn from_mesh(mesh: &Mesh) -> Result<Self> {
+        let mut faces = Vec::with_capacity(mesh.face_vertex_counts.len());
+        let mut offset = 0;
+        for (face, &count) in mesh.face_vertex_counts.iter().enumerate() {
+            if count < 0 {
+                return Err(Error::InvalidMesh(format!(
+                    "face {} has negative vertex count {}",
+                    face, count
+                )));
+            }
+            let count = count as usize;
+            let indices = mesh.face_vertex_indices.get(offset..offset + count).ok_or(
+                Error::IndexOutOfBounds {
+                    index: offset + count.max(1) - 1,
+                    len: mesh.face_vertex_indices.len(),
+                },
+            )?;
+            faces.push(
+                indices
+                    .iter()
+                    .map(|&i| checked_index(i, mesh.points.len()))
+                    .collect::<Result<Vec<_>>>()?,
+            );
+            offset += count;
+        }
+
+        let mut holes = vec![false; faces.len()];
+        for &h in &mesh.hole_indices {
+            if let Some(hole) = usize::try_from(h).ok().and_then(|h| holes.get_mut(h)) {
+                *hole = true;
+            }
+        }
+
+        let mut creases = HashMap::new();
+        let edge_count: usize = mesh
+            .crease_lengths
+            .iter()
+            .map(|&l| (l.max(1) - 1) as usize)
+            .sum();
+        let per_edge = mesh.crease_sharpnesses.len() == edge_count
+            && mesh.crease_sharpnesses.len() != mesh.crease_lengths.len();
+        let (mut start, mut edge) = (0, 0);
+        for (crease, &length) in mesh.crease_lengths.iter().enumerate() {
+            let length = length.max(0) as usize;
+            let chain =
+                mesh.crease_indices
+                    .get(start..start + length)
+                    .ok_or(Error::IndexOutOfBounds {
+                        index: start + length.max(1) - 1,
+                        len: mesh.crease_indices.len(),
+                    })?;
+            for pair in chain.windows(2) {
+                let sharpness = if per_edge {
+                    mesh.crease_sharpnesses[edge]
+                } else {
+                    mesh.crease_sharpnesses.get(crease).copied().unwrap_or(0.0)
+                };
+                let a = checked_index(pair[0], mesh.points.len())?;
+                let b = checked_index(pair[1], mesh.points.len())?;
+                creases.insert(edge_key(a, b), sharpness);
+                edge += 1;
+            }
+            start += length;
+        }
+
+        let mut corners = HashMap::new();
+        for (i, &corner) in mesh.corner_indices.iter().enumerate() {
+            let sharpness = mesh.corner_sharpnesses.get(i).copied().unwrap_or(0.0);
+            corners.insert(checked_index(corner, mesh.points.len())?, sharpness);
+        }
+
+        let uvs = mesh.uvs.clone().map(|uvs| (uvs, mesh.uvs_interpolation));
+        if let Some((uvs, interpolation)) = &uvs {
+            let expected = match interpolation {
+                Interpolation::Constant => 1,
+                Interpolation::Uniform => faces.len(),
+                Interpolation::Vertex | Interpolation::Varying => mesh.points.len(),
+                Interpolation::FaceVarying => mesh.face_vertex_indices.len(),
+            };
+            if uvs.len() < expected {
+                return Err(Error::IndexOutOfBounds {
+                    index: expected - 1,
+                    len: uvs.len(),
+                });
+            }
+        }
+
+        Ok(Level {
+            points: mesh.points.clone(),
+            origins: (0..faces.len()).collect(),
+            faces,
+            holes,
+            creases,
+            corners,
+            uvs,
+        })
+    }
+
+    fn refine(&self, scheme: SubdivisionScheme, boundary: InterpolateBoundary) -> Level {
+        let topology = Topology::new(self.points.len(), &self.faces);
+        let edge_sharpness: Vec<f32> = topology
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(e, &key)| {
+                if topology.is_boundary(e) {
+                    INFINITELY_SHARP
+                } else {
+                    self.creases.get(&key).copied().unwrap_or(0.0)
+                }
+            })
+            .collect();
+
+        let (stencils, faces, parents) = match scheme {
+            SubdivisionScheme::Loop => self.loop_stencils(&topology, &edge_sharpness, boundary),
+            SubdivisionScheme::Bilinear => self.bilinear_stencils(&topology),
+            _ => self.catmull_clark_stencils(&topology, &edge_sharpness, boundary),
+        };
+
+        let points = apply(&stencils, &self.points);
+        let holes = parents.iter().map(|&(f, _)| self.holes[f]).collect();
+        let origins = parents.iter().map(|&(f, _)| self.origins[f]).collect();
+
+        // Sharpness decays by one per level on both halves of each edge.
+        let first_edge_point = self.points.len();
+        let mut creases = HashMap::new();
+        for (e, &(a, b)) in topology.edges.iter().enumerate() {
+            let sharpness = self.creases.get(&(a, b)).copied().unwrap_or(0.0);
+            let child = decay(sharpness);
+            if child > 0.0 {
+                let mid = first_edge_point + e;
+                creases.insert(edge_key(a, mid), child);
+                creases.insert(edge_key(mid, b), child);
+            }
+        }
+        let corners = self
+            .corners
+            .iter()
+            .map(|(&p, &s)| (p, decay(s)))
+            .filter(|&(_, s)| s > 0.0)
+            .collect();
+
+        let uvs = self.uvs.as_ref().map(|(uvs, interpolation)| {
+            let refined = match interpolation {
+                Interpolation::Constant => uvs.clone(),
+                Interpolation::Uniform => parents.iter().map(|&(f, _)| uvs[f]).collect(),
+                Interpolation::Vertex | Interpolation::Varying => apply(&stencils, uvs),
+                Interpolation::FaceVarying => self.refine_face_varying(uvs, scheme),
+            };
+            (refined, *interpolation)
+        });
+
+        Level {
+            points,
+            faces,
+            holes,
+            origins,
+            creases,
+            corners,
+            uvs,
+        }
+    }
+
+    /// Builds Catmull-Clark stencils and the refined quads.
+    ///
+    /// Points are ordered as vertex points, then edge points, then face
+    /// points.
+    fn catmull_clark_stencils(
+        &self,
+        topology: &Topology,
+        edge_sharpness: &[f32],
+        boundary: InterpolateBoundary,
+    ) -> Refinement {
+        let face_stencils: Vec<Stencil> = self
+            .faces
+            .iter()
+            .map(|face| {
+                let w = 1.0 / face.len() as f32;
+                face.iter().map(|&p| (p, w)).collect()
+            })
+            .collect();
+
+        let mut stencils =
+            Vec::with_capacity(self.points.len() + topology.edges.len() + self.faces.len());
+
+        for p in 0..self.points.len() {
+            let edges = &topology.point_edges[p];
+            let faces = &topology.point_faces[p];
+            let n = edges.len() as f32;
+
+            let smooth = || {
+                if faces.is_empty() || n < 3.0 {
+                    return vec![(p, 1.0)];
+                }
+                // (Q + 2R + (n - 3)S) / n
+                let mut s = vec![(p, (n - 3.0) / n)];
+                for &f in faces {
+                    add_scaled(&mut s, &face_stencils[f], 1.0 / (faces.len() as f32 * n));
+                }
+                for &e in edges {
+                    let (a, b) = topology.edges[e];
+                    s.push((a, 1.0 / (n * n)));
+                    s.push((b, 1.0 / (n * n)));
+                }
+                s
+            };
+            let crease = |e1: usize, e2: usize| {
+                vec![
+                    (p, 0.75),
+                    (topology.other(e1, p), 0.125),
+                    (topology.other(e2, p), 0.125),
+                ]
+            };
+
+            stencils.push(self.vertex_rule(p, topology, edge_sharpness, boundary, smooth, crease));
+        }
+
+        for (e, &(a, b)) in topology.edges.iter().enumerate() {
+            let sharp = vec![(a, 0.5), (b, 0.5)];
+            let sharpness = edge_sharpness[e];
+            let stencil = if sharpness >= 1.0 || topology.is_boundary(e) {
+                sharp
+            } else {
+                let mut smooth = vec![(a, 0.25), (b, 0.25)];
+                for &f in &topology.edge_faces[e] {
+                    add_scaled(&mut smooth, &face_stencils[f], 0.25);
+                }
+                blend(&smooth, &sharp, sharpness)
+            };
+            stencils.push(stencil);
+        }
+
+        stencils.extend(face_stencils);
+
+        let edge_point = |e: usize| self.points.len() + e;
+        let face_point = |f: usize| self.points.len() + topology.edges.len() + f;
+
+        let mut faces = Vec::new();
+        let mut parents = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let edges = &topology.face_edges[f];
+            let n = face.len();
+            for (i, &p) in face.iter().enumerate() {
+                faces.push(vec![
+                    p,
+                    edge_point(edges[i]),
+                    face_point(f),
+                    edge_point(edges[(i + n - 1) % n]),
+                ]);
+                parents.push((f, i));
+            }
+        }
+
+        (merge_all(stencils), faces, parents)
+    }
+
+    /// Builds Loop stencils and the refined triangles.
+    fn loop_stencils(
+        &self,
+        topology: &Topology,
+        edge_sharpness: &[f32],
+        boundary: InterpolateBoundary,
+    ) -> Refinement {
+        let mut stencils = Vec::with_capacity(self.points.len() + topology.edges.len());
+
+        for p in 0..self.points.len() {
+            let edges = &topology.point_edges[p];
+            let n = edges.len() as f32;
+
+            let smooth = || {
+                if topology.point_faces[p].is_empty() || n < 3.0 {
+                    return vec![(p, 1.0)];
+                }
+                let c = 0.375 + 0.25 * (2.0 * std::f32::consts::PI / n).cos();
+                let beta = (0.625 - c * c) / n;
+                let mut s = vec![(p, 1.0 - n * beta)];
+                s.extend(edges.iter().map(|&e| (topology.other(e, p), beta)));
+                s
+            };
+            let crease = |e1: usize, e2: usize| {
+                vec![
+                    (p, 0.75),
+                    (topology.other(e1, p), 0.125),
+                    (topology.other(e2, p), 0.125),
+                ]
+            };
+
+            stencils.push(self.vertex_rule(p, topology, edge_sharpness, boundary, smooth, crease));
+        }
+
+        for (e, &(a, b)) in topology.edges.iter().enumerate() {
+            let sharp = vec![(a, 0.5), (b, 0.5)];
+            let sharpness = edge_sharpness[e];
+            let stencil = if sharpness >= 1.0 || topology.is_boundary(e) {
+                sharp
+            } else {
+                let mut smooth = vec![(a, 0.375), (b, 0.375)];
+                for &f in &topology.edge_faces[e] {
+                    let opposite = self.faces[f].iter().find(|&&v| v != a && v != b);
+                    if let Some(&opposite) = opposite {
+                        smooth.push((opposite, 0.125));
+                    }
+                }
+                blend(&smooth, &sharp, sharpness)
+            };
+            stencils.push(stencil);
+        }
+
+        let edge_point = |e: usize| self.points.len() + e;
+        let mut faces = Vec::new();
+        let mut parents = Vec::new();
+        for (f, face) in self.faces.iter().enumerate() {
+            let e = &topology.face_edges[f];
+            let (e01, e12, e20) = (edge_point(e[0]), edge_point(e[1]), edge_point(e[2]));
+            faces.push(vec![face[0], e01, e20]);
+            faces.push(vec![face[1], e12, e01]);
+            faces.push(vec![face[2], e20, e12]);
+            faces.push(vec![e01, e12, e20]);
+            parents.extend((0..4).map(|i| (f, i)));
+        }
+
+        (merge_all(stencils), faces, parents)
+    }
+
+    /// Builds bilinear stencils: the Catmull-Clark topology with linear points.
+    fn bilinear_stencils(&self, topology: &Topology) -> Refinement {
+        let sharp = vec![INFINITELY_SHARP; topology.edges.len()];
+        let (mut stencils, faces, parents) =
+            self.catmull_clark_stencils(topology, &sharp, InterpolateBoundary::EdgeAndCorner);
+        for (p, stencil) in stencils.iter_mut().take(self.points.len()).enumerate() {
+            *stencil = vec![(p, 1.0)];
+        }
+        (stencils, faces, parents)
+    }
+
+    /// Chooses the smooth, crease or corner rule for a vertex.
+    fn vertex_rule(
+        &self,
+        p: usize,
+        topology: &Topology,
+        edge_sharpness: &[f32],
+        boundary: InterpolateBoundary,
+        smooth: impl Fn() -> Stencil,
+        crease: impl Fn(usize, usize) -> Stencil,
+    ) -> Stencil {
+        let corner = vec![(p, 1.0)];
+        let sharp_edges: Vec<usize> = topology.point_edges[p]
+            .iter()
+            .copied()
+            .filter(|&e| edge_sharpness[e] > 0.0)
+            .collect();
+
+        let mut corner_sharpness = self.corners.get(&p).copied().unwrap_or(0.0);
+        if boundary == InterpolateBoundary::EdgeAndCorner
+            && topology.point_faces[p].len() == 1
+            && sharp_edges.iter().all(|&e| topology.is_boundary(e))
+        {
+            corner_sharpness = INFINITELY_SHARP;
+        }
+
+        let edge_average = if sharp_edges.is_empty() {
+            0.0
+        } else {
+            sharp_edges.iter().map(|&e| edge_sharpness[e]).sum::<f32>() / sharp_edges.len() as f32
+        };
+
+        let (sharp_rule, sharpness) = if corner_sharpness > 0.0 || sharp_edges.len() > 2 {
+            (corner, corner_sharpness.max(edge_average))
+        } else if sharp_edges.len() == 2 {
+            (crease(sharp_edges[0], sharp_edges[1]), edge_average)
+        } else {
+            return smooth();
+        };
+
+        if sharpness >= 1.0 {
+            sharp_rule
+        } else {
+            blend(&smooth(), &sharp_rule, sharpness)
+        }
+    }
+
+    /// Refines face-varying values linearly within each face.
+    fn refine_face_varying(&self, values: &[[f32; 2]], scheme: SubdivisionScheme) -> Vec<[f32; 2]> {
+        let mid = |a: [f32; 2], b: [f32; 2]| [(a[0] + b[0]) * 0.5, (a[1] + b[1]) * 0.5];
+        let mut result = Vec::new();
+        let mut offset = 0;
+
+        for face in &self.faces {
+            let n = face.len();
+            let corners = &values[offset..offset + n];
+            offset += n;
+
+            if scheme == SubdivisionScheme::Loop {
+                let (m01, m12, m20) = (
+                    mid(corners[0], corners[1]),
+                    mid(corners[1], corners[2]),
+                    mid(corners[2], corners[0]),
+                );
+                result.extend_from_slice(&[corners[0], m01, m20]);
+                result.extend_from_slice(&[corners[1], m12, m01]);
+                result.extend_from_slice(&[corners[2], m20, m12]);
+                result.extend_from_slice(&[m01, m12, m20]);
+            } else {
+                let mut center = [0.0f32; 2];
+                for c in corners {
+                    center[0] += c[0] / n as f32;
+                    center[1] += c[1] / n as f32;
+                }
+                for i in 0..n {
+                    result.push(corners[i]);
+                    result.push(mid(corners[i], corners[(i + 1) % n]));
+                    result.push(center);
+                    result.push(mid(corners[(i + n - 1) % n], corners[i]));
+                }
+            }
+        }
+
+        result
+    }
+
+    fn into_mesh(self, source: &Mesh) -> Mesh {
+        let mut mesh = Mesh::new(&source.name);
+        mesh.local_transform = source.local_transform;
+        mesh.world_transform = source.world_transform;
+        mesh.orientation = source.orientation;
+        mesh.subdivision_scheme = source.subdivision_scheme;
+        mesh.interpolate_boundary = source.interpolate_boundary;
+        mesh.material_binding = source.material_binding.clone();
+        mesh.subsets = source.remap_subsets(&self.origins);
+
+        mesh.points = self.points;
+        mesh.face_vertex_counts = self.faces.iter().map(|f| f.len() as i32).collect();
+        mesh.face_vertex_indices = self.faces.iter().flatten().map(|&p| p as i32).collect();
+        mesh.hole_indices = (0..self.holes.len() as i32)
+            .filter(|&f| self.holes[f as usize])
+            .collect();
+
+        let mut creases: Vec<_> = self.creases.into_iter().collect();
+        creases.sort_by_key(|&(key, _)| key);
+        for ((a, b), sharpness) in creases {
+            mesh.crease_indices.extend_from_slice(&[a as i32, b as i32]);
+            mesh.crease_lengths.push(2);
+            mesh.crease_sharpnesses.push(sharpness);
+        }
+        let mut corners: Vec<_> = self.corners.into_iter().collect();
+        corners.sort_by_key(|&(p, _)| p);
+        for (p, sharpness) in corners {
+            mesh.corner_indices.push(p as i32);
+            mesh.corner_sharpnesses.push(sharpness);
+        }
+
+        if let Some((uvs, interpolation)) = self.uvs {
+            mesh.uvs = Some(uvs);
+            mesh.uvs_interpolation = interpolation;
+        }
+
+        mesh
+    }
+}
+
+fn checked_index(index: i32, len: usize) -> Result<usize> {
+    usize::try_from(index)
+        .ok()
+        .filter(|&i| i < len)
+        .ok_or(Error::IndexOutOfBounds {
+            index: index.max(0) as usize,
+            len,
+        })
+}
+
+/// Sharpness of the child edges of an edge with the given sharpness.
+fn decay(sharpness: f32) -> f32 {
+    if sharpness >= INFINITELY_SHARP {
+        sharpness
+    } else {
+        (sharpness - 1.0).max(0.0)
+    }
+}
+
+fn add_scaled(stencil: &mut Stencil, other: &[(usize, f32)], scale: f32) {
+    stencil.extend(other.iter().map(|&(p, w)| (p, w * scale)));
+}
+
+/// Linearly blends two stencils: `smooth * (1 - t) + sharp * t`.
+fn blend(smooth: &[(usize, f32)], sharp: &[(usize, f32)], t: f32) -> Stencil {
+    let mut s = Vec::with_capacity(smooth.len() + sharp.len());
+    add_scaled(&mut s, smooth, 1.0 - t);
+    add_scaled(&mut s, sharp, t);
+    s
+}
+
+/// Merges duplicate source points in each stencil.
+fn merge_all(stencils: Vec<Stencil>) -> Vec<Stencil> {
+    stencils
+        .into_iter()
+        .map(|mut s| {
+            s.sort_by_key(|&(p, _)| p);
+            s.dedup_by(|b, a| {
+                if a.0 == b.0 {
+                    a.1 += b.1;
+                    true
+                } else {
+                    false
+                }
+            });
+            s
+        })
+        .collect()
+}
+
+fn apply<const N: usize>(stencils: &[Stencil], values: &[[f32; N]]) -> Vec<[f32; N]> {
+    stencils
+        .iter()
+        .map(|stencil| {
+            let mut v = [0.0f32; N];
+            for &(p, w) in stencil {
+                for (c, value) in v.iter_mut().enumerate() {
+                    *value += values[p][c] * w;
+                }
+            }
+            v
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A closed unit cube made of six quads.
+    fn cube() -> Mesh {
+        let mut mesh = Mesh::new("cube");
+        mesh.points = vec![
+            [-1.0, -1.0, -1.0],
+            [1.0, -1.0, -1.0],
+            [1.0, 1.0, -1.0],
+            [-1.0, 1.0, -1.0],
+            [-1.0, -1.0, 1.0],
+            [1.0, -1.0, 1.0],
+            [1.0, 1.0, 1.0],
+            [-1.0, 1.0, 1.0],
+        ];
+        mesh.face_vertex_counts = vec![4; 6];
+        mesh.face_vertex_indices = vec![
+            0, 3, 2, 1, 4, 5, 6, 7, 0, 1, 5, 4, 1, 2, 6, 5, 2, 3, 7, 6, 3, 0, 4, 7,
+        ];
+        mesh
+    }
+
+    #[test]
+    fn test_catmull_clark_cube() {
+        let mesh = cube().subdivide(1).unwrap();
+        assert_eq!(mesh.points.len(), 8 + 12 + 6);
+        assert_eq!(mesh.face_count(), 24);
+
+        // Corner points move to 5/9 of the way towards the center.
+        let p = mesh.points[6];
+        assert!(p.iter().all(|&c| (c - 5.0 / 9.0).abs() < 1e-6));
+
+        // Face points are the face centers.
+        assert_eq!(mesh.points[8 + 12 + 1], [0.0, 0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_catmull_clark_sharp_corner_and_crease() {
+        let mut mesh = cube();
+        mesh.corner_indices = vec![6];
+        mesh.corner_sharpnesses = vec![INFINITELY_SHARP];
+        mesh.crease_indices = vec![0, 1];
+        mesh.crease_lengths = vec![2];
+        mesh.crease_sharpnesses = vec![INFINITELY_SHARP];
+
+        let result = mesh.subdivide(1).unwrap();
+        assert_eq!(result.points[6], [1.0, 1.0, 1.0]);
+
+        // The creased edge's midpoint stays on the original edge.
+        let midpoint = result.points[8..20]
+            .iter()
+            .find(|p| p[1] == -1.0 && p[2] == -1.0)
+            .unwrap();
+        assert_eq!(*midpoint, [0.0, -1.0, -1.0]);
+        assert_eq!(result.crease_lengths.len(), 2);
+    }
+
+    #[test]
+    fn test_loop_subdivision() {
+        let mut mesh = Mesh::new("tri");
+        mesh.subdivision_scheme = SubdivisionScheme::Loop;
+        mesh.points = vec![[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]];
+        mesh.face_vertex_counts = vec![3];
+        mesh.face_vertex_indices = vec![0, 1, 2];
+        mesh.uvs = Some(vec![[0.0, 0.0], [1.0, 0.0], [0.0, 1.0]]);
+        mesh.uvs_interpolation = Interpolation::FaceVarying;
+
+        let result = mesh.subdivide(2).unwrap();
+        assert_eq!(result.face_count(), 16);
+        assert_eq!(result.uvs.as_ref().unwrap().len(), 48);
+        // Boundary corners are kept with the default edgeAndCorner rule.
+        assert_eq!(&result.points[..3], &mesh.points[..]);
+
+        mesh.uvs = None;
+        mesh.face_vertex_counts = vec![4];
+        mesh.face_vertex_indices = vec![0, 1, 2, 0];
+        assert!(matches!(mesh.subdivide(1), Err(Error::InvalidMesh(_))));
+    }
+}
@@ -0,0 +1,449 @@
+//! Filtered, prunable stage traversal.
+
+use crate::prim::{self, Prim, Purpose, Specifier, Visibility};
+use crate::stage::Stage;
+
+/// Predicates for [`Stage::traverse_with`].
+///
+/// A prim that fails a predicate is skipped along with its whole subtree,
+/// except for [`purposes`](Self::purposes), which only filters the prims
+/// that are yielded. By default every prim is visited, without descending
+/// into native instances.
+///
+/// # Example
+/// ```no_run
+/// use tinyusdz_rs::{Purpose, Stage, TraversalOptions};
+///
+/// let stage = Stage::open("scene.usda").unwrap();
+/// let options = TraversalOptions::new()
+///     .active_only(true)
+///     .defined_only(true)
+///     .purposes(&[Purpose::Default, Purpose::Render]);
+///
+/// let mut prims = stage.traverse_with(options);
+/// while let Some(prim) = prims.next() {
+///     println!("{}{}", "  ".repeat(prims.depth()), prim.name());
+///     if prim.is_mesh() {
+///         prims.skip_children();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TraversalOptions {
+    active_only: bool,
+    defined_only: bool,
+    models_only: bool,
+    visible_only: bool,
+    purposes: Option<Vec<Purpose>>,
+    instance_proxies: bool,
+}
+
+impl TraversalOptions {
+    /// Creates options that visit every prim.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Skips deactivated prims (`active = false`).
+    pub fn active_only(mut self, active_only: bool) -> Self {
+        self.active_only = active_only;
+        self
+    }
+
+    /// Skips `over` and abstract `class` prims.
+    pub fn defined_only(mut self, defined_only: bool) -> Self {
+        self.defined_only = defined_only;
+        self
+    }
+
+    /// Visits only the model hierarchy: prims with a model kind whose
+    /// parents are all groups.
+    pub fn models_only(mut self, models_only: bool) -> Self {
+        self.models_only = models_only;
+        self
+    }
+
+    /// Skips invisible prims.
+    pub fn visible_only(mut self, visible_only: bool) -> Self {
+        self.visible_only = visible_only;
+        self
+    }
+
+    /// Yields only prims whose computed purpose is listed.
+    ///
+    /// Other prims are not yielded but their children are still visited, so
+    /// `render` prims under a `default` parent are found.
+    pub fn purposes(mut self, purposes: &[Purpose]) -> Self {
+        self.purposes = Some(purposes.to_vec());
+        self
+    }
+
+    /// Whether to descend below native instances into their instance
+    /// proxies. Disabled by default, as in USD.
+    pub fn instance_proxies(mut self, instance_proxies: bool) -> Self {
+        self.instance_proxies = instance_proxies;
+        self
+    }
+}
+
+/// What the predicates decide for a prim.
+enum Filter {
+    /// Skip the prim and its subtree.
+    Prune,
+    /// Skip the prim but visit its children.
+    Skip,
+    /// Yield the prim.
+    Yield,
+}
+
+/// A prim waiting to be visited, with the state inherited from its parent.
+struct Entry<'a> {
+    prim: Prim<'a>,
+    path: String,
+    depth: usize,
+    purpose: Purpose,
+    /// True if all ancestors are groups, so this prim may be a model.
+    in_model_hierarchy: bool,
+}
+
+/// A depth-first cursor over the prims of a stage.
+///
+/// Returned by [`Stage::traverse_with`]. After each call to `next`,
+/// [`depth`](Self::depth) and [`path`](Self::path) describe the yielded
+/// prim and [`skip_children`](Self::skip_children) prunes its subtree.
+pub struct Traversal<'a> {
+    options: TraversalOptions,
+    stack: Vec<Entry<'a>>,
+    current: Option<Entry<'a>>,
+    depth: usize,
+    path: String,
+}
+
+impl<'a> Traversal<'a> {
+    pub(crate) fn new(stage: &'a Stage, options: TraversalOptions) -> Self {
+        let stack = stage
+            .root_prims()
+            .into_iter()
+            .rev()
+            .map(|prim| Entry {
+                path: prim.path(),
+                prim,
+                depth: 0,
+                purpose: Purpose::Default,
+                in_model_hierarchy: true,
+            })
+            .collect();
+
+        Traversal {
+            options,
+            stack,
+            current: None,
+            depth: 0,
+            path: String::new(),
+        }
+    }
+
+    /// Returns the depth of the last yielded prim; root prims have depth 0.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the full path of the last yielded prim.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Skips the descendants of the last yielded prim.
+    pub fn skip_children(&mut self) {
+        self.current = None;
+    }
+
+    /// Pushes the children of a visited prim.
+    fn push_children(&mut self, entry: Entry<'a>) {
+        let Some(spec) = entry.prim.spec() else {
+            return;
+        };
+        let in_model_hierarchy =
+            entry.in_model_hierarchy && prim::kind(spec).is_some_and(|k| k.is_group());
+        let children: Vec<Entry<'a>> = entry
+            .prim
+            .children()
+            .map(|child| Entry {
+                path: child.path(),
+                prim: child,
+                depth: entry.depth + 1,
+                purpose: entry.purpose,
+                in_model_hierarchy,
+            })
+            .collect();
+        self.stack.extend(children.into_iter().rev());
+    }
+
+    /// Applies the predicates to an entry, resolving its inherited state.
+    fn accept(&self, entry: &mut Entry<'a>) -> Filter {
+        let Some(spec) = entry.prim.spec() else {
+            return if self.is_filtering() {
+                Filter::Prune
+            } else {
+                Filter::Yield
+            };
+        };
+
+        if entry.purpose == Purpose::Default {
+            entry.purpose = prim::purpose(spec);
+        }
+
+        let options = &self.options;
+        if options.active_only && prim::bool_meta(spec, "active") == Some(false) {
+            return Filter::Prune;
+        }
+        if options.defined_only
+            && Specifier::from_token(&spec.specifier).unwrap_or_default() != Specifier::Def
+        {
+            return Filter::Prune;
+        }
+        if options.models_only
+            && !(entry.in_model_hierarchy && prim::kind(spec).is_some_and(|k| k.is_model()))
+        {
+            return Filter::Prune;
+        }
+        if options.visible_only && prim::visibility(spec) == Visibility::Invisible {
+            return Filter::Prune;
+        }
+        if let Some(purposes) = &options.purposes {
+            if !purposes.contains(&entry.purpose) {
+                return Filter::Skip;
+            }
+        }
+        Filter::Yield
+    }
+
+    fn is_filtering(&self) -> bool {
+        let options = &self.options;
+        options.active_only
+            || options.defined_only
+            || options.models_only
+            || options.visible_only
+            || options.purposes.is_some()
+    }
+}
+
+impl<'a> Iterator for Traversal<'a> {
+    type Item = Prim<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(entry) = self.current.take() {
+            self.push_children(entry);
+        }
+
+        while let Some(mut entry) = self.stack.pop() {
+            match self.accept(&mut entry) {
+                Filter::Prune => continue,
+                Filter::Skip => {
+                    if self.options.instance_proxies || !entry.prim.is_instance() {
+                        self.push_children(entry);
+                    }
+                    continue;
+                }
+                Filter::Yield => {}
+            }
+
+            let prim = entry.prim.clone();
+            self.depth = entry.depth;
+            self.path = entry.path.clone();
+            let is_instance = entry.prim.is_instance();
+            if self.options.instance_proxies || !is_instance {
+                self.current = Some(entry);
+            }
+            return Some(prim);
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENE: &str = r#"#usda 1.0
+
+def Xform "World" (
+    kind = "assembly"
+)
+{
+    def Xform "Car" (
+        kind = "component"
+    )
+    {
+        def Mesh "Body"
+        {
+        }
+
+        def Mesh "Proxy"
+        {
+            uniform token purpose = "proxy"
+        }
+    }
+
+    def Xform "Guide"
+    {
+        uniform token purpose = "guide"
+
+        def Mesh "Child"
+        {
+        }
+    }
+
+    def Mesh "RenderOnly"
+    {
+        uniform token purpose = "render"
+    }
+
+    def Xform "Hidden"
+    {
+        token visibility = "invisible"
+
+        def Mesh "Inner"
+        {
+        }
+    }
+
+    def Xform "Off" (
+        active = false
+    )
+    {
+        def Mesh "Inner"
+        {
+        }
+    }
+
+    over "Over"
+    {
+        def Mesh "Inner"
+        {
+        }
+    }
+
+    def Xform "Tree" (
+        instanceable = true
+        references = </Protos/Tree>
+    )
+    {
+        def Mesh "Leaf"
+        {
+        }
+    }
+
+    def Xform "Rig" (
+        variants = {
+            string lod = "high"
+        }
+        prepend variantSets = "lod"
+    )
+    {
+        variantSet "lod" = {
+            "high" {
+                def Mesh "Detail"
+                {
+                }
+            }
+        }
+    }
+}
+"#;
+
+    fn paths(stage: &Stage, options: TraversalOptions) -> Vec<String> {
+        stage.traverse_with(options).map(|p| p.path()).collect()
+    }
+
+    #[test]
+    fn test_default_traversal() {
+        let stage = Stage::from_usda(SCENE.as_bytes()).unwrap();
+        let all = paths(&stage, TraversalOptions::new());
+        // Instance proxies are not visited, variant children are.
+        assert!(all.contains(&"/World/Tree".to_string()));
+        assert!(!all.contains(&"/World/Tree/Leaf".to_string()));
+        assert!(all.contains(&"/World/Rig/Detail".to_string()));
+        assert_eq!(all.len(), 16);
+
+        let proxies = paths(&stage, TraversalOptions::new().instance_proxies(true));
+        assert!(proxies.contains(&"/World/Tree/Leaf".to_string()));
+        assert_eq!(proxies.len(), 17);
+    }
+
+    #[test]
+    fn test_pruning_predicates() {
+        let stage = Stage::from_usda(SCENE.as_bytes()).unwrap();
+        let without = |options: TraversalOptions| {
+            let kept = paths(&stage, options);
+            let mut removed = paths(&stage, TraversalOptions::new());
+            removed.retain(|p| !kept.contains(p));
+            removed
+        };
+
+        assert_eq!(
+            without(TraversalOptions::new().active_only(true)),
+            vec!["/World/Off", "/World/Off/Inner"]
+        );
+        assert_eq!(
+            without(TraversalOptions::new().defined_only(true)),
+            vec!["/World/Over", "/World/Over/Inner"]
+        );
+        assert_eq!(
+            without(TraversalOptions::new().visible_only(true)),
+            vec!["/World/Hidden", "/World/Hidden/Inner"]
+        );
+        assert_eq!(
+            paths(&stage, TraversalOptions::new().models_only(true)),
+            vec!["/World", "/World/Car"]
+        );
+    }
+
+    #[test]
+    fn test_purposes_filter_without_pruning() {
+        let stage = Stage::from_usda(SCENE.as_bytes()).unwrap();
+        assert_eq!(
+            paths(&stage, TraversalOptions::new().purposes(&[Purpose::Render])),
+            vec!["/World/RenderOnly"]
+        );
+        // Children inherit a non-default purpose.
+        assert_eq!(
+            paths(&stage, TraversalOptions::new().purposes(&[Purpose::Guide])),
+            vec!["/World/Guide", "/World/Guide/Child"]
+        );
+        assert_eq!(
+            without_default(&stage),
+            vec![
+                "/World/Car/Proxy",
+                "/World/Guide",
+                "/World/Guide/Child",
+                "/World/RenderOnly"
+            ]
+        );
+    }
+
+    fn without_default(stage: &Stage) -> Vec<String> {
+        let kept = paths(stage, TraversalOptions::new().purposes(&[Purpose::Default]));
+        let mut removed = paths(stage, TraversalOptions::new());
+        removed.retain(|p| !kept.contains(p));
+        removed
+    }
+
+    #[test]
+    fn test_depth_and_skip_children() {
+        let stage = Stage::from_usda(SCENE.as_bytes()).unwrap();
+        let mut prims = stage.traverse_with(TraversalOptions::new());
+        let mut visited = Vec::new();
+        while let Some(prim) = prims.next() {
+            visited.push((prims.depth(), prims.path().to_string()));
+            if prim.name() != "World" {
+                prims.skip_children();
+            }
+        }
+        assert_eq!(visited[0], (0, "/World".to_string()));
+        assert_eq!(visited[1], (1, "/World/Car".to_string()));
+        assert_eq!(visited[2], (1, "/World/Guide".to_string()));
+        assert!(visited[1..].iter().all(|(depth, _)| *depth == 1));
+        assert_eq!(visited.len(), 9);
+    }
+}
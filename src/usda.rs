@@ -0,0 +1,1858 @@
+//! Minimal USDA (ASCII USD) parser.
+//!
+//! Note: The tinyusdz C API can print a stage as USDA text but does not yet
+//! expose property values. Each stage is printed once with
+//! `c_tinyusd_stage_to_string` and parsed back into prim and property specs,
+//! and every value read on the Rust side (attributes, mesh geometry,
+//! transforms, cameras, lights, skeletons and instancers) comes from them.
+
+use half::f16;
+
+use crate::value::Value;
+
+/// A raw, untyped value as written in USDA.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Node {
+    /// The `None` literal (a blocked value).
+    None,
+    /// A number, kept as text so integers keep their full precision.
+    Number(String),
+    /// A quoted string.
+    String(String),
+    /// A bare identifier such as `true`.
+    Ident(String),
+    /// An asset path (`@file.usd@`).
+    Asset(String),
+    /// A prim or property path (`</World/Mesh>`).
+    Path(String),
+    /// A reference or payload with optional prim path and layer offset.
+    Reference {
+        asset: Option<String>,
+        path: Option<String>,
+        offset: f64,
+        scale: f64,
+    },
+    /// A parenthesized tuple.
+    Tuple(Vec<Node>),
+    /// A bracketed list.
+    List(Vec<Node>),
+    /// A dictionary of `type key = value` entries.
+    Dict(Vec<DictEntry>),
+    /// A `{ time: value }` map of time samples.
+    Samples(Vec<(f64, Node)>),
+}
+
+impl Node {
+    /// Returns the node as a number, if it is one.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            Node::Number(n) => n.parse().ok(),
+            Node::Ident(s) => match s.as_str() {
+                "inf" => Some(f64::INFINITY),
+                "-inf" => Some(f64::NEG_INFINITY),
+                "nan" => Some(f64::NAN),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Returns the node as a string, if it is a string or identifier.
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Node::String(s) | Node::Ident(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the node as a path, if it is one.
+    pub(crate) fn as_path(&self) -> Option<&str> {
+        match self {
+            Node::Path(p) => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Returns the elements of a list, or the node itself as a single item.
+    pub(crate) fn items(&self) -> &[Node] {
+        match self {
+            Node::List(items) => items,
+            Node::None => &[],
+            other => std::slice::from_ref(other),
+        }
+    }
+
+    /// Converts the node to a [`Value`], inferring the type from its shape.
+    ///
+    /// Used for metadata, whose values carry no declared type.
+    pub(crate) fn to_untyped_value(&self) -> Value {
+        match self {
+            Node::None => Value::None,
+            Node::Number(n) => match n.parse::<i64>() {
+                Ok(i) => i32::try_from(i).map(Value::Int).unwrap_or(Value::Int64(i)),
+                Err(_) => self.as_f64().map(Value::Double).unwrap_or(Value::None),
+            },
+            Node::String(s) => Value::String(s.clone()),
+            Node::Ident(s) => match s.as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => self
+                    .as_f64()
+                    .map(Value::Double)
+                    .unwrap_or_else(|| Value::Token(s.clone())),
+            },
+            Node::Asset(a) => Value::Asset(a.clone()),
+            Node::Path(p) => Value::String(p.clone()),
+            Node::Dict(entries) => Value::Dictionary(
+                entries
+                    .iter()
+                    .map(|e| {
+                        let value = match &e.type_name {
+                            Some(t) if t != "dictionary" => to_value(t, &e.value),
+                            _ => e.value.to_untyped_value(),
+                        };
+                        (e.key.clone(), value)
+                    })
+                    .collect(),
+            ),
+            Node::List(items) if items.iter().all(|i| matches!(i, Node::String(_))) => {
+                Value::StringArray(
+                    items
+                        .iter()
+                        .filter_map(|i| i.as_str())
+                        .map(String::from)
+                        .collect(),
+                )
+            }
+            Node::List(items) if items.iter().all(|i| i.as_f64().is_some()) => {
+                Value::DoubleArray(items.iter().filter_map(Node::as_f64).collect())
+            }
+            _ => Value::None,
+        }
+    }
+}
+
+/// An entry of a USDA dictionary.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DictEntry {
+    /// Declared value type (e.g. `string`), if any.
+    pub type_name: Option<String>,
+    pub key: String,
+    pub value: Node,
+}
+
+/// A metadata entry such as `kind = "component"` or `prepend references = ...`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MetaEntry {
+    /// List-op qualifier (`prepend`, `append`, `add`, `delete`, `reorder`).
+    pub op: Option<String>,
+    pub key: String,
+    pub value: Node,
+}
+
+/// Looks up a metadata entry by key.
+pub(crate) fn find_meta<'a>(metadata: &'a [MetaEntry], key: &str) -> Option<&'a Node> {
+    metadata.iter().find(|m| m.key == key).map(|m| &m.value)
+}
+
+/// Returns the items of a list-op metadata entry such as `prepend references`,
+/// across all of its operations except `delete`.
+pub(crate) fn list_op_items<'a>(
+    metadata: &'a [MetaEntry],
+    key: &'a str,
+) -> impl Iterator<Item = &'a Node> {
+    metadata
+        .iter()
+        .filter(move |m| m.key == key && m.op.as_deref() != Some("delete"))
+        .flat_map(|m| m.value.items())
+}
+
+/// A property (attribute or relationship) authored on a prim.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PropertySpec {
+    pub name: String,
+    /// Declared type including `[]` for arrays; `rel` for relationships.
+    pub type_name: String,
+    pub custom: bool,
+    pub uniform: bool,
+    pub default: Option<Node>,
+    pub time_samples: Vec<(f64, Node)>,
+    /// Connection targets (`.connect`) or relationship targets.
+    pub targets: Vec<String>,
+    pub metadata: Vec<MetaEntry>,
+}
+
+impl PropertySpec {
+    /// Returns true if this property is a relationship.
+    pub(crate) fn is_relationship(&self) -> bool {
+        self.type_name == "rel"
+    }
+}
+
+/// A variant set and its variants.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct VariantSetSpec {
+    pub name: String,
+    pub variants: Vec<PrimSpec>,
+}
+
+/// A prim as written in USDA.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct PrimSpec {
+    /// `def`, `over` or `class`.
+    pub specifier: String,
+    pub type_name: String,
+    pub name: String,
+    pub metadata: Vec<MetaEntry>,
+    pub properties: Vec<PropertySpec>,
+    pub children: Vec<PrimSpec>,
+    pub variant_sets: Vec<VariantSetSpec>,
+}
+
+impl PrimSpec {
+    /// Looks up a property by name.
+    pub(crate) fn property(&self, name: &str) -> Option<&PropertySpec> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+
+    /// Returns the property's value, falling back to its first time sample.
+    pub(crate) fn value(&self, name: &str) -> Option<Value> {
+        let prop = self.property(name)?;
+        let node = prop
+            .default
+            .as_ref()
+            .or_else(|| prop.time_samples.first().map(|(_, v)| v))?;
+        Some(to_value(&prop.type_name, node))
+    }
+
+    /// Returns a property's time samples as typed values, or its default
+    /// value as a single sample at time 0.
+    pub(crate) fn samples(&self, name: &str) -> Vec<(f64, Value)> {
+        let Some(prop) = self.property(name) else {
+            return Vec::new();
+        };
+        if prop.time_samples.is_empty() {
+            return prop
+                .default
+                .iter()
+                .map(|node| (0.0, to_value(&prop.type_name, node)))
+                .collect();
+        }
+        prop.time_samples
+            .iter()
+            .map(|(t, node)| (*t, to_value(&prop.type_name, node)))
+            .collect()
+    }
+
+    /// Returns a property's samples converted by `convert`, dropping samples
+    /// it rejects.
+    pub(crate) fn track<T>(
+        &self,
+        name: &str,
+        convert: impl Fn(Value) -> Option<Vec<T>>,
+    ) -> Vec<(f64, Vec<T>)> {
+        self.samples(name)
+            .into_iter()
+            .filter_map(|(t, value)| convert(value).map(|v| (t, v)))
+            .collect()
+    }
+
+    /// Returns the authored selection of a variant set (`variants` metadata).
+    pub(crate) fn variant_selection(&self, set: &str) -> Option<String> {
+        match find_meta(&self.metadata, "variants")? {
+            Node::Dict(entries) => entries
+                .iter()
+                .find(|e| e.key == set)
+                .and_then(|e| e.value.as_str())
+                .map(String::from),
+            _ => None,
+        }
+    }
+
+    /// Returns the prim with its selected variants composed in.
+    ///
+    /// `select` overrides the authored selection of a variant set. Opinions
+    /// authored on the prim itself are stronger than those of its variants,
+    /// and variant sets nested in a selected variant are composed too. The
+    /// `variants` metadata of the result holds the selections that were
+    /// used.
+    pub(crate) fn with_variants(&self, select: impl Fn(&str) -> Option<String>) -> PrimSpec {
+        let mut prim = self.clone();
+        let mut sets = std::mem::take(&mut prim.variant_sets);
+        let mut i = 0;
+        while i < sets.len() {
+            let name = sets[i].name.clone();
+            let selection = select(&name).or_else(|| prim.variant_selection(&name));
+            if let Some(variant) = selection
+                .as_ref()
+                .and_then(|s| sets[i].variants.iter().find(|v| v.name == *s))
+            {
+                let mut variant = variant.clone();
+                sets.append(&mut variant.variant_sets);
+                prim.merge_weaker(variant);
+            }
+            if let Some(selection) = selection {
+                prim.set_variant_selection(&name, selection);
+            }
+            i += 1;
+        }
+        prim.variant_sets = self.variant_sets.clone();
+        prim
+    }
+
+    /// Sets the selection of a variant set in the `variants` metadata.
+    fn set_variant_selection(&mut self, set: &str, variant: String) {
+        let entry = DictEntry {
+            type_name: Some("string".into()),
+            key: set.to_string(),
+            value: Node::String(variant),
+        };
+        match self.metadata.iter_mut().find(|m| m.key == "variants") {
+            Some(MetaEntry {
+                value: Node::Dict(entries),
+                ..
+            }) => match entries.iter_mut().find(|e| e.key == set) {
+                Some(existing) => *existing = entry,
+                None => entries.push(entry),
+            },
+            Some(meta) => meta.value = Node::Dict(vec![entry]),
+            None => self.metadata.push(MetaEntry {
+                op: None,
+                key: "variants".into(),
+                value: Node::Dict(vec![entry]),
+            }),
+        }
+    }
+
+    /// Adds the opinions of `weaker` that this prim does not author itself.
+    fn merge_weaker(&mut self, weaker: PrimSpec) {
+        for entry in weaker.metadata {
+            // Dictionaries such as `variants` and `customData` merge per key.
+            match self.metadata.iter_mut().find(|m| m.key == entry.key) {
+                Some(MetaEntry {
+                    value: Node::Dict(stronger),
+                    ..
+                }) => {
+                    if let Node::Dict(weaker) = entry.value {
+                        for e in weaker {
+                            if !stronger.iter().any(|s| s.key == e.key) {
+                                stronger.push(e);
+                            }
+                        }
+                    }
+                }
+                Some(_) => {}
+                None => self.metadata.push(entry),
+            }
+        }
+        for property in weaker.properties {
+            if self.property(&property.name).is_none() {
+                self.properties.push(property);
+            }
+        }
+        for child in weaker.children {
+            match self.children.iter_mut().find(|c| c.name == child.name) {
+                Some(existing) => existing.merge_weaker(child),
+                None => self.children.push(child),
+            }
+        }
+        self.variant_sets.extend(weaker.variant_sets);
+    }
+
+    fn property_mut(&mut self, name: &str) -> &mut PropertySpec {
+        match self.properties.iter().position(|p| p.name == name) {
+            Some(i) => &mut self.properties[i],
+            None => {
+                self.properties.push(PropertySpec {
+                    name: name.to_string(),
+                    ..Default::default()
+                });
+                self.properties.last_mut().unwrap()
+            }
+        }
+    }
+}
+
+/// A layer as written in USDA: its metadata and root prims.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct LayerSpec {
+    pub metadata: Vec<MetaEntry>,
+    pub prims: Vec<PrimSpec>,
+}
+
+impl LayerSpec {
+    /// Returns the asset paths of the layer's sublayers, strongest first.
+    pub(crate) fn sublayers(&self) -> Vec<String> {
+        list_op_items(&self.metadata, "subLayers")
+            .filter_map(|node| match node {
+                Node::Asset(asset) => Some(asset.clone()),
+                Node::Reference { asset, .. } => asset.clone(),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Parses a whole layer, as printed by `c_tinyusd_stage_to_string`.
+pub(crate) fn parse_layer(text: &str) -> Result<LayerSpec, String> {
+    let mut parser = Parser::new(text)?;
+    let mut layer = LayerSpec::default();
+    if parser.eat_punct('(') {
+        layer.metadata = parser.parse_metadata()?;
+    }
+    while parser.peek().is_some() {
+        if let Some(prim) = parser.parse_statement(true)? {
+            layer.prims.push(prim);
+        }
+    }
+    Ok(layer)
+}
+
+/// Parses the text of a single prim, as printed by `c_tinyusd_prim_to_string`.
+///
+/// Nested prims are skipped unless `children` is set.
+#[cfg(test)]
+pub(crate) fn parse_prim(text: &str, children: bool) -> Result<PrimSpec, String> {
+    let mut parser = Parser::new(text)?;
+    while parser.peek().is_some() {
+        if let Some(prim) = parser.parse_statement(children)? {
+            return Ok(prim);
+        }
+    }
+    Err("no prim found".into())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(String),
+    Str(String),
+    Asset(String),
+    Path(String),
+    Punct(char),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Result<Self, String> {
+        Ok(Parser {
+            tokens: tokenize(text)?,
+            pos: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn is_punct(&self, c: char) -> bool {
+        self.peek() == Some(&Token::Punct(c))
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        if self.is_punct(c) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), String> {
+        match self.next() {
+            Some(Token::Punct(p)) if p == c => Ok(()),
+            other => Err(format!("expected '{}', found {:?}", c, other)),
+        }
+    }
+
+    fn expect_ident(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Ident(s)) => Ok(s),
+            other => Err(format!("expected identifier, found {:?}", other)),
+        }
+    }
+
+    fn expect_string(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Str(s)) => Ok(s),
+            other => Err(format!("expected string, found {:?}", other)),
+        }
+    }
+
+    /// Parses a top-level statement, returning a prim if one was defined.
+    fn parse_statement(&mut self, children: bool) -> Result<Option<PrimSpec>, String> {
+        match self.peek() {
+            Some(Token::Ident(s)) if matches!(s.as_str(), "def" | "over" | "class") => {
+                let specifier = self.expect_ident()?;
+                self.parse_prim(specifier, children).map(Some)
+            }
+            _ => {
+                // Anything else at the top level (e.g. stray tokens) is ignored.
+                self.next();
+                Ok(None)
+            }
+        }
+    }
+
+    /// Parses a prim after its specifier.
+    fn parse_prim(&mut self, specifier: String, children: bool) -> Result<PrimSpec, String> {
+        let mut prim = PrimSpec {
+            specifier,
+            ..Default::default()
+        };
+
+        if let Some(Token::Ident(_)) = self.peek() {
+            prim.type_name = self.expect_ident()?;
+        }
+        prim.name = self.expect_string()?;
+        if self.eat_punct('(') {
+            prim.metadata = self.parse_metadata()?;
+        }
+        self.expect_punct('{')?;
+        self.parse_prim_body(&mut prim, children)?;
+        Ok(prim)
+    }
+
+    /// Parses prim contents up to and including the closing brace.
+    fn parse_prim_body(&mut self, prim: &mut PrimSpec, children: bool) -> Result<(), String> {
+        loop {
+            if self.eat_punct('}') {
+                return Ok(());
+            }
+            if self.eat_punct(';') {
+                continue;
+            }
+
+            match self.peek() {
+                None => return Err("unexpected end of prim".into()),
+                Some(Token::Ident(s)) if matches!(s.as_str(), "def" | "over" | "class") => {
+                    if children {
+                        let specifier = self.expect_ident()?;
+                        prim.children.push(self.parse_prim(specifier, true)?);
+                    } else {
+                        self.skip_prim()?;
+                    }
+                }
+                Some(Token::Ident(s)) if s == "variantSet" => {
+                    self.next();
+                    let set = self.parse_variant_set(children)?;
+                    prim.variant_sets.push(set);
+                }
+                Some(Token::Ident(s)) if s == "reorder" => {
+                    // `reorder nameChildren = [...]` or `reorder properties = [...]`
+                    self.next();
+                    self.expect_ident()?;
+                    self.expect_punct('=')?;
+                    self.parse_value()?;
+                }
+                _ => self.parse_property(prim)?,
+            }
+        }
+    }
+
+    /// Skips a nested prim without building it.
+    fn skip_prim(&mut self) -> Result<(), String> {
+        while !self.is_punct('{') {
+            if self.next().is_none() {
+                return Err("unexpected end of prim".into());
+            }
+        }
+        let mut depth = 0;
+        while let Some(token) = self.next() {
+            match token {
+                Token::Punct('{') => depth += 1,
+                Token::Punct('}') => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err("unterminated prim".into())
+    }
+
+    fn parse_variant_set(&mut self, children: bool) -> Result<VariantSetSpec, String> {
+        let mut set = VariantSetSpec {
+            name: self.expect_string()?,
+            variants: Vec::new(),
+        };
+        self.expect_punct('=')?;
+        self.expect_punct('{')?;
+        while !self.eat_punct('}') {
+            let mut variant = PrimSpec {
+                name: self.expect_string()?,
+                ..Default::default()
+            };
+            if self.eat_punct('(') {
+                variant.metadata = self.parse_metadata()?;
+            }
+            self.expect_punct('{')?;
+            self.parse_prim_body(&mut variant, children)?;
+            set.variants.push(variant);
+        }
+        Ok(set)
+    }
+
+    fn parse_property(&mut self, prim: &mut PrimSpec) -> Result<(), String> {
+        let mut custom = false;
+        let mut uniform = false;
+        let mut op = None;
+
+        loop {
+            match self.peek() {
+                Some(Token::Ident(s)) if s == "custom" => custom = true,
+                Some(Token::Ident(s)) if matches!(s.as_str(), "uniform" | "config") => {
+                    uniform = true
+                }
+                Some(Token::Ident(s)) if s == "varying" => {}
+                Some(Token::Ident(s))
+                    if matches!(s.as_str(), "prepend" | "append" | "add" | "delete") =>
+                {
+                    op = Some(s.clone())
+                }
+                _ => break,
+            }
+            self.next();
+        }
+
+        let mut type_name = self.expect_ident()?;
+        if type_name != "rel" && self.is_punct('[') {
+            self.expect_punct('[')?;
+            self.expect_punct(']')?;
+            type_name.push_str("[]");
+        }
+
+        let full_name = self.expect_ident()?;
+        let (name, suffix) = match full_name.rsplit_once('.') {
+            Some((name, suffix)) if matches!(suffix, "timeSamples" | "connect" | "spline") => {
+                (name.to_string(), Some(suffix.to_string()))
+            }
+            _ => (full_name, None),
+        };
+
+        let value = if self.eat_punct('=') {
+            Some(self.parse_value()?)
+        } else {
+            None
+        };
+        let metadata = if self.eat_punct('(') {
+            self.parse_metadata()?
+        } else {
+            Vec::new()
+        };
+
+        // Deleting targets from a list-op does not author anything we track.
+        if op.as_deref() == Some("delete") {
+            return Ok(());
+        }
+
+        let prop = prim.property_mut(&name);
+        prop.type_name = type_name;
+        prop.custom |= custom;
+        prop.uniform |= uniform;
+        prop.metadata.extend(metadata);
+
+        match (suffix.as_deref(), value) {
+            (Some("timeSamples"), Some(Node::Samples(samples))) => prop.time_samples = samples,
+            (Some("timeSamples"), Some(Node::Dict(entries))) if entries.is_empty() => {}
+            (Some("connect"), Some(value)) => {
+                prop.targets = value
+                    .items()
+                    .iter()
+                    .filter_map(Node::as_path)
+                    .map(String::from)
+                    .collect();
+            }
+            (Some(_), _) => {}
+            (None, Some(value)) if prop.type_name == "rel" => {
+                let targets = value
+                    .items()
+                    .iter()
+                    .filter_map(Node::as_path)
+                    .map(String::from);
+                prop.targets.extend(targets);
+            }
+            (None, value) => prop.default = value,
+        }
+
+        Ok(())
+    }
+
+    /// Parses metadata entries after the opening parenthesis.
+    fn parse_metadata(&mut self) -> Result<Vec<MetaEntry>, String> {
+        let mut entries = Vec::new();
+        loop {
+            if self.eat_punct(')') {
+                return Ok(entries);
+            }
+            if self.eat_punct(';') || self.eat_punct(',') {
+                continue;
+            }
+
+            match self.next() {
+                // A bare string is the documentation of the prim or layer.
+                Some(Token::Str(doc)) => entries.push(MetaEntry {
+                    op: None,
+                    key: "doc".into(),
+                    value: Node::String(doc),
+                }),
+                Some(Token::Ident(first)) => {
+                    let (op, key) = if matches!(
+                        first.as_str(),
+                        "prepend" | "append" | "add" | "delete" | "reorder"
+                    ) && matches!(self.peek(), Some(Token::Ident(_)))
+                    {
+                        (Some(first), self.expect_ident()?)
+                    } else {
+                        (None, first)
+                    };
+                    let value = if self.eat_punct('=') {
+                        self.parse_value()?
+                    } else {
+                        Node::None
+                    };
+                    entries.push(MetaEntry { op, key, value });
+                }
+                None => return Err("unterminated metadata".into()),
+                Some(other) => return Err(format!("unexpected {:?} in metadata", other)),
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Number(n)),
+            Some(Token::Str(s)) => Ok(Node::String(s)),
+            Some(Token::Ident(s)) if s == "None" => Ok(Node::None),
+            Some(Token::Ident(s)) => Ok(Node::Ident(s)),
+            Some(Token::Asset(asset)) => {
+                let path = match self.peek() {
+                    Some(Token::Path(_)) => match self.next() {
+                        Some(Token::Path(p)) => Some(p),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let (offset, scale, has_offset) = self.parse_layer_offset()?;
+                if path.is_none() && !has_offset {
+                    Ok(Node::Asset(asset))
+                } else {
+                    Ok(Node::Reference {
+                        asset: Some(asset),
+                        path,
+                        offset,
+                        scale,
+                    })
+                }
+            }
+            Some(Token::Path(path)) => {
+                let (offset, scale, has_offset) = self.parse_layer_offset()?;
+                if has_offset {
+                    Ok(Node::Reference {
+                        asset: None,
+                        path: Some(path),
+                        offset,
+                        scale,
+                    })
+                } else {
+                    Ok(Node::Path(path))
+                }
+            }
+            Some(Token::Punct('(')) => Ok(Node::Tuple(self.parse_sequence(')')?)),
+            Some(Token::Punct('[')) => Ok(Node::List(self.parse_sequence(']')?)),
+            Some(Token::Punct('{')) => self.parse_dict(),
+            other => Err(format!("expected value, found {:?}", other)),
+        }
+    }
+
+    /// Parses an optional `(offset = 1; scale = 2)` following a reference.
+    fn parse_layer_offset(&mut self) -> Result<(f64, f64, bool), String> {
+        let is_offset = self.is_punct('(')
+            && matches!(self.peek_at(1), Some(Token::Ident(k)) if k == "offset" || k == "scale")
+            && self.peek_at(2) == Some(&Token::Punct('='));
+        if !is_offset {
+            return Ok((0.0, 1.0, false));
+        }
+
+        self.expect_punct('(')?;
+        let (mut offset, mut scale) = (0.0, 1.0);
+        for entry in self.parse_metadata()? {
+            let value = entry.value.as_f64().unwrap_or_default();
+            match entry.key.as_str() {
+                "offset" => offset = value,
+                "scale" => scale = value,
+                _ => {}
+            }
+        }
+        Ok((offset, scale, true))
+    }
+
+    fn parse_sequence(&mut self, close: char) -> Result<Vec<Node>, String> {
+        let mut items = Vec::new();
+        loop {
+            if self.eat_punct(close) {
+                return Ok(items);
+            }
+            if self.eat_punct(',') {
+                continue;
+            }
+            items.push(self.parse_value()?);
+        }
+    }
+
+    /// Parses a dictionary or time-sample map after the opening brace.
+    fn parse_dict(&mut self) -> Result<Node, String> {
+        if let Some(Token::Number(_)) = self.peek() {
+            let mut samples = Vec::new();
+            loop {
+                if self.eat_punct('}') {
+                    return Ok(Node::Samples(samples));
+                }
+                if self.eat_punct(',') {
+                    continue;
+                }
+                let time = match self.next() {
+                    Some(Token::Number(n)) => n.parse().map_err(|_| format!("bad time {}", n))?,
+                    other => return Err(format!("expected time, found {:?}", other)),
+                };
+                self.expect_punct(':')?;
+                samples.push((time, self.parse_value()?));
+            }
+        }
+
+        let mut entries = Vec::new();
+        loop {
+            if self.eat_punct('}') {
+                return Ok(Node::Dict(entries));
+            }
+            if self.eat_punct(';') || self.eat_punct(',') {
+                continue;
+            }
+
+            let first = match self.next() {
+                Some(Token::Ident(s)) | Some(Token::Str(s)) => s,
+                other => return Err(format!("expected dictionary key, found {:?}", other)),
+            };
+            let (type_name, key) = if self.is_punct('=') || self.is_punct(':') {
+                (None, first)
+            } else {
+                let mut type_name = first;
+                if self.eat_punct('[') {
+                    self.expect_punct(']')?;
+                    type_name.push_str("[]");
+                }
+                let key = match self.next() {
+                    Some(Token::Ident(s)) | Some(Token::Str(s)) => s,
+                    other => return Err(format!("expected dictionary key, found {:?}", other)),
+                };
+                (Some(type_name), key)
+            };
+            if !self.eat_punct('=') {
+                self.expect_punct(':')?;
+            }
+            let value = self.parse_value()?;
+            entries.push(DictEntry {
+                type_name,
+                key,
+                value,
+            });
+        }
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = text.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+        } else if c == '#' {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+        } else if c == '"' || c == '\'' {
+            let triple = chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c);
+            i += if triple { 3 } else { 1 };
+            let mut s = String::new();
+            loop {
+                match chars.get(i) {
+                    None => return Err("unterminated string".into()),
+                    Some(&q) if q == c => {
+                        if !triple {
+                            i += 1;
+                            break;
+                        }
+                        if chars.get(i + 1) == Some(&c) && chars.get(i + 2) == Some(&c) {
+                            i += 3;
+                            break;
+                        }
+                        s.push(q);
+                        i += 1;
+                    }
+                    Some('\\') => {
+                        let escaped = chars.get(i + 1).copied().unwrap_or('\\');
+                        s.push(match escaped {
+                            'n' => '\n',
+                            't' => '\t',
+                            'r' => '\r',
+                            other => other,
+                        });
+                        i += 2;
+                    }
+                    Some(&ch) => {
+                        s.push(ch);
+                        i += 1;
+                    }
+                }
+            }
+            tokens.push(Token::Str(s));
+        } else if c == '@' {
+            let triple = chars.get(i + 1) == Some(&'@') && chars.get(i + 2) == Some(&'@');
+            let delimiter: &[char] = if triple { &['@', '@', '@'] } else { &['@'] };
+            i += delimiter.len();
+            let start = i;
+            while i < chars.len() && !chars[i..].starts_with(delimiter) {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("unterminated asset path".into());
+            }
+            tokens.push(Token::Asset(chars[start..i].iter().collect()));
+            i += delimiter.len();
+        } else if c == '<' {
+            let start = i + 1;
+            while i < chars.len() && chars[i] != '>' {
+                i += 1;
+            }
+            if i >= chars.len() {
+                return Err("unterminated path".into());
+            }
+            tokens.push(Token::Path(chars[start..i].iter().collect()));
+            i += 1;
+        } else if is_number_start(&chars, i) {
+            let start = i;
+            i += 1;
+            if chars[start..].starts_with(&['-', 'i', 'n', 'f']) {
+                i += 3;
+            }
+            while i < chars.len()
+                && (chars[i].is_ascii_digit()
+                    || matches!(chars[i], '.' | 'e' | 'E')
+                    || (matches!(chars[i], '-' | '+') && matches!(chars[i - 1], 'e' | 'E')))
+            {
+                i += 1;
+            }
+            let text: String = chars[start..i].iter().collect();
+            if text == "-inf" {
+                tokens.push(Token::Ident(text));
+            } else {
+                tokens.push(Token::Number(text));
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            let start = i;
+            while i < chars.len()
+                && (chars[i].is_alphanumeric() || matches!(chars[i], '_' | ':' | '.'))
+            {
+                i += 1;
+            }
+            // A trailing ':' belongs to a time sample or dictionary separator.
+            while i > start + 1 && chars[i - 1] == ':' {
+                i -= 1;
+            }
+            tokens.push(Token::Ident(chars[start..i].iter().collect()));
+        } else {
+            tokens.push(Token::Punct(c));
+            i += 1;
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn is_number_start(chars: &[char], i: usize) -> bool {
+    let next = chars.get(i + 1).copied().unwrap_or(' ');
+    match chars[i] {
+        c if c.is_ascii_digit() => true,
+        '-' | '+' => next.is_ascii_digit() || next == '.' || chars[i..].starts_with(&['-', 'i']),
+        '.' => next.is_ascii_digit(),
+        _ => false,
+    }
+}
+
+/// Converts a node to a [`Value`] of the declared USD type.
+///
+/// Half-precision and quaternion arrays are widened to their float
+/// counterparts. Quaternions are stored as `(x, y, z, w)`, while USDA
+/// writes them real part first. Unsupported types yield [`Value::None`].
+pub(crate) fn to_value(type_name: &str, node: &Node) -> Value {
+    if let Node::None = node {
+        return Value::None;
+    }
+
+    if let Some(element) = type_name.strip_suffix("[]") {
+        return to_array_value(element, node.items());
+    }
+
+    let f = |n: &Node| n.as_f64();
+    let scalar = match type_name {
+        "bool" => match node {
+            Node::Ident(s) => Some(Value::Bool(s == "true")),
+            Node::Number(n) => Some(Value::Bool(n != "0")),
+            _ => None,
+        },
+        "int" => int(node).map(|v| Value::Int(v as i32)),
+        "uint" => int(node).map(|v| Value::Uint(v as u32)),
+        "int64" => int(node).map(Value::Int64),
+        "uint64" => match node {
+            Node::Number(n) => n.parse().ok().map(Value::Uint64),
+            _ => None,
+        },
+        "half" => f(node).map(|v| Value::Half(f16::from_f64(v))),
+        "float" => f(node).map(|v| Value::Float(v as f32)),
+        "double" | "timecode" => f(node).map(Value::Double),
+        "token" => node.as_str().map(|s| Value::Token(s.to_string())),
+        "string" => node.as_str().map(|s| Value::String(s.to_string())),
+        "asset" => match node {
+            Node::Asset(a) => Some(Value::Asset(a.clone())),
+            _ => None,
+        },
+        "matrix2d" => matrix::<2>(node).map(Value::Matrix2d),
+        "matrix3d" => matrix::<3>(node).map(Value::Matrix3d),
+        "matrix4d" => matrix::<4>(node).map(Value::Matrix4d),
+        "frame4d" => matrix::<4>(node).map(Value::Frame4d),
+        "quath" => quat(node).map(|q| Value::Quath(q.map(f16::from_f64))),
+        "quatf" => quat(node).map(|q| Value::Quatf(q.map(|v| v as f32))),
+        "quatd" => quat(node).map(Value::Quatd),
+        _ => None,
+    };
+    if let Some(value) = scalar {
+        return value;
+    }
+
+    let Some((base, n)) = vector_type(type_name) else {
+        return Value::None;
+    };
+    let Some(v) = tuple(node) else {
+        return Value::None;
+    };
+    match (base, n) {
+        (VectorBase::Int(_), 2) => Value::Int2(v2(&v).map(|c| c as i32)),
+        (VectorBase::Int(_), 3) => Value::Int3(v3(&v).map(|c| c as i32)),
+        (VectorBase::Int(_), 4) => Value::Int4(v4(&v).map(|c| c as i32)),
+        (VectorBase::Uint, 2) => Value::Uint2(v2(&v).map(|c| c as u32)),
+        (VectorBase::Uint, 3) => Value::Uint3(v3(&v).map(|c| c as u32)),
+        (VectorBase::Uint, 4) => Value::Uint4(v4(&v).map(|c| c as u32)),
+        (VectorBase::Half(kind), 2) => vec2_half(kind, v2(&v).map(f16::from_f64)),
+        (VectorBase::Half(kind), 3) => vec3_half(kind, v3(&v).map(f16::from_f64)),
+        (VectorBase::Half(_), 4) => match type_name {
+            "color4h" => Value::Color4h(v4(&v).map(f16::from_f64)),
+            _ => Value::Half4(v4(&v).map(f16::from_f64)),
+        },
+        (VectorBase::Float(kind), 2) => vec2_float(kind, v2(&v).map(|c| c as f32)),
+        (VectorBase::Float(kind), 3) => vec3_float(kind, v3(&v).map(|c| c as f32)),
+        (VectorBase::Float(_), 4) => match type_name {
+            "color4f" => Value::Color4f(v4(&v).map(|c| c as f32)),
+            _ => Value::Float4(v4(&v).map(|c| c as f32)),
+        },
+        (VectorBase::Double(kind), 2) => vec2_double(kind, v2(&v)),
+        (VectorBase::Double(kind), 3) => vec3_double(kind, v3(&v)),
+        (VectorBase::Double(_), 4) => match type_name {
+            "color4d" => Value::Color4d(v4(&v)),
+            _ => Value::Double4(v4(&v)),
+        },
+        _ => Value::None,
+    }
+}
+
+fn to_array_value(element: &str, items: &[Node]) -> Value {
+    let floats = |items: &[Node]| -> Vec<f64> { items.iter().filter_map(Node::as_f64).collect() };
+    let tuples = |items: &[Node]| -> Vec<Vec<f64>> { items.iter().filter_map(tuple).collect() };
+
+    match element {
+        "bool" => Value::BoolArray(
+            items
+                .iter()
+                .map(|n| {
+                    matches!(n, Node::Ident(s) if s == "true")
+                        || matches!(n, Node::Number(s) if s != "0")
+                })
+                .collect(),
+        ),
+        "int" => Value::IntArray(items.iter().filter_map(int).map(|v| v as i32).collect()),
+        "uint" => Value::UintArray(items.iter().filter_map(int).map(|v| v as u32).collect()),
+        "int64" => Value::Int64Array(items.iter().filter_map(int).collect()),
+        "uint64" => Value::Uint64Array(
+            items
+                .iter()
+                .filter_map(|n| match n {
+                    Node::Number(s) => s.parse().ok(),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        "half" => Value::HalfArray(floats(items).into_iter().map(f16::from_f64).collect()),
+        "float" => Value::FloatArray(floats(items).into_iter().map(|v| v as f32).collect()),
+        "double" | "timecode" => Value::DoubleArray(floats(items)),
+        "token" => Value::TokenArray(
+            items
+                .iter()
+                .filter_map(Node::as_str)
+                .map(String::from)
+                .collect(),
+        ),
+        "string" => Value::StringArray(
+            items
+                .iter()
+                .filter_map(Node::as_str)
+                .map(String::from)
+                .collect(),
+        ),
+        "asset" => Value::StringArray(
+            items
+                .iter()
+                .filter_map(|n| match n {
+                    Node::Asset(a) => Some(a.clone()),
+                    _ => None,
+                })
+                .collect(),
+        ),
+        "matrix4d" => Value::Matrix4dArray(items.iter().filter_map(matrix::<4>).collect()),
+        "quath" | "quatf" => Value::Float4Array(
+            items
+                .iter()
+                .filter_map(quat)
+                .map(|q| q.map(|v| v as f32))
+                .collect(),
+        ),
+        "quatd" => Value::Double4Array(items.iter().filter_map(quat).collect()),
+        _ => match vector_type(element) {
+            Some((VectorBase::Int(_), 2)) => Value::Int2Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v2(v).map(|c| c as i32))
+                    .collect(),
+            ),
+            Some((VectorBase::Int(_), 3)) => Value::Int3Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v3(v).map(|c| c as i32))
+                    .collect(),
+            ),
+            Some((VectorBase::Int(_), 4)) => Value::Int4Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v4(v).map(|c| c as i32))
+                    .collect(),
+            ),
+            Some((VectorBase::Half(_) | VectorBase::Float(_), 2)) => Value::Float2Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v2(v).map(|c| c as f32))
+                    .collect(),
+            ),
+            Some((VectorBase::Half(_) | VectorBase::Float(_), 3)) => Value::Float3Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v3(v).map(|c| c as f32))
+                    .collect(),
+            ),
+            Some((VectorBase::Half(_) | VectorBase::Float(_), 4)) => Value::Float4Array(
+                tuples(items)
+                    .iter()
+                    .map(|v| v4(v).map(|c| c as f32))
+                    .collect(),
+            ),
+            Some((VectorBase::Double(_), 2)) => {
+                Value::Double2Array(tuples(items).iter().map(|v| v2(v)).collect())
+            }
+            Some((VectorBase::Double(_), 3)) => {
+                Value::Double3Array(tuples(items).iter().map(|v| v3(v)).collect())
+            }
+            Some((VectorBase::Double(_), 4)) => {
+                Value::Double4Array(tuples(items).iter().map(|v| v4(v)).collect())
+            }
+            _ => Value::None,
+        },
+    }
+}
+
+/// Role of a vector type, which selects the matching [`Value`] variant.
+#[derive(Clone, Copy, PartialEq)]
+enum VectorKind {
+    Plain,
+    Color,
+    Point,
+    Normal,
+    Vector,
+    TexCoord,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum VectorBase {
+    Int(VectorKind),
+    Uint,
+    Half(VectorKind),
+    Float(VectorKind),
+    Double(VectorKind),
+}
+
+/// Splits a vector type name such as `point3f` into its base and size.
+fn vector_type(type_name: &str) -> Option<(VectorBase, usize)> {
+    let (kind, rest) = [
+        ("color", VectorKind::Color),
+        ("point", VectorKind::Point),
+        ("normal", VectorKind::Normal),
+        ("vector", VectorKind::Vector),
+        ("texCoord", VectorKind::TexCoord),
+    ]
+    .iter()
+    .find_map(|(prefix, kind)| type_name.strip_prefix(prefix).map(|rest| (*kind, rest)))
+    .unwrap_or((VectorKind::Plain, type_name));
+
+    let (base, size) = if kind == VectorKind::Plain {
+        let split = rest.find(|c: char| c.is_ascii_digit())?;
+        let (base, size) = rest.split_at(split);
+        let base = match base {
+            "int" => VectorBase::Int(kind),
+            "uint" => VectorBase::Uint,
+            "half" => VectorBase::Half(kind),
+            "float" => VectorBase::Float(kind),
+            "double" => VectorBase::Double(kind),
+            _ => return None,
+        };
+        (base, size)
+    } else {
+        let (size, suffix) = rest.split_at(1);
+        let base = match suffix {
+            "h" => VectorBase::Half(kind),
+            "f" => VectorBase::Float(kind),
+            "d" => VectorBase::Double(kind),
+            _ => return None,
+        };
+        (base, size)
+    };
+
+    size.parse().ok().map(|n| (base, n))
+}
+
+fn vec2_half(kind: VectorKind, v: [f16; 2]) -> Value {
+    match kind {
+        VectorKind::TexCoord => Value::TexCoord2h(v),
+        _ => Value::Half2(v),
+    }
+}
+
+fn vec3_half(kind: VectorKind, v: [f16; 3]) -> Value {
+    match kind {
+        VectorKind::Color => Value::Color3h(v),
+        VectorKind::Point => Value::Point3h(v),
+        VectorKind::Normal => Value::Normal3h(v),
+        VectorKind::Vector => Value::Vector3h(v),
+        VectorKind::TexCoord => Value::TexCoord3h(v),
+        VectorKind::Plain => Value::Half3(v),
+    }
+}
+
+fn vec2_float(kind: VectorKind, v: [f32; 2]) -> Value {
+    match kind {
+        VectorKind::TexCoord => Value::TexCoord2f(v),
+        _ => Value::Float2(v),
+    }
+}
+
+fn vec3_float(kind: VectorKind, v: [f32; 3]) -> Value {
+    match kind {
+        VectorKind::Color => Value::Color3f(v),
+        VectorKind::Point => Value::Point3f(v),
+        VectorKind::Normal => Value::Normal3f(v),
+        VectorKind::Vector => Value::Vector3f(v),
+        VectorKind::TexCoord => Value::TexCoord3f(v),
+        VectorKind::Plain => Value::Float3(v),
+    }
+}
+
+fn vec2_double(kind: VectorKind, v: [f64; 2]) -> Value {
+    match kind {
+        VectorKind::TexCoord => Value::TexCoord2d(v),
+        _ => Value::Double2(v),
+    }
+}
+
+fn vec3_double(kind: VectorKind, v: [f64; 3]) -> Value {
+    match kind {
+        VectorKind::Color => Value::Color3d(v),
+        VectorKind::Point => Value::Point3d(v),
+        VectorKind::Normal => Value::Normal3d(v),
+        VectorKind::Vector => Value::Vector3d(v),
+        VectorKind::TexCoord => Value::TexCoord3d(v),
+        VectorKind::Plain => Value::Double3(v),
+    }
+}
+
+fn int(node: &Node) -> Option<i64> {
+    match node {
+        Node::Number(n) => n
+            .parse()
+            .ok()
+            .or_else(|| n.parse::<f64>().ok().map(|v| v as i64)),
+        _ => None,
+    }
+}
+
+fn tuple(node: &Node) -> Option<Vec<f64>> {
+    match node {
+        Node::Tuple(items) => items.iter().map(Node::as_f64).collect(),
+        _ => None,
+    }
+}
+
+fn v2(v: &[f64]) -> [f64; 2] {
+    [get(v, 0), get(v, 1)]
+}
+
+fn v3(v: &[f64]) -> [f64; 3] {
+    [get(v, 0), get(v, 1), get(v, 2)]
+}
+
+fn v4(v: &[f64]) -> [f64; 4] {
+    [get(v, 0), get(v, 1), get(v, 2), get(v, 3)]
+}
+
+fn get(v: &[f64], i: usize) -> f64 {
+    v.get(i).copied().unwrap_or_default()
+}
+
+/// Parses a `(w, x, y, z)` quaternion into `(x, y, z, w)` order.
+fn quat(node: &Node) -> Option<[f64; 4]> {
+    let v = tuple(node)?;
+    (v.len() == 4).then(|| [v[1], v[2], v[3], v[0]])
+}
+
+fn matrix<const N: usize>(node: &Node) -> Option<[[f64; N]; N]> {
+    let Node::Tuple(rows) = node else {
+        return None;
+    };
+    if rows.len() != N {
+        return None;
+    }
+
+    let mut m = [[0.0; N]; N];
+    for (i, row) in rows.iter().enumerate() {
+        let row = tuple(row)?;
+        if row.len() != N {
+            return None;
+        }
+        m[i].copy_from_slice(&row);
+    }
+    Some(m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MESH: &str = r#"
+def Mesh "Cube" (
+    prepend apiSchemas = ["MaterialBindingAPI"]
+    kind = "component"
+)
+{
+    float3[] extent = [(-1, -1, -1), (1, 1, 1)]
+    int[] faceVertexCounts = [4]
+    int[] faceVertexIndices = [0, 1, 2, 3]
+    rel material:binding = </Looks/Red>
+    point3f[] points = [(-1, -1, 0), (1, -1, 0), (1, 1, 0), (-1, 1, 0)]
+    texCoord2f[] primvars:st = [(0, 0), (1, 0), (1, 1), (0, 1)] (
+        interpolation = "faceVarying"
+    )
+    uniform token subdivisionScheme = "none"
+    double3 xformOp:translate.timeSamples = {
+        0: (0, 0, 0),
+        10: (1, 2, 3e-1),
+    }
+    quatf xformOp:orient = (1, 0, 0, 0)
+    uniform token[] xformOpOrder = ["xformOp:translate", "xformOp:orient"]
+
+    def Xform "Child"
+    {
+        token visibility = "invisible"
+    }
+}
+"#;
+
+    #[test]
+    fn test_parse_prim_properties() {
+        let prim = parse_prim(MESH, false).unwrap();
+        assert_eq!(prim.specifier, "def");
+        assert_eq!(prim.type_name, "Mesh");
+        assert_eq!(prim.name, "Cube");
+        assert!(prim.children.is_empty());
+        assert_eq!(
+            find_meta(&prim.metadata, "kind"),
+            Some(&Node::String("component".into()))
+        );
+
+        assert_eq!(
+            prim.value("extent"),
+            Some(Value::Float3Array(vec![[-1.0; 3], [1.0; 3]]))
+        );
+        assert_eq!(
+            prim.value("faceVertexIndices"),
+            Some(Value::IntArray(vec![0, 1, 2, 3]))
+        );
+        assert_eq!(
+            prim.value("subdivisionScheme"),
+            Some(Value::Token("none".into()))
+        );
+        assert_eq!(
+            prim.value("xformOp:orient"),
+            Some(Value::Quatf([0.0, 0.0, 0.0, 1.0]))
+        );
+
+        let st = prim.property("primvars:st").unwrap();
+        assert_eq!(
+            find_meta(&st.metadata, "interpolation"),
+            Some(&Node::String("faceVarying".into()))
+        );
+
+        let binding = prim.property("material:binding").unwrap();
+        assert!(binding.is_relationship());
+        assert_eq!(binding.targets, vec!["/Looks/Red".to_string()]);
+
+        let translate = prim.property("xformOp:translate").unwrap();
+        assert_eq!(translate.time_samples.len(), 2);
+        assert_eq!(
+            to_value(&translate.type_name, &translate.time_samples[1].1),
+            Value::Double3([1.0, 2.0, 0.3])
+        );
+    }
+
+    #[test]
+    fn test_with_variants() {
+        let prim = parse_prim(
+            r#"def Xform "Car" (
+    variants = {
+        string trim = "base"
+    }
+    prepend variantSets = "trim"
+)
+{
+    token color = "red"
+
+    variantSet "trim" = {
+        "base" {
+            token color = "grey"
+            float spoiler = 0
+        }
+        "sport" {
+            float spoiler = 1
+
+            def Mesh "Spoiler"
+            {
+            }
+        }
+    }
+}"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(prim.variant_selection("trim").as_deref(), Some("base"));
+
+        let base = prim.with_variants(|_| None);
+        assert_eq!(base.value("color"), Some(Value::Token("red".into())));
+        assert_eq!(base.value("spoiler"), Some(Value::Float(0.0)));
+        assert!(base.children.is_empty());
+
+        let sport = prim.with_variants(|set| (set == "trim").then(|| "sport".to_string()));
+        assert_eq!(sport.value("spoiler"), Some(Value::Float(1.0)));
+        assert_eq!(sport.variant_selection("trim").as_deref(), Some("sport"));
+        assert_eq!(sport.children[0].name, "Spoiler");
+    }
+
+    #[test]
+    fn test_parse_layer() {
+        let layer = parse_layer(
+            r#"#usda 1.0
+(
+    defaultPrim = "World"
+    subLayers = [@./lighting.usda@, @./anim.usda@ (offset = 24)]
+)
+
+def Xform "World"
+{
+}
+
+over "Extra"
+{
+}
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            find_meta(&layer.metadata, "defaultPrim"),
+            Some(&Node::String("World".into()))
+        );
+        assert_eq!(layer.sublayers(), vec!["./lighting.usda", "./anim.usda"]);
+        assert_eq!(layer.prims.len(), 2);
+        assert_eq!(layer.prims[1].specifier, "over");
+    }
+
+    #[test]
+    fn test_parse_arrays() {
+        let prim = parse_prim(
+            r#"def "Arrays"
+{
+    bool[] flags = [true, false, 1, 0]
+    int[] empty = []
+    int64[] big = [9007199254740993, -2]
+    uint64[] huge = [18446744073709551615]
+    half[] halves = [0.5, -1]
+    double[] doubles = [1e-3, -inf, 2.5E2]
+    token[] tokens = ["a", "b:c"]
+    asset[] textures = [@a.png@, @@@b@c.png@@@]
+    int2[] pairs = [(1, 2), (3, 4)]
+    double3[] positions = [(0, 0, 0), (1.5, -2, 3)]
+    quatf[] rotations = [(1, 0, 0, 0)]
+    matrix4d[] bind = [((1, 0, 0, 0), (0, 1, 0, 0), (0, 0, 1, 0), (0, 0, 0, 1))]
+    float[] blocked = None
+}"#,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            prim.value("flags"),
+            Some(Value::BoolArray(vec![true, false, true, false]))
+        );
+        assert_eq!(prim.value("empty"), Some(Value::IntArray(vec![])));
+        assert_eq!(
+            prim.value("big"),
+            Some(Value::Int64Array(vec![9_007_199_254_740_993, -2]))
+        );
+        assert_eq!(prim.value("huge"), Some(Value::Uint64Array(vec![u64::MAX])));
+        assert_eq!(
+            prim.value("halves"),
+            Some(Value::HalfArray(vec![
+                f16::from_f32(0.5),
+                f16::from_f32(-1.0)
+            ]))
+        );
+        assert_eq!(
+            prim.value("doubles"),
+            Some(Value::DoubleArray(vec![1e-3, f64::NEG_INFINITY, 250.0]))
+        );
+        assert_eq!(
+            prim.value("tokens"),
+            Some(Value::TokenArray(vec!["a".into(), "b:c".into()]))
+        );
+        assert_eq!(
+            prim.value("textures"),
+            Some(Value::StringArray(vec!["a.png".into(), "b@c.png".into()]))
+        );
+        assert_eq!(
+            prim.value("pairs"),
+            Some(Value::Int2Array(vec![[1, 2], [3, 4]]))
+        );
+        assert_eq!(
+            prim.value("positions"),
+            Some(Value::Double3Array(vec![[0.0; 3], [1.5, -2.0, 3.0]]))
+        );
+        assert_eq!(
+            prim.value("rotations"),
+            Some(Value::Float4Array(vec![[0.0, 0.0, 0.0, 1.0]]))
+        );
+        let mut identity = [[0.0; 4]; 4];
+        for (i, row) in identity.iter_mut().enumerate() {
+            row[i] = 1.0;
+        }
+        assert_eq!(
+            prim.value("bind"),
+            Some(Value::Matrix4dArray(vec![identity]))
+        );
+        assert_eq!(prim.value("blocked"), Some(Value::None));
+    }
+
+    #[test]
+    fn test_parse_time_samples() {
+        let prim = parse_prim(
+            r#"def Xform "Anim"
+{
+    float radius = 1
+    float radius.timeSamples = {
+        -1: 0.5,
+        2.5: None,
+        10: 2,
+    }
+    float[] weights.timeSamples = {
+        0: [0, 1],
+        1: [1, 0],
+    }
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let radius = prim.property("radius").unwrap();
+        assert_eq!(radius.default, Some(Node::Number("1".into())));
+        assert_eq!(
+            prim.samples("radius"),
+            vec![
+                (-1.0, Value::Float(0.5)),
+                (2.5, Value::None),
+                (10.0, Value::Float(2.0)),
+            ]
+        );
+        // The default wins over the first sample.
+        assert_eq!(prim.value("radius"), Some(Value::Float(1.0)));
+
+        assert_eq!(
+            prim.value("weights"),
+            Some(Value::FloatArray(vec![0.0, 1.0]))
+        );
+        assert_eq!(
+            prim.track("weights", |v| match v {
+                Value::FloatArray(w) => Some(w),
+                _ => None,
+            }),
+            vec![(0.0, vec![0.0, 1.0]), (1.0, vec![1.0, 0.0])]
+        );
+        assert!(prim.samples("missing").is_empty());
+    }
+
+    #[test]
+    fn test_parse_metadata() {
+        let prim = parse_prim(
+            r#"def Xform "Meta" (
+    doc = """Multi-line
+"quoted" doc"""
+    active = false
+    customData = {
+        int count = 3
+        dictionary nested = {
+            string label = "inner"
+        }
+    }
+    assetInfo = {
+        asset identifier = @./meta.usd@
+    }
+)
+{
+    custom uniform double weight = 0.5 (
+        doc = "A weight"
+        hidden = true
+    )
+    color3f inputs:color.connect = </Looks/Shader.outputs:rgb>
+}"#,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_meta(&prim.metadata, "doc").and_then(Node::as_str),
+            Some("Multi-line\n\"quoted\" doc")
+        );
+        assert_eq!(
+            find_meta(&prim.metadata, "active"),
+            Some(&Node::Ident("false".into()))
+        );
+
+        assert_eq!(
+            find_meta(&prim.metadata, "customData").map(Node::to_untyped_value),
+            Some(Value::Dictionary(vec![
+                ("count".into(), Value::Int(3)),
+                (
+                    "nested".into(),
+                    Value::Dictionary(vec![("label".into(), Value::String("inner".into()))])
+                ),
+            ]))
+        );
+        assert_eq!(
+            find_meta(&prim.metadata, "assetInfo").map(Node::to_untyped_value),
+            Some(Value::Dictionary(vec![(
+                "identifier".into(),
+                Value::Asset("./meta.usd".into())
+            )]))
+        );
+
+        let weight = prim.property("weight").unwrap();
+        assert!(weight.custom);
+        assert!(weight.uniform);
+        assert_eq!(weight.type_name, "double");
+        assert_eq!(
+            find_meta(&weight.metadata, "doc"),
+            Some(&Node::String("A weight".into()))
+        );
+
+        let color = prim.property("inputs:color").unwrap();
+        assert!(color.default.is_none());
+        assert_eq!(color.targets, vec!["/Looks/Shader.outputs:rgb".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_list_ops() {
+        let prim = parse_prim(
+            r#"def "Ops" (
+    prepend references = [@./a.usd@</A>, @./b.usd@ (offset = 10; scale = 2)]
+    append references = </Local>
+    delete references = @./c.usd@
+    add inherits = </_class_Base>
+    payload = None
+)
+{
+    prepend rel proxyPrim = [</P1>, </P2>]
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let ops: Vec<_> = prim
+            .metadata
+            .iter()
+            .filter(|m| m.key == "references")
+            .map(|m| m.op.as_deref())
+            .collect();
+        assert_eq!(ops, vec![Some("prepend"), Some("append"), Some("delete")]);
+
+        let references: Vec<_> = list_op_items(&prim.metadata, "references").collect();
+        assert_eq!(references.len(), 3);
+        assert_eq!(
+            references[1],
+            &Node::Reference {
+                asset: Some("./b.usd".into()),
+                path: None,
+                offset: 10.0,
+                scale: 2.0,
+            }
+        );
+        assert!(matches!(
+            references[2],
+            Node::Reference { asset: None, path: Some(p), .. } | Node::Path(p) if p == "/Local"
+        ));
+
+        let inherits: Vec<_> = list_op_items(&prim.metadata, "inherits")
+            .filter_map(Node::as_path)
+            .collect();
+        assert_eq!(inherits, vec!["/_class_Base"]);
+        assert_eq!(list_op_items(&prim.metadata, "payload").count(), 0);
+
+        let proxy = prim.property("proxyPrim").unwrap();
+        assert!(proxy.is_relationship());
+        assert_eq!(proxy.targets, vec!["/P1".to_string(), "/P2".to_string()]);
+    }
+
+    #[test]
+    fn test_parse_nested_variant_sets() {
+        let prim = parse_prim(
+            r#"def Xform "Asset" (
+    variants = {
+        string lod = "high"
+    }
+    prepend variantSets = ["lod", "look"]
+)
+{
+    variantSet "lod" = {
+        "high" (
+            variants = {
+                string look = "shiny"
+            }
+        ) {
+            int detail = 2
+
+            variantSet "look" = {
+                "matte" {
+                    token finish = "matte"
+                }
+                "shiny" {
+                    token finish = "gloss"
+                }
+            }
+        }
+        "low" {
+            int detail = 0
+        }
+    }
+}"#,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(prim.variant_sets.len(), 1);
+        let lod = &prim.variant_sets[0];
+        assert_eq!(lod.name, "lod");
+        let names: Vec<_> = lod.variants.iter().map(|v| v.name.as_str()).collect();
+        assert_eq!(names, vec!["high", "low"]);
+        assert_eq!(lod.variants[0].variant_sets[0].name, "look");
+
+        let high = prim.with_variants(|_| None);
+        assert_eq!(high.value("detail"), Some(Value::Int(2)));
+        assert_eq!(high.value("finish"), Some(Value::Token("gloss".into())));
+        // The authored variant sets are kept for inspection.
+        assert_eq!(high.variant_sets, prim.variant_sets);
+
+        let matte = prim.with_variants(|set| (set == "look").then(|| "matte".to_string()));
+        assert_eq!(matte.value("finish"), Some(Value::Token("matte".into())));
+
+        let low = prim.with_variants(|set| (set == "lod").then(|| "low".to_string()));
+        assert_eq!(low.value("detail"), Some(Value::Int(0)));
+        assert_eq!(low.value("finish"), None);
+    }
+
+    #[test]
+    fn test_parse_escapes_and_comments() {
+        let prim = parse_prim(
+            r#"# leading comment
+def "Text" # trailing comment
+{
+    string tab = "a\tb\nc"
+    string quote = 'it\'s "fine"'
+    string backslash = "C:\\path"
+    string hash = "not # a comment"
+    string block = '''line 1
+line 2'''
+    asset path = @./with space.png@
+}"#,
+            false,
+        )
+        .unwrap();
+
+        let string = |name| match prim.value(name) {
+            Some(Value::String(s)) => s,
+            other => panic!("{name}: {other:?}"),
+        };
+        assert_eq!(string("tab"), "a\tb\nc");
+        assert_eq!(string("quote"), "it's \"fine\"");
+        assert_eq!(string("backslash"), "C:\\path");
+        assert_eq!(string("hash"), "not # a comment");
+        assert_eq!(string("block"), "line 1\nline 2");
+        assert_eq!(
+            prim.value("path"),
+            Some(Value::Asset("./with space.png".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(parse_layer("def \"Open\" {").is_err());
+        assert!(parse_layer("def \"Bad\" { string s = \"unterminated }").is_err());
+        assert!(parse_layer("def \"Bad\" { asset a = @missing }").is_err());
+    }
+}
@@ -117,7 +117,7 @@ pub enum Value {
     /// 4D double vector.
     Double4([f64; 4]),
 
-    // Quaternion types
+    // Quaternion types, stored as (x, y, z, w)
     /// Half precision quaternion.
     Quath([f16; 4]),
     /// Float quaternion.
@@ -188,6 +188,8 @@ pub enum Value {
     Token(String),
     /// String value.
     String(String),
+    /// Asset path (e.g. a texture file).
+    Asset(String),
 
     // Array types
     /// Array of booleans.
@@ -238,6 +240,9 @@ pub enum Value {
     // Matrix arrays
     /// Array of 4x4 matrices.
     Matrix4dArray(Vec<[[f64; 4]; 4]>),
+
+    /// Dictionary of named values (e.g. `customData`), in authored order.
+    Dictionary(Vec<(String, Value)>),
 }
 
 impl Value {
@@ -300,6 +305,20 @@ impl Value {
         }
     }
 
+    /// Returns the value as an f64, converting from any scalar numeric type.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Half(v) => Some(v.to_f64()),
+            Value::Float(v) => Some(*v as f64),
+            Value::Double(v) => Some(*v),
+            Value::Int(v) => Some(*v as f64),
+            Value::Uint(v) => Some(*v as f64),
+            Value::Int64(v) => Some(*v as f64),
+            Value::Uint64(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
     /// Returns the value as a string slice, if it is a string or token.
     pub fn as_str(&self) -> Option<&str> {
         match self {
@@ -343,6 +362,43 @@ impl Value {
         }
     }
 
+    /// Returns the value as a double 2-vector, converting from any 2-component
+    /// half, float or double type.
+    pub fn as_double2(&self) -> Option<[f64; 2]> {
+        match self {
+            Value::Half2(v) | Value::TexCoord2h(v) => Some(v.map(|c| c.to_f64())),
+            Value::Float2(v) | Value::TexCoord2f(v) => Some(v.map(|c| c as f64)),
+            Value::Double2(v) | Value::TexCoord2d(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a double 3-vector, converting from any 3-component
+    /// half, float or double type.
+    pub fn as_double3(&self) -> Option<[f64; 3]> {
+        match self {
+            Value::Half3(v)
+            | Value::Color3h(v)
+            | Value::Point3h(v)
+            | Value::Normal3h(v)
+            | Value::Vector3h(v)
+            | Value::TexCoord3h(v) => Some(v.map(|c| c.to_f64())),
+            Value::Float3(v)
+            | Value::Color3f(v)
+            | Value::Point3f(v)
+            | Value::Normal3f(v)
+            | Value::Vector3f(v)
+            | Value::TexCoord3f(v) => Some(v.map(|c| c as f64)),
+            Value::Double3(v)
+            | Value::Color3d(v)
+            | Value::Point3d(v)
+            | Value::Normal3d(v)
+            | Value::Vector3d(v)
+            | Value::TexCoord3d(v) => Some(*v),
+            _ => None,
+        }
+    }
+
     /// Returns the value as a Float4 (single value), if it is one.
     pub fn as_float4(&self) -> Option<[f32; 4]> {
         match self {
@@ -351,6 +407,41 @@ impl Value {
         }
     }
 
+    /// Returns the value as a double quaternion `(x, y, z, w)`, converting
+    /// from any quaternion type.
+    pub fn as_quatd(&self) -> Option<[f64; 4]> {
+        match self {
+            Value::Quath(q) => Some(q.map(|c| c.to_f64())),
+            Value::Quatf(q) => Some(q.map(|c| c as f64)),
+            Value::Quatd(q) => Some(*q),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as an asset path, if it is one.
+    pub fn as_asset(&self) -> Option<&str> {
+        match self {
+            Value::Asset(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a Token array, if it is one.
+    pub fn as_token_array(&self) -> Option<&[String]> {
+        match self {
+            Value::TokenArray(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Returns the value as a Dictionary, if it is one.
+    pub fn as_dictionary(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Dictionary(v) => Some(v),
+            _ => None,
+        }
+    }
+
     /// Returns the value as a Matrix4d, if it is one.
     pub fn as_matrix4d(&self) -> Option<[[f64; 4]; 4]> {
         match self {
@@ -0,0 +1,329 @@
+//! Transform (xformOp) evaluation.
+//!
+//! Matrices follow the USD convention: row vectors multiplied on the left
+//! (`p' = p * M`), with the translation in the last row.
+
+use crate::usda::PrimSpec;
+use crate::value::Value;
+
+/// Returns a 4x4 identity matrix.
+pub fn identity() -> [[f64; 4]; 4] {
+    [
+        [1.0, 0.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0, 0.0],
+        [0.0, 0.0, 1.0, 0.0],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Transforms a point by a matrix.
+pub fn transform_point(m: &[[f64; 4]; 4], p: [f64; 3]) -> [f64; 3] {
+    let mut r = [0.0; 3];
+    for (j, value) in r.iter_mut().enumerate() {
+        *value = p[0] * m[0][j] + p[1] * m[1][j] + p[2] * m[2][j] + m[3][j];
+    }
+    let w = p[0] * m[0][3] + p[1] * m[1][3] + p[2] * m[2][3] + m[3][3];
+    if w != 0.0 && w != 1.0 {
+        r = r.map(|c| c / w);
+    }
+    r
+}
+
+/// Transforms a direction by a matrix, ignoring translation.
+pub fn transform_vector(m: &[[f64; 4]; 4], v: [f64; 3]) -> [f64; 3] {
+    let mut r = [0.0; 3];
+    for (j, value) in r.iter_mut().enumerate() {
+        *value = v[0] * m[0][j] + v[1] * m[1][j] + v[2] * m[2][j];
+    }
+    r
+}
+
+/// Inverts a 4x4 matrix, returning `None` if it is singular.
+#[allow(clippy::needless_range_loop)]
+pub fn invert(m: &[[f64; 4]; 4]) -> Option<[[f64; 4]; 4]> {
+    // Gauss-Jordan elimination with partial pivoting.
+    let mut a = *m;
+    let mut inv = identity();
+
+    for col in 0..4 {
+        let pivot = (col..4).max_by(|&i, &j| a[i][col].abs().total_cmp(&a[j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+
+        let d = a[col][col];
+        for j in 0..4 {
+            a[col][j] /= d;
+            inv[col][j] /= d;
+        }
+
+        for row in 0..4 {
+            if row != col {
+                let f = a[row][col];
+                for j in 0..4 {
+                    a[row][j] -= f * a[col][j];
+                    inv[row][j] -= f * inv[col][j];
+                }
+            }
+        }
+    }
+
+    Some(inv)
+}
+
+/// Builds a rotation matrix from a quaternion `(x, y, z, w)`.
+pub fn quat_to_matrix(q: [f64; 4]) -> [[f64; 4]; 4] {
+    let len = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if len == 0.0 {
+        return identity();
+    }
+    let [x, y, z, w] = q.map(|c| c / len);
+
+    [
+        [
+            1.0 - 2.0 * (y * y + z * z),
+            2.0 * (x * y + z * w),
+            2.0 * (x * z - y * w),
+            0.0,
+        ],
+        [
+            2.0 * (x * y - z * w),
+            1.0 - 2.0 * (x * x + z * z),
+            2.0 * (y * z + x * w),
+            0.0,
+        ],
+        [
+            2.0 * (x * z + y * w),
+            2.0 * (y * z - x * w),
+            1.0 - 2.0 * (x * x + y * y),
+            0.0,
+        ],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Returns a translation matrix.
+pub fn translation(t: [f64; 3]) -> [[f64; 4]; 4] {
+    let mut m = identity();
+    m[3][0] = t[0];
+    m[3][1] = t[1];
+    m[3][2] = t[2];
+    m
+}
+
+/// Returns a scale matrix.
+pub fn scale(s: [f64; 3]) -> [[f64; 4]; 4] {
+    let mut m = identity();
+    m[0][0] = s[0];
+    m[1][1] = s[1];
+    m[2][2] = s[2];
+    m
+}
+
+/// Returns a rotation of `degrees` about the X (0), Y (1) or Z (2) axis.
+pub fn rotation(axis: usize, degrees: f64) -> [[f64; 4]; 4] {
+    let (s, c) = degrees.to_radians().sin_cos();
+    let (a, b) = match axis {
+        0 => (1, 2),
+        1 => (2, 0),
+        _ => (0, 1),
+    };
+    let mut m = identity();
+    m[a][a] = c;
+    m[a][b] = s;
+    m[b][a] = -s;
+    m[b][b] = c;
+    m
+}
+
+/// Samples a track of arrays at `time`, interpolating element-wise.
+///
+/// Times outside the samples hold the first or last value. Samples whose
+/// arrays differ in length are held rather than interpolated.
+pub(crate) fn sample_track<T: Copy>(
+    track: &[(f64, Vec<T>)],
+    time: f64,
+    interpolate: impl Fn(T, T, f32) -> T,
+) -> Vec<T> {
+    let next = track.partition_point(|(t, _)| *t <= time);
+    match (next.checked_sub(1).map(|i| &track[i]), track.get(next)) {
+        (None, None) => Vec::new(),
+        (Some((_, values)), None) | (None, Some((_, values))) => values.clone(),
+        (Some((t0, a)), Some((t1, b))) => {
+            if a.len() != b.len() {
+                return a.clone();
+            }
+            let t = ((time - t0) / (t1 - t0)) as f32;
+            a.iter()
+                .zip(b)
+                .map(|(&a, &b)| interpolate(a, b, t))
+                .collect()
+        }
+    }
+}
+
+pub(crate) fn lerp3(a: [f32; 3], b: [f32; 3], t: f32) -> [f32; 3] {
+    [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * t)
+}
+
+/// Spherically interpolates two `(x, y, z, w)` quaternions.
+pub fn slerp(a: [f32; 4], mut b: [f32; 4], t: f32) -> [f32; 4] {
+    let mut cos = a[0] * b[0] + a[1] * b[1] + a[2] * b[2] + a[3] * b[3];
+    // Take the shortest path.
+    if cos < 0.0 {
+        b = b.map(|c| -c);
+        cos = -cos;
+    }
+
+    let (wa, wb) = if cos > 0.9995 {
+        (1.0 - t, t)
+    } else {
+        let angle = cos.acos();
+        let sin = angle.sin();
+        (((1.0 - t) * angle).sin() / sin, (t * angle).sin() / sin)
+    };
+    let q = [0, 1, 2, 3].map(|i| a[i] * wa + b[i] * wb);
+    let len = (q[0] * q[0] + q[1] * q[1] + q[2] * q[2] + q[3] * q[3]).sqrt();
+    if len == 0.0 {
+        a
+    } else {
+        q.map(|c| c / len)
+    }
+}
+
+/// Evaluates a prim's `xformOpOrder` into its local transform.
+///
+/// Returns the matrix and whether the prim resets the transform stack
+/// (`!resetXformStack!`), in which case parent transforms are ignored.
+pub(crate) fn local_transform(spec: &PrimSpec) -> ([[f64; 4]; 4], bool) {
+    local_transform_with(spec, |name| spec.value(name))
+}
+
+/// Evaluates `xformOpOrder`, reading op values through `value`.
+pub(crate) fn local_transform_with(
+    spec: &PrimSpec,
+    value: impl Fn(&str) -> Option<Value>,
+) -> ([[f64; 4]; 4], bool) {
+    let order = match spec.value("xformOpOrder") {
+        Some(Value::TokenArray(order)) => order,
+        _ => return (identity(), false),
+    };
+
+    let mut result = identity();
+    let mut resets = false;
+
+    for op in &order {
+        if op == "!resetXformStack!" {
+            result = identity();
+            resets = true;
+            continue;
+        }
+
+        let (name, inverse) = match op.strip_prefix("!invert!") {
+            Some(name) => (name, true),
+            None => (op.as_str(), false),
+        };
+        let Some(op_value) = value(name) else {
+            continue;
+        };
+        let Some(mut m) = op_matrix(name, &op_value) else {
+            continue;
+        };
+        if inverse {
+            m = invert(&m).unwrap_or_else(identity);
+        }
+
+        // The first op in the order is the outermost, i.e. applied last.
+        result = crate::mesh::matrix_multiply(m, result);
+    }
+
+    (result, resets)
+}
+
+/// Builds the matrix for a single xformOp.
+fn op_matrix(name: &str, value: &Value) -> Option<[[f64; 4]; 4]> {
+    let kind = name.strip_prefix("xformOp:")?;
+    let kind = kind.split(':').next()?;
+
+    let angle = || value.as_f64();
+    let vec3 = || value.as_double3();
+
+    match kind {
+        "translate" => vec3().map(translation),
+        "scale" => vec3().map(scale),
+        "rotateX" => angle().map(|a| rotation(0, a)),
+        "rotateY" => angle().map(|a| rotation(1, a)),
+        "rotateZ" => angle().map(|a| rotation(2, a)),
+        "orient" => value.as_quatd().map(quat_to_matrix),
+        "transform" => value.as_matrix4d(),
+        _ => {
+            // rotateXYZ, rotateZYX, ...: the first axis is applied first.
+            let axes = kind.strip_prefix("rotate")?;
+            if axes.len() != 3 {
+                return None;
+            }
+            let angles = vec3()?;
+            let mut m = identity();
+            for axis_name in axes.chars() {
+                let axis = match axis_name {
+                    'X' => 0,
+                    'Y' => 1,
+                    'Z' => 2,
+                    _ => return None,
+                };
+                m = crate::mesh::matrix_multiply(m, rotation(axis, angles[axis]));
+            }
+            Some(m)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usda::parse_prim;
+
+    fn assert_close(a: [f64; 3], b: [f64; 3]) {
+        assert!(
+            (0..3).all(|i| (a[i] - b[i]).abs() < 1e-9),
+            "{:?} != {:?}",
+            a,
+            b
+        );
+    }
+
+    #[test]
+    fn test_local_transform_op_order() {
+        let spec = parse_prim(
+            r#"def Xform "X"
+{
+    double3 xformOp:translate = (10, 0, 0)
+    float xformOp:rotateZ = 90
+    float3 xformOp:scale = (2, 2, 2)
+    uniform token[] xformOpOrder = ["xformOp:translate", "xformOp:rotateZ", "xformOp:scale"]
+}"#,
+            false,
+        )
+        .unwrap();
+
+        // Scale, then rotate, then translate.
+        let (m, resets) = local_transform(&spec);
+        assert!(!resets);
+        assert_close(transform_point(&m, [1.0, 0.0, 0.0]), [10.0, 2.0, 0.0]);
+
+        let inverse = invert(&m).unwrap();
+        assert_close(transform_point(&inverse, [10.0, 2.0, 0.0]), [1.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn test_orient_matches_rotate() {
+        // 90 degrees about Y as (x, y, z, w).
+        let h = std::f64::consts::FRAC_1_SQRT_2;
+        let q = quat_to_matrix([0.0, h, 0.0, h]);
+        let r = rotation(1, 90.0);
+        assert_close(transform_point(&q, [1.0, 0.0, 0.0]), [0.0, 0.0, -1.0]);
+        assert_close(transform_point(&r, [1.0, 0.0, 0.0]), [0.0, 0.0, -1.0]);
+    }
+}